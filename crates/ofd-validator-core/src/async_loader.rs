@@ -0,0 +1,282 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tokio::task::JoinSet;
+
+use crate::orchestrator::DataSet;
+use crate::schema_cache::SchemaCache;
+use crate::util::{
+    file_name_on_disk, logo_asset_dir, logo_escape_error, logo_ref_escapes, normalize_dir, normalize_logo_ref,
+    parse_json,
+};
+use crate::validators;
+use crate::validators::LogoContext;
+
+/// A directory discovered during the walk: (path, depth below the root, file names it contains).
+type DirListing = (PathBuf, usize, HashSet<String>);
+
+/// List the subdirectories and file names of `dir`, or nothing if it can't be read.
+async fn read_dir_listing(dir: &Path) -> (Vec<PathBuf>, HashSet<String>) {
+    let mut subdirs = Vec::new();
+    let mut files = HashSet::new();
+
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(e) => e,
+        Err(_) => return (subdirs, files),
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        match tokio::fs::metadata(&path).await {
+            Ok(meta) if meta.is_dir() => subdirs.push(path),
+            Ok(_) => {
+                files.insert(entry.file_name().to_string_lossy().to_string());
+            }
+            Err(_) => {}
+        }
+    }
+
+    (subdirs, files)
+}
+
+/// Walk `root` depth-first, returning every directory in the order `read_dir` yields them.
+async fn walk_dirs(root: &Path) -> Vec<DirListing> {
+    let mut listings = Vec::new();
+    let mut stack = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        let (subdirs, files) = read_dir_listing(&dir).await;
+        // Push in reverse so children are visited in listing order
+        for subdir in subdirs.into_iter().rev() {
+            stack.push((subdir, depth + 1));
+        }
+        listings.push((dir, depth, files));
+    }
+
+    listings
+}
+
+/// Run `load` for every path concurrently, returning results in input order.
+async fn load_all<T, F, Fut>(paths: &[PathBuf], load: F) -> Vec<Option<T>>
+where
+    T: Send + 'static,
+    F: Fn(PathBuf) -> Fut,
+    Fut: Future<Output = Option<T>> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+    for (idx, path) in paths.iter().enumerate() {
+        let fut = load(path.clone());
+        tasks.spawn(async move { (idx, fut.await) });
+    }
+
+    let mut loaded: Vec<Option<T>> = (0..paths.len()).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((idx, value)) = joined {
+            loaded[idx] = value;
+        }
+    }
+    loaded
+}
+
+/// A JSON file's parsed value and source text.
+async fn load_json_async(path: PathBuf) -> Option<(Value, String)> {
+    let content = tokio::fs::read_to_string(&path).await.ok()?;
+    let value = parse_json(&content)?;
+    Some((value, content))
+}
+
+/// A logo's bytes and the name it is stored under (see `file_name_on_disk`).
+async fn read_logo_async(path: PathBuf) -> Option<(Vec<u8>, String)> {
+    let bytes = tokio::fs::read(&path).await.ok()?;
+    let name = tokio::task::spawn_blocking(move || file_name_on_disk(&path)).await.ok()?;
+    Some((bytes, name))
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl DataSet {
+    /// Build a DataSet by walking the filesystem with `tokio::fs`, loading files concurrently.
+    ///
+    /// Produces the same entries as `from_directories` and must be awaited inside a Tokio
+    /// runtime. Only loading is async — `validate_dataset` still runs synchronously on Rayon,
+    /// so call it via `spawn_blocking` when validating from an async context.
+    pub async fn from_directories_async(
+        data_dir: &Path,
+        stores_dir: &Path,
+        schemas_dir: &Path,
+    ) -> Self {
+        let data_dir = &normalize_dir(data_dir);
+        let stores_dir = &normalize_dir(stores_dir);
+        let schemas_dir = &normalize_dir(schemas_dir);
+
+        // Schemas and the file manifest are small synchronous walks; keep them off the async workers
+        let (schema_cache, file_manifest) = {
+            let data_dir = data_dir.to_path_buf();
+            let stores_dir = stores_dir.to_path_buf();
+            let schemas_dir = schemas_dir.to_path_buf();
+            tokio::task::spawn_blocking(move || {
+                (
+                    SchemaCache::from_directory(&schemas_dir),
+                    validators::missing_files::build_file_manifest(&data_dir, &stores_dir),
+                )
+            })
+            .await
+            .expect("schema/manifest loading task panicked")
+        };
+
+        // Discover which JSON files to load: (file path, schema name, owning directory)
+        let mut json_jobs: Vec<(PathBuf, &'static str, PathBuf)> = Vec::new();
+        // sizes.json files outside the expected variant depth (validated for gtin/store_id only)
+        let mut stray_sizes: Vec<PathBuf> = Vec::new();
+
+        for (dir, depth, files) in walk_dirs(data_dir).await {
+            let expected: &[(&str, &'static str)] = match depth {
+                1 => &[("brand.json", "brand")],
+                2 => &[("material.json", "material")],
+                3 => &[("filament.json", "filament")],
+                4 => &[("variant.json", "variant"), ("sizes.json", "sizes")],
+                _ => &[],
+            };
+            for (filename, schema_name) in expected {
+                if files.contains(*filename) {
+                    json_jobs.push((dir.join(filename), schema_name, dir.clone()));
+                }
+            }
+            if depth != 4 && files.contains("sizes.json") {
+                stray_sizes.push(dir.join("sizes.json"));
+            }
+        }
+
+        // The manifest already searched grouping folders for store directories
+        for store in file_manifest.stores.iter().filter(|s| s.has_store_json) {
+            let store_dir = PathBuf::from(&store.path);
+            json_jobs.push((store_dir.join("store.json"), "store", store_dir));
+        }
+
+        let job_paths: Vec<PathBuf> = json_jobs.iter().map(|(p, _, _)| p.clone()).collect();
+        let json_loaded = load_all(&job_paths, load_json_async).await;
+        let stray_loaded = load_all(&stray_sizes, load_json_async).await;
+
+        let mut json_entries = Vec::new();
+        let mut json_sources = HashMap::new();
+        let mut folder_entries = Vec::new();
+        let mut sizes_entries = Vec::new();
+        let mut valid_store_ids = HashSet::new();
+        let mut load_issues = Vec::new();
+        // (logo path, logo name from JSON, brand or store logo)
+        let mut logo_jobs: Vec<(PathBuf, String, LogoContext)> = Vec::new();
+
+        // A subfolder holding the brand logo (e.g. "assets/logo.png") is not a material
+        let asset_dirs: Vec<PathBuf> = json_jobs
+            .iter()
+            .zip(&json_loaded)
+            .filter(|((_, schema_name, _), _)| *schema_name == "brand")
+            .filter_map(|((_, _, dir), loaded)| Some(dir.join(logo_asset_dir(&loaded.as_ref()?.0)?)))
+            .collect();
+
+        for ((file, schema_name, dir), loaded) in json_jobs.into_iter().zip(json_loaded) {
+            let (data, source) = match loaded {
+                Some(l) => l,
+                None => continue,
+            };
+            let file_str = file.to_string_lossy().to_string();
+
+            if schema_name != "brand" && schema_name != "store" && asset_dirs.iter().any(|a| dir.starts_with(a)) {
+                // Like any sizes.json outside the hierarchy, still checked for gtin/store_id
+                if schema_name == "sizes" {
+                    sizes_entries.push((file_str, data));
+                }
+                continue;
+            }
+
+            json_sources.insert(file_str.clone(), source);
+            json_entries.push((file_str.clone(), schema_name.to_string(), data.clone()));
+
+            if schema_name == "sizes" {
+                sizes_entries.push((file_str, data));
+                continue;
+            }
+
+            if schema_name == "store" {
+                if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+                    valid_store_ids.insert(id.to_string());
+                }
+            }
+
+            let logo_context = match schema_name {
+                "brand" => Some(LogoContext::Brand),
+                "store" => Some(LogoContext::Store),
+                _ => None,
+            };
+            if let Some(context) = logo_context {
+                if let Some(logo_name) = data.get("logo").and_then(|v| v.as_str()) {
+                    let normalized = normalize_logo_ref(logo_name);
+                    // Never read a file outside the entity folder
+                    if logo_ref_escapes(&normalized) {
+                        load_issues.push(logo_escape_error(logo_name, Some(&dir.to_string_lossy())));
+                    } else {
+                        logo_jobs.push((dir.join(normalized), logo_name.to_string(), context));
+                    }
+                }
+            }
+
+            let json_key = if schema_name == "material" { "material" } else { "id" };
+            folder_entries.push((
+                dir.to_string_lossy().to_string(),
+                file_name_of(&dir),
+                data,
+                json_key.to_string(),
+            ));
+        }
+
+        for (path, loaded) in stray_sizes.iter().zip(stray_loaded) {
+            if let Some((data, _)) = loaded {
+                sizes_entries.push((path.to_string_lossy().to_string(), data));
+            }
+        }
+
+        let logo_paths: Vec<PathBuf> = logo_jobs.iter().map(|(p, _, _)| p.clone()).collect();
+        let logo_loaded = load_all(&logo_paths, read_logo_async).await;
+
+        let logo_entries = logo_jobs
+            .into_iter()
+            .zip(logo_loaded)
+            .map(|((logo_path, logo_name, context), loaded)| match loaded {
+                Some((bytes, filename)) => (
+                    logo_path.to_string_lossy().to_string(),
+                    filename,
+                    bytes,
+                    Some(logo_name),
+                    context,
+                ),
+                // Empty bytes so the validator can report "file not found"
+                None => (
+                    logo_path.to_string_lossy().to_string(),
+                    logo_name.clone(),
+                    Vec::new(),
+                    Some(logo_name),
+                    context,
+                ),
+            })
+            .collect();
+
+        DataSet {
+            json_entries,
+            json_sources,
+            logo_entries,
+            folder_entries,
+            sizes_entries,
+            valid_store_ids,
+            file_manifest,
+            schema_cache,
+            load_issues,
+            changed_only: false,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Baselines of known issues, for adopting the validator on a dataset that already has
+//! many: record the current issues once, then report only the ones that aren't in it.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ValidationError, ValidationResult};
+
+/// An issue as recorded in a baseline. Issues match on category, path and message; the
+/// level is left out so promoting a warning to an error doesn't resurface it.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineIssue {
+    pub category: String,
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl From<&ValidationError> for BaselineIssue {
+    fn from(e: &ValidationError) -> Self {
+        Self {
+            category: e.category.clone(),
+            path: e.path.clone(),
+            message: e.message.clone(),
+        }
+    }
+}
+
+/// A set of known issues, stored as JSON sorted by category, path and message so that
+/// regenerating it gives a reviewable diff.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub issues: BTreeSet<BaselineIssue>,
+}
+
+impl Baseline {
+    /// Every issue in `result`, duplicates collapsed.
+    pub fn from_result(result: &ValidationResult) -> Self {
+        Self {
+            issues: result.errors.iter().map(BaselineIssue::from).collect(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing the baseline to JSON failed")
+    }
+
+    pub fn contains(&self, error: &ValidationError) -> bool {
+        self.issues.contains(&BaselineIssue::from(error))
+    }
+
+    /// Drop the issues of `result` that are in the baseline, leaving only new ones.
+    /// Returns how many were dropped.
+    pub fn suppress(&self, result: &mut ValidationResult) -> usize {
+        let before = result.errors.len();
+        result.errors.retain(|e| !self.contains(e));
+        before - result.errors.len()
+    }
+}
@@ -0,0 +1,168 @@
+//! The catalog a `DataSet` describes: brands with their materials, filaments, variants
+//! and sizes, plus the stores. Built from the file manifest and the parsed JSON, so it
+//! reflects the directory walk even where files failed validation.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::orchestrator::DataSet;
+use crate::validators::missing_files::{
+    BrandEntry, FilamentEntry, MaterialEntry, StoreEntry, VariantEntry,
+};
+
+#[derive(Serialize)]
+pub struct Catalog {
+    pub brands: Vec<CatalogBrand>,
+    pub stores: Vec<CatalogStore>,
+}
+
+/// `id` and `name` come from the entity's JSON file and are `None` when the file or
+/// the field is missing.
+#[derive(Serialize)]
+pub struct CatalogBrand {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub path: String,
+    pub materials: Vec<CatalogMaterial>,
+}
+
+/// Materials are identified by their `material` field rather than an `id`.
+#[derive(Serialize)]
+pub struct CatalogMaterial {
+    pub material: Option<String>,
+    pub name: Option<String>,
+    pub path: String,
+    pub filaments: Vec<CatalogFilament>,
+}
+
+#[derive(Serialize)]
+pub struct CatalogFilament {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub path: String,
+    pub variants: Vec<CatalogVariant>,
+}
+
+#[derive(Serialize)]
+pub struct CatalogVariant {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub path: String,
+    /// The entries of sizes.json as written (empty if it's missing or not an array)
+    pub sizes: Vec<Value>,
+}
+
+#[derive(Serialize)]
+pub struct CatalogStore {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub path: String,
+}
+
+impl Catalog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing the catalog to JSON failed")
+    }
+}
+
+/// The directory part of a path label, accepting both separators.
+fn parent(path: &str) -> &str {
+    path.rsplit_once(['/', '\\']).map_or("", |(dir, _)| dir)
+}
+
+fn field(data: Option<&Value>, key: &str) -> Option<String> {
+    data.and_then(|d| d.get(key)).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Parsed JSON of the entity files, keyed by the directory holding them.
+struct Index<'a> {
+    /// (directory, schema_name) -> parsed JSON
+    entities: HashMap<(&'a str, &'a str), &'a Value>,
+    /// Includes sizes inlined in variant.json, which only appear in `sizes_entries`
+    sizes: HashMap<&'a str, &'a Value>,
+}
+
+impl<'a> Index<'a> {
+    fn new(dataset: &'a DataSet) -> Self {
+        Self {
+            entities: dataset
+                .json_entries
+                .iter()
+                .map(|(path, schema, data)| ((parent(path), schema.as_str()), data))
+                .collect(),
+            sizes: dataset.sizes_entries.iter().map(|(path, data)| (parent(path), data)).collect(),
+        }
+    }
+
+    fn get(&self, dir: &str, schema: &str) -> Option<&'a Value> {
+        self.entities.get(&(dir, schema)).copied()
+    }
+
+    fn brand(&self, brand: &BrandEntry) -> CatalogBrand {
+        let data = self.get(&brand.path, "brand");
+        CatalogBrand {
+            id: field(data, "id"),
+            name: field(data, "name"),
+            path: brand.path.clone(),
+            materials: brand.materials.iter().map(|m| self.material(m)).collect(),
+        }
+    }
+
+    fn material(&self, material: &MaterialEntry) -> CatalogMaterial {
+        let data = self.get(&material.path, "material");
+        CatalogMaterial {
+            material: field(data, "material"),
+            name: field(data, "name"),
+            path: material.path.clone(),
+            filaments: material.filaments.iter().map(|f| self.filament(f)).collect(),
+        }
+    }
+
+    fn filament(&self, filament: &FilamentEntry) -> CatalogFilament {
+        let data = self.get(&filament.path, "filament");
+        CatalogFilament {
+            id: field(data, "id"),
+            name: field(data, "name"),
+            path: filament.path.clone(),
+            variants: filament.variants.iter().map(|v| self.variant(v)).collect(),
+        }
+    }
+
+    fn variant(&self, variant: &VariantEntry) -> CatalogVariant {
+        let data = self.get(&variant.path, "variant");
+        CatalogVariant {
+            id: field(data, "id"),
+            name: field(data, "name"),
+            path: variant.path.clone(),
+            sizes: self
+                .sizes
+                .get(variant.path.as_str())
+                .and_then(|s| s.as_array())
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn store(&self, store: &StoreEntry) -> CatalogStore {
+        let data = self.get(&store.path, "store");
+        CatalogStore {
+            id: field(data, "id"),
+            name: field(data, "name"),
+            path: store.path.clone(),
+        }
+    }
+}
+
+impl DataSet {
+    /// The brand/material/filament/variant/sizes hierarchy and stores found while
+    /// loading, in directory-walk order.
+    pub fn catalog(&self) -> Catalog {
+        let index = Index::new(self);
+        Catalog {
+            brands: self.file_manifest.brands.iter().map(|b| index.brand(b)).collect(),
+            stores: self.file_manifest.stores.iter().map(|s| index.store(s)).collect(),
+        }
+    }
+}
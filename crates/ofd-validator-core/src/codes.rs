@@ -0,0 +1,99 @@
+//! The categories (`ValidationError::category`) the validator emits. Emission sites use
+//! these constants, so `ALL` lists exactly what can be produced.
+
+pub const BRAND: &str = "Brand";
+pub const COLOR: &str = "Color";
+pub const CONTENT: &str = "Content";
+pub const EAN: &str = "EAN";
+pub const FOLDER: &str = "Folder";
+pub const FORMAT: &str = "Format";
+pub const GTIN: &str = "GTIN";
+pub const GTIN_EAN: &str = "GTIN/EAN";
+pub const ID: &str = "ID";
+pub const IO: &str = "IO";
+pub const JSON: &str = "JSON";
+pub const LIMITS: &str = "Limits";
+pub const LOGO: &str = "Logo";
+pub const MATERIAL: &str = "Material";
+pub const MISSING_FILE: &str = "Missing File";
+pub const PATH: &str = "Path";
+pub const PURCHASE_LINK: &str = "PurchaseLink";
+pub const SCHEMA: &str = "Schema";
+pub const SCOPE: &str = "Scope";
+pub const SIZES: &str = "Sizes";
+pub const STORE: &str = "Store";
+pub const STORE_ID: &str = "StoreID";
+pub const STRUCTURE: &str = "Structure";
+pub const VERSION: &str = "Version";
+pub const WHITESPACE: &str = "Whitespace";
+
+/// Every code with a one-line description, sorted by code.
+pub const ALL: &[(&str, &str)] = &[
+    (BRAND, "Two brands showing the same display `name` under different folders"),
+    (COLOR, "Variant `color_name` spelled differently from other variants with the same color"),
+    (CONTENT, "String value longer than the limit configured for its key, or a broken opt-in conditional rule"),
+    (EAN, "Invalid, disallowed or unquoted (numeric) barcode in a sizes.json `ean` field"),
+    (FOLDER, "Folder name doesn't match the ID in its JSON file, or the ID is missing"),
+    (FORMAT, "Number stored as a string, or a string field holding a bare number"),
+    (GTIN, "Invalid, disallowed or unquoted (numeric) `gtin`, one used by two brands, or an opt-in missing barcode"),
+    (GTIN_EAN, "A size's `gtin` and `ean` encode different products"),
+    (ID, "`id` that doesn't make a clean folder name or match the configured pattern"),
+    (IO, "File or directory that still couldn't be read after retrying"),
+    (JSON, "Data file failing its schema, whose schema is unavailable, or needing JSON5"),
+    (LIMITS, "In-memory input over the configured file count, total size or logo size"),
+    (LOGO, "Logo missing, misnamed, wrongly sized, undecodable, animated or failing an opt-in check"),
+    (MATERIAL, "`material` not spelled as in `material_types`"),
+    (MISSING_FILE, "Required brand/material/filament/variant/sizes JSON file missing"),
+    (PATH, "Folder name or folder path longer than the configured limit"),
+    (PURCHASE_LINK, "Purchase link missing required keys, listed twice, or too many per size"),
+    (SCHEMA, "Schema missing, unreadable or not compiling, schemas sharing an `$id`, mixed `$schema` drafts, or an ambiguous `$ref`"),
+    (SCOPE, "A changed-files run whose changes don't touch the dataset, so nothing was validated"),
+    (SIZES, "Size marked available without purchase links or with an implausible weight, or mixed diameters"),
+    (STORE, "Store metadata problem, e.g. a country that isn't ISO 3166-1 alpha-2"),
+    (STORE_ID, "Purchase link with an unknown or missing `store_id`, or an unlisted brand"),
+    (STRUCTURE, "Unexpected directory layout, e.g. directories below the variant level"),
+    (VERSION, "sizes.json version marker missing or not matching the configured pattern"),
+    (WHITESPACE, "Leading/trailing whitespace or tabs in a string value"),
+];
+
+/// The description of `code`, or `None` if the validator never emits it.
+pub fn describe(code: &str) -> Option<&'static str> {
+    ALL.iter().find(|(c, _)| *c == code).map(|(_, description)| *description)
+}
+
+/// The stable identifier of `code`, spelled like its constant here (e.g. "MISSING_FILE"
+/// for "Missing File", "PURCHASE_LINK" for "PurchaseLink"), or `None` if the validator
+/// never emits it, e.g. for categories added by custom validators.
+pub fn identifier(code: &str) -> Option<String> {
+    describe(code)?;
+    let mut id = String::new();
+    let mut prev: Option<char> = None;
+    for c in code.chars() {
+        if !c.is_ascii_alphanumeric() {
+            id.push('_');
+        } else {
+            // A new word starts at an uppercase letter following a lowercase one
+            if c.is_ascii_uppercase() && prev.is_some_and(|p| p.is_ascii_lowercase()) {
+                id.push('_');
+            }
+            id.push(c.to_ascii_uppercase());
+        }
+        prev = Some(c);
+    }
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers_match_the_constant_names() {
+        assert_eq!(identifier(MISSING_FILE).as_deref(), Some("MISSING_FILE"));
+        assert_eq!(identifier(PURCHASE_LINK).as_deref(), Some("PURCHASE_LINK"));
+        assert_eq!(identifier(STORE_ID).as_deref(), Some("STORE_ID"));
+        assert_eq!(identifier(GTIN_EAN).as_deref(), Some("GTIN_EAN"));
+        assert_eq!(identifier(LOGO).as_deref(), Some("LOGO"));
+        assert_eq!(identifier("Custom"), None);
+    }
+}
@@ -0,0 +1,54 @@
+//! Changed-file discovery through the `git` command line, for validating only what a
+//! branch or pull request touched.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Files that differ between `since` (any git ref, e.g. "origin/main") and the working
+/// tree of the repository containing `dir`, as absolute paths.
+///
+/// Deleted files are left out, since there is nothing left to validate, and a renamed
+/// file is listed under its new path. Fails if `git` can't be run, `dir` isn't inside a
+/// repository or `since` doesn't name a commit.
+pub fn changed_files(dir: &Path, since: &str) -> io::Result<Vec<PathBuf>> {
+    let toplevel = git(dir, &["rev-parse", "--show-toplevel"])?;
+    let toplevel = PathBuf::from(String::from_utf8_lossy(&toplevel).trim());
+
+    // With -z each entry is "<status>\0<path>\0", or "<status>\0<old>\0<new>\0" for
+    // renames and copies
+    let output = git(&toplevel, &["diff", "--name-status", "-z", "-M", since, "--"])?;
+    let mut fields = output.split(|&b| b == 0).filter(|f| !f.is_empty());
+    let mut changed = Vec::new();
+
+    while let Some(status) = fields.next() {
+        let path = match status.first() {
+            Some(b'R') | Some(b'C') => {
+                fields.next();
+                fields.next()
+            }
+            Some(b'D') => {
+                fields.next();
+                continue;
+            }
+            _ => fields.next(),
+        };
+        if let Some(path) = path {
+            changed.push(toplevel.join(String::from_utf8_lossy(path).as_ref()));
+        }
+    }
+
+    Ok(changed)
+}
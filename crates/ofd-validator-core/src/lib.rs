@@ -1,9 +1,33 @@
+#[cfg(feature = "async")]
+mod async_loader;
+pub mod baseline;
+pub mod catalog;
+pub mod codes;
+#[cfg(feature = "filesystem")]
+pub mod git;
+pub mod limits;
+mod memory;
+pub mod options;
 pub mod orchestrator;
+pub mod pipeline;
+#[cfg(feature = "filesystem")]
+pub mod producer;
+pub mod report;
 pub mod schema_cache;
 pub mod types;
 pub mod util;
 pub mod validators;
 
-pub use orchestrator::{validate_dataset, DataSet};
-pub use schema_cache::SchemaCache;
-pub use types::{ValidationError, ValidationLevel, ValidationResult};
+pub use limits::ContentLimits;
+pub use options::{GtinOptions, JsonOptions, LogoConstraints, LogoOptions, PostHooks, ValidationOptions};
+pub use orchestrator::{
+    validate_dataset, validate_dataset_iter, validate_dataset_with_options, validate_dataset_with_validators,
+    DataSet,
+};
+#[cfg(feature = "filesystem")]
+pub use orchestrator::{validate_all_with_dataset, validate_task};
+#[cfg(feature = "filesystem")]
+pub use producer::{LayoutStrategy, RetryPolicy, Task, TaskProducer};
+pub use pipeline::{builtin_validators, validate_json_schemas, Validator};
+pub use schema_cache::{RefResolver, SchemaCache};
+pub use types::{Summary, ValidationError, ValidationLevel, ValidationResult};
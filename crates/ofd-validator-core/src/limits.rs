@@ -0,0 +1,87 @@
+//! Size limits for datasets handed over in memory (zip archives, content-mode input),
+//! so an oversized upload is rejected before anything is parsed or decoded.
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Limits on in-memory input; `None` means unlimited, which is the default.
+#[derive(Clone, Debug, Default)]
+pub struct ContentLimits {
+    /// Combined size of all files, in bytes
+    pub max_total_bytes: Option<u64>,
+    pub max_files: Option<usize>,
+    /// Size of any single logo, in bytes
+    pub max_logo_bytes: Option<u64>,
+}
+
+impl ContentLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_total_bytes.is_none() && self.max_files.is_none() && self.max_logo_bytes.is_none()
+    }
+
+    /// Check input files given as (path, size in bytes, is_logo). Each exceeded limit
+    /// gives one `codes::LIMITS` error, plus one per oversized logo; an empty result
+    /// means the input may be validated.
+    pub fn check<'a, I>(&self, files: I) -> ValidationResult
+    where
+        I: IntoIterator<Item = (&'a str, u64, bool)>,
+    {
+        let mut result = ValidationResult::default();
+        let mut total_bytes: u64 = 0;
+        let mut file_count: usize = 0;
+
+        for (path, size, is_logo) in files {
+            total_bytes = total_bytes.saturating_add(size);
+            file_count += 1;
+            if let Some(max) = self.max_logo_bytes.filter(|&max| is_logo && size > max) {
+                result.add(ValidationError::error(
+                    codes::LIMITS,
+                    format!("Logo is {} bytes, over the limit of {} bytes", size, max),
+                    Some(path.to_string()),
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_files.filter(|&max| file_count > max) {
+            result.add(ValidationError::error(
+                codes::LIMITS,
+                format!("Input holds {} files, over the limit of {}", file_count, max),
+                None,
+            ));
+        }
+        if let Some(max) = self.max_total_bytes.filter(|&max| total_bytes > max) {
+            result.add(ValidationError::error(
+                codes::LIMITS,
+                format!("Input totals {} bytes, over the limit of {} bytes", total_bytes, max),
+                None,
+            ));
+        }
+
+        result
+    }
+
+    /// Check a zip archive using the sizes declared in its central directory, without
+    /// decompressing anything. `DataSet::from_zip` never reads past the declared size
+    /// of an entry, so an archive lying about its sizes can't get around the limits.
+    /// Without limits, `from_zip` still rejects archives over `util::ZIP_MAX_ENTRY_BYTES`
+    /// per entry or `util::ZIP_MAX_TOTAL_BYTES` in total.
+    /// Entries other than JSON files count as logos.
+    #[cfg(feature = "zip")]
+    pub fn check_zip(&self, archive: &[u8]) -> Result<ValidationResult, zip::result::ZipError> {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))?;
+        let mut files = Vec::new();
+        for idx in 0..zip.len() {
+            let entry = zip.by_index_raw(idx)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let path = path.to_string_lossy().replace('\\', "/");
+            let is_logo = !(path.ends_with(".json") || path.ends_with(".json5"));
+            files.push((path, entry.size(), is_logo));
+        }
+        Ok(self.check(files.iter().map(|(path, size, is_logo)| (path.as_str(), *size, *is_logo))))
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::orchestrator::DataSet;
+use crate::schema_cache::{SchemaCache, SCHEMA_FILES};
+use crate::util::{
+    find_store_dirs, json5_warning, logo_asset_dir, logo_escape_error, logo_ref_escapes, normalize_logo_ref,
+    parse_json_relaxed,
+};
+use crate::validators::LogoContext;
+use crate::validators::missing_files::{
+    BrandEntry, FileManifest, FilamentEntry, MaterialEntry, StoreEntry, VariantEntry,
+};
+
+/// Directory layout of an in-memory file tree, keyed by '/'-separated paths.
+struct Tree<'a> {
+    files: &'a BTreeMap<String, Vec<u8>>,
+    /// Directory -> its direct subdirectories, sorted
+    children: HashMap<String, BTreeSet<String>>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(files: &'a BTreeMap<String, Vec<u8>>) -> Self {
+        let mut children: HashMap<String, BTreeSet<String>> = HashMap::new();
+        for path in files.keys() {
+            let mut parent = String::new();
+            let segments: Vec<&str> = path.split('/').collect();
+            // Every segment but the last (the file name) is a directory
+            for segment in &segments[..segments.len() - 1] {
+                let dir = join(&parent, segment);
+                children.entry(parent).or_default().insert(dir.clone());
+                parent = dir;
+            }
+        }
+        Self { files, children }
+    }
+
+    fn subdirs(&self, dir: &str) -> Vec<&str> {
+        self.children
+            .get(dir)
+            .map(|dirs| dirs.iter().map(|d| d.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    fn has_file(&self, dir: &str, name: &str) -> bool {
+        self.files.contains_key(&join(dir, name))
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Strip a single wrapping folder (e.g. `dataset/` in `dataset/data/...`) when the tree
+/// has no `data/` or `stores/` at its root, as happens with zips of a whole directory.
+fn strip_wrapper(files: BTreeMap<String, Vec<u8>>) -> BTreeMap<String, Vec<u8>> {
+    let at_root = |prefix: &str| files.keys().any(|p| p.starts_with(prefix));
+    if at_root("data/") || at_root("stores/") {
+        return files;
+    }
+    let roots: HashSet<&str> = files.keys().filter_map(|p| p.split_once('/').map(|(r, _)| r)).collect();
+    if roots.len() != 1 {
+        return files;
+    }
+    files
+        .into_iter()
+        .filter_map(|(path, bytes)| path.split_once('/').map(|(_, rest)| (rest.to_string(), bytes)))
+        .collect()
+}
+
+impl DataSet {
+    /// Build a DataSet from files held in memory, keyed by '/'-separated paths that follow
+    /// the repository layout: `data/<brand>/<material>/<filament>/<variant>/...`,
+    /// `stores/<store>/...` and `schemas/<name>_schema.json`.
+    ///
+    /// A single wrapping folder around those (e.g. `dataset/data/...`) is ignored. Path
+    /// labels in the results are the keys as given, minus that folder. Produces the same
+    /// entries `from_directories` would for the same tree.
+    pub fn from_memory(files: BTreeMap<String, Vec<u8>>) -> Self {
+        let files = strip_wrapper(
+            files
+                .into_iter()
+                .map(|(path, bytes)| (path.trim_start_matches("./").to_string(), bytes))
+                .filter(|(path, _)| !path.is_empty() && !path.ends_with('/'))
+                .collect(),
+        );
+        let tree = Tree::new(&files);
+
+        let schemas: HashMap<String, Value> = SCHEMA_FILES
+            .iter()
+            .filter_map(|(name, filename)| {
+                let bytes = files.get(&join("schemas", filename))?;
+                let value = crate::util::parse_schema_json(&String::from_utf8_lossy(bytes))?;
+                Some((name.to_string(), value))
+            })
+            .collect();
+
+        let mut dataset = DataSet {
+            json_entries: Vec::new(),
+            json_sources: HashMap::new(),
+            logo_entries: Vec::new(),
+            folder_entries: Vec::new(),
+            sizes_entries: Vec::new(),
+            valid_store_ids: HashSet::new(),
+            file_manifest: FileManifest {
+                brands: Vec::new(),
+                stores: Vec::new(),
+                orphan_sizes_dirs: Vec::new(),
+            },
+            schema_cache: SchemaCache::from_map(schemas),
+            load_issues: Vec::new(),
+            changed_only: false,
+        };
+        let mut seen_sizes: HashSet<String> = HashSet::new();
+
+        for brand_dir in tree.subdirs("data") {
+            let brand_data = dataset.add_entity(&tree, brand_dir, "brand.json", "brand", "id", Some(LogoContext::Brand));
+            // A subfolder holding the brand logo (e.g. "assets/logo.png") is not a material
+            let asset_dir = brand_data.as_ref().and_then(logo_asset_dir).map(|dir| join(brand_dir, dir));
+
+            let mut materials = Vec::new();
+            for material_dir in tree.subdirs(brand_dir) {
+                if asset_dir.as_deref() == Some(material_dir) {
+                    continue;
+                }
+                dataset.add_entity(&tree, material_dir, "material.json", "material", "material", None);
+
+                let mut filaments = Vec::new();
+                for filament_dir in tree.subdirs(material_dir) {
+                    dataset.add_entity(&tree, filament_dir, "filament.json", "filament", "id", None);
+
+                    let mut variants = Vec::new();
+                    for variant_dir in tree.subdirs(filament_dir) {
+                        dataset.add_entity(&tree, variant_dir, "variant.json", "variant", "id", None);
+
+                        let sizes_path = join(variant_dir, "sizes.json");
+                        if let Some((data, source)) = dataset.parse_file(&files, &sizes_path) {
+                            seen_sizes.insert(sizes_path.clone());
+                            dataset.json_sources.insert(sizes_path.clone(), source);
+                            dataset.json_entries.push((sizes_path.clone(), "sizes".to_string(), data.clone()));
+                            dataset.sizes_entries.push((sizes_path, data));
+                        }
+
+                        variants.push(VariantEntry {
+                            path: variant_dir.to_string(),
+                            has_variant_json: tree.has_file(variant_dir, "variant.json"),
+                            has_sizes_json: tree.has_file(variant_dir, "sizes.json"),
+                            nested_dirs: tree.subdirs(variant_dir).into_iter().map(String::from).collect(),
+                        });
+                    }
+
+                    filaments.push(FilamentEntry {
+                        path: filament_dir.to_string(),
+                        has_filament_json: tree.has_file(filament_dir, "filament.json"),
+                        variants,
+                    });
+                }
+
+                materials.push(MaterialEntry {
+                    path: material_dir.to_string(),
+                    has_material_json: tree.has_file(material_dir, "material.json"),
+                    filaments,
+                });
+            }
+
+            dataset.file_manifest.brands.push(BrandEntry {
+                path: brand_dir.to_string(),
+                has_brand_json: tree.has_file(brand_dir, "brand.json"),
+                materials,
+            });
+        }
+
+        let store_dirs = find_store_dirs(&"stores", |dir| tree.subdirs(dir), |dir| tree.has_file(dir, "store.json"));
+        for store_dir in store_dirs {
+            dataset.add_entity(&tree, store_dir, "store.json", "store", "id", Some(LogoContext::Store));
+            dataset.file_manifest.stores.push(StoreEntry {
+                path: store_dir.to_string(),
+                has_store_json: tree.has_file(store_dir, "store.json"),
+            });
+        }
+
+        // sizes.json outside the variant level still feeds the gtin/store_id checks
+        for path in files.keys().filter(|p| p.starts_with("data/") && p.ends_with("/sizes.json")) {
+            let dir = &path[..path.len() - "/sizes.json".len()];
+            if dir.split('/').count() != 5 && !tree.has_file(dir, "variant.json") {
+                dataset.file_manifest.orphan_sizes_dirs.push(dir.to_string());
+            }
+            if !seen_sizes.contains(path) {
+                if let Some((data, _)) = dataset.parse_file(&files, path) {
+                    dataset.sizes_entries.push((path.clone(), data));
+                }
+            }
+        }
+
+        dataset
+    }
+
+    /// Parse one JSON file, recording a load issue when it needed JSON5. Returns the
+    /// parsed value and the source text.
+    fn parse_file(&mut self, files: &BTreeMap<String, Vec<u8>>, path: &str) -> Option<(Value, String)> {
+        let bytes = files.get(path)?;
+        let source = String::from_utf8_lossy(bytes).into_owned();
+        let (value, used_json5) = parse_json_relaxed(&source, std::path::Path::new(path))?;
+        if used_json5 {
+            self.load_issues.push(json5_warning(path));
+        }
+        Some((value, source))
+    }
+
+    /// Add the JSON, logo and folder entries for one entity directory, returning its data.
+    fn add_entity(
+        &mut self,
+        tree: &Tree,
+        dir: &str,
+        json_file: &str,
+        schema_name: &str,
+        json_key: &str,
+        logo_context: Option<LogoContext>,
+    ) -> Option<Value> {
+        let (data, source) = self.parse_file(tree.files, &join(dir, json_file))?;
+
+        self.json_sources.insert(join(dir, json_file), source);
+        self.json_entries.push((join(dir, json_file), schema_name.to_string(), data.clone()));
+
+        if schema_name == "store" {
+            if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+                self.valid_store_ids.insert(id.to_string());
+            }
+        }
+
+        if let Some(context) = logo_context {
+            if let Some(logo_name) = data.get("logo").and_then(|v| v.as_str()) {
+                let normalized = normalize_logo_ref(logo_name);
+                let logo_path = join(dir, &normalized);
+                match tree.files.get(&logo_path) {
+                    // Never read a file outside the entity folder
+                    _ if logo_ref_escapes(&normalized) => self.load_issues.push(logo_escape_error(logo_name, Some(dir))),
+                    Some(bytes) => self.logo_entries.push((
+                        logo_path.clone(),
+                        last_segment(&logo_path).to_string(),
+                        bytes.clone(),
+                        Some(logo_name.to_string()),
+                        context,
+                    )),
+                    // Empty bytes so the validator can report "file not found"
+                    None => self.logo_entries.push((
+                        logo_path,
+                        logo_name.to_string(),
+                        Vec::new(),
+                        Some(logo_name.to_string()),
+                        context,
+                    )),
+                }
+            }
+        }
+
+        self.folder_entries.push((
+            dir.to_string(),
+            last_segment(dir).to_string(),
+            data.clone(),
+            json_key.to_string(),
+        ));
+
+        Some(data)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl DataSet {
+    /// Build a DataSet from a zip archive held in memory, without extracting it to disk.
+    /// Entries are mapped onto the dataset layout as described for `from_memory`.
+    ///
+    /// An archive declaring an entry over `util::ZIP_MAX_ENTRY_BYTES`, or entries adding
+    /// up to more than `util::ZIP_MAX_TOTAL_BYTES`, is rejected before it is read.
+    pub fn from_zip(archive: &[u8]) -> Result<Self, zip::result::ZipError> {
+        use std::io::Read;
+        use zip::result::ZipError;
+
+        use crate::util::{ZIP_MAX_ENTRY_BYTES, ZIP_MAX_TOTAL_BYTES};
+
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive))?;
+        let mut total: u64 = 0;
+        for idx in 0..zip.len() {
+            let size = zip.by_index_raw(idx)?.size();
+            if size > ZIP_MAX_ENTRY_BYTES {
+                return Err(ZipError::UnsupportedArchive("entry larger than the per-entry size cap"));
+            }
+            total = total.saturating_add(size);
+            if total > ZIP_MAX_TOTAL_BYTES {
+                return Err(ZipError::UnsupportedArchive("entries larger than the total size cap"));
+            }
+        }
+
+        let mut files = BTreeMap::new();
+        for idx in 0..zip.len() {
+            let mut entry = zip.by_index(idx)?;
+            if !entry.is_file() {
+                continue;
+            }
+            // Skip entries whose names would escape the archive root
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let path = path.to_string_lossy().replace('\\', "/");
+            // Stop at the declared size, which the caps above and `ContentLimits::check_zip`
+            // rely on; the declared size isn't trusted for preallocation
+            let size = entry.size();
+            let mut bytes = Vec::new();
+            (&mut entry).take(size).read_to_end(&mut bytes)?;
+            files.insert(path, bytes);
+        }
+        Ok(Self::from_memory(files))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::validate_dataset;
+
+    #[test]
+    fn backslash_logo_reference_resolves_with_guidance() {
+        let files: BTreeMap<String, Vec<u8>> = [
+            ("data/acme/brand.json", br#"{"id": "acme", "name": "Acme", "logo": "assets\\logo.svg"}"#.to_vec()),
+            ("data/acme/assets/logo.svg", br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#.to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| (path.to_string(), bytes))
+        .collect();
+        let dataset = DataSet::from_memory(files);
+
+        let (path, filename, bytes, logo_name, _) = &dataset.logo_entries[0];
+        assert_eq!(path, "data/acme/assets/logo.svg");
+        assert_eq!(filename, "logo.svg");
+        assert!(!bytes.is_empty());
+        assert_eq!(logo_name.as_deref(), Some(r"assets\logo.svg"));
+        // The logo's folder isn't mistaken for a material
+        assert!(dataset.file_manifest.brands[0].materials.is_empty());
+
+        let logo_errors: Vec<String> = validate_dataset(&dataset)
+            .errors
+            .into_iter()
+            .filter(|e| e.category == crate::codes::LOGO)
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            logo_errors,
+            [r"Logo path 'assets\logo.svg' uses backslashes; use forward slashes (e.g. 'assets/logo.svg') so it resolves on every OS"]
+        );
+    }
+
+    #[test]
+    fn logo_reference_outside_the_entity_folder_is_not_read() {
+        let files: BTreeMap<String, Vec<u8>> = [
+            ("data/acme/brand.json", br#"{"id": "acme", "name": "Acme", "logo": "../../stores/shop/logo.svg"}"#.to_vec()),
+            ("stores/shop/store.json", br#"{"id": "shop", "name": "Shop", "logo": "/etc/passwd"}"#.to_vec()),
+            ("stores/shop/logo.svg", br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#.to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| (path.to_string(), bytes))
+        .collect();
+        let dataset = DataSet::from_memory(files);
+
+        assert!(dataset.logo_entries.is_empty());
+        let issues: Vec<(&str, &str)> = dataset
+            .load_issues
+            .iter()
+            .map(|e| (e.path.as_deref().unwrap(), e.message.as_str()))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                ("data/acme", "Logo path '../../stores/shop/logo.svg' must be a relative path inside the entity folder (e.g. 'logo.png' or 'assets/logo.png')"),
+                ("stores/shop", "Logo path '/etc/passwd' must be a relative path inside the entity folder (e.g. 'logo.png' or 'assets/logo.png')"),
+            ]
+        );
+    }
+
+    /// A zip holding `files` stored uncompressed, with every entry's declared uncompressed
+    /// size overwritten by `declared` when given.
+    #[cfg(feature = "zip")]
+    fn zip_archive(files: &[(&str, &[u8])], declared: Option<u32>) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (path, bytes) in files {
+            writer.start_file(*path, stored).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        let mut archive = writer.finish().unwrap().into_inner();
+        if let Some(size) = declared {
+            // Uncompressed size sits 22 bytes into a local header, 24 into a central one
+            for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+                let starts: Vec<usize> =
+                    archive.windows(4).enumerate().filter(|(_, w)| w == signature).map(|(i, _)| i).collect();
+                for start in starts {
+                    archive[start + offset..start + offset + 4].copy_from_slice(&size.to_le_bytes());
+                }
+            }
+        }
+        archive
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zip_declaring_a_huge_entry_is_rejected_without_limits() {
+        let archive = zip_archive(&[("data/acme/brand.json", br#"{"id": "acme"}"#)], Some(u32::MAX));
+
+        assert!(crate::ContentLimits::default().is_unlimited());
+        let err = DataSet::from_zip(&archive).err().expect("archive over the size cap");
+        assert!(err.to_string().contains("per-entry size cap"), "{err}");
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zip_declaring_more_than_it_holds_loads_what_is_there() {
+        let brand = br#"{"id": "acme", "name": "Acme"}"#;
+        let archive = zip_archive(&[("data/acme/brand.json", brand)], Some(1024 * 1024));
+
+        let dataset = DataSet::from_zip(&archive).unwrap();
+        let (path, schema, data) = &dataset.json_entries[0];
+        assert_eq!((path.as_str(), schema.as_str()), ("data/acme/brand.json", "brand"));
+        assert_eq!(data["id"], "acme");
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zip_limits_use_the_declared_sizes() {
+        let archive = zip_archive(&[("data/acme/logo.png", b"tiny")], Some(1024 * 1024));
+        let limits = crate::ContentLimits { max_logo_bytes: Some(1024), ..Default::default() };
+
+        let exceeded = limits.check_zip(&archive).unwrap();
+        assert_eq!(exceeded.errors.len(), 1);
+        assert_eq!(exceeded.errors[0].category, crate::codes::LIMITS);
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+
+use crate::types::{ValidationLevel, ValidationResult};
+use crate::util::{
+    DEFAULT_FIELD_LENGTH_LIMITS, DEFAULT_ID_PATTERN, DEFAULT_LOGO_EXTENSIONS, DEFAULT_MAX_LOGO_PIXELS, DEFAULT_MAX_MESSAGE_LEN, DEFAULT_NUMBER_FIELDS,
+    DEFAULT_PATH_LENGTH_LIMITS, DEFAULT_WEIGHT_RANGE, LOGO_MAX_SIZE, LOGO_MIN_SIZE,
+};
+use crate::validators::{ConditionalRule, GtinFormat, LogoContext};
+
+/// Tunables for policy-dependent checks run by `validate_dataset_with_options`.
+///
+/// `Default` matches the behaviour of `validate_dataset`.
+#[derive(Clone, Debug)]
+pub struct ValidationOptions {
+    /// Severity for purchase links that have no `store_id` (some links point
+    /// straight at a manufacturer page and legitimately omit it).
+    pub missing_store_id_level: ValidationLevel,
+    /// Purchase-link key that names a store by a path relative to the sizes.json folder,
+    /// as an alternative to `store_id`. Paths that don't lead to a store directory are
+    /// "StoreID" errors. `None` disables the check and the alternative.
+    pub store_ref_field: Option<String>,
+    /// Severity for material directories without filaments and filament directories
+    /// without variants ("Structure"). `None` disables the check.
+    pub empty_dir_level: Option<ValidationLevel>,
+    /// Severity for brands whose sizes.json files have no purchase link naming a store.
+    /// Off (`None`) by default.
+    pub brand_without_store_level: Option<ValidationLevel>,
+    /// Warn ("StoreID") about purchase links pointing at a store whose `brands` list
+    /// doesn't include the brand. Off by default.
+    pub store_brand_coverage: bool,
+    /// Record the paths that passed each validator in `ValidationResult::passing`,
+    /// so consumers can tell "checked and clean" apart from "not checked".
+    pub include_passing: bool,
+    /// Pattern that store/brand/filament/variant `id` fields must match.
+    /// Defaults to `util::DEFAULT_ID_PATTERN`; `None` disables the check.
+    pub id_format: Option<Regex>,
+    /// Pattern the `version`/`schema_version` marker of every sizes.json must match.
+    /// `None` disables the check; `util::DEFAULT_VERSION_PATTERN` is a semver-style default.
+    pub sizes_version: Option<Regex>,
+    /// Name of the per-size flag marking a size as available for purchase.
+    pub availability_field: String,
+    /// Plausible (min, max) size weight in grams; `weight_grams` or a textual `weight`
+    /// outside it gets a "Sizes" warning. Defaults to `util::DEFAULT_WEIGHT_RANGE`;
+    /// `None` disables the check.
+    pub weight_range: Option<(f64, f64)>,
+    /// Warn about sizes.json files whose sizes list different diameters ("Sizes"). Off by
+    /// default, since a variant may legitimately span diameters.
+    pub diameter_consistency: bool,
+    /// Longest (folder name, whole path) in bytes a folder may have before getting a
+    /// "Path" warning. Defaults to `util::DEFAULT_PATH_LENGTH_LIMITS`; `None` disables the
+    /// check.
+    pub path_length_limits: Option<(usize, usize)>,
+    /// Keys every purchase link must have. `store_id` is left out by default because
+    /// missing store IDs are already reported at `missing_store_id_level`.
+    pub purchase_link_fields: Vec<String>,
+    /// Warn about sizes with more purchase links than this. `None` disables the check;
+    /// `util::DEFAULT_MAX_PURCHASE_LINKS` is a reasonable cap.
+    pub max_purchase_links: Option<usize>,
+    /// JSON keys whose string values must not have leading/trailing whitespace or tabs.
+    /// Matched at any depth; an empty list disables the check.
+    pub whitespace_fields: Vec<String>,
+    /// Maximum length, in characters, of string values under each key (matched at any
+    /// depth). Longer values get a "Content" warning; an empty map disables the check.
+    /// Defaults to `util::DEFAULT_FIELD_LENGTH_LIMITS`.
+    pub field_length_limits: BTreeMap<String, usize>,
+    /// JSON keys whose values must be numbers; a string such as `"1.75"` gets a "Format"
+    /// warning. Matched at any depth. Defaults to `util::DEFAULT_NUMBER_FIELDS`.
+    pub number_fields: Vec<String>,
+    /// JSON keys whose values must be strings; a bare number gets a "Format" warning.
+    /// Matched at any depth; empty by default.
+    pub text_fields: Vec<String>,
+    /// If/then rules between fields of one object, e.g. a size marked available must have
+    /// purchase links; objects breaking one get a "Content" error. Empty by default;
+    /// `util::DEFAULT_CONDITIONAL_RULES` holds an example.
+    pub conditional_rules: Vec<ConditionalRule>,
+    /// Report schema files that are missing or unreadable once, up front, as "Schema"
+    /// errors (see `SchemaCache::preflight`). The JSON stage then skips the files using
+    /// those schemas without reporting them again.
+    pub schema_preflight: bool,
+    /// Stop after the first validator stage that reports an `Error`, skipping the rest,
+    /// for quick pass/fail checks such as pre-commit hooks. Best effort: a stage runs to
+    /// completion (in parallel), so it may still report several issues.
+    pub fail_fast: bool,
+    /// Opt-in checks applied to brand and store logos.
+    pub logo: LogoOptions,
+    /// Settings for JSON schema validation messages.
+    pub json: JsonOptions,
+    /// Barcode formats accepted in sizes.json.
+    pub gtin: GtinOptions,
+    /// Closures run on the combined result before it is returned, see `with_post_hook`.
+    pub post_hooks: PostHooks,
+}
+
+impl ValidationOptions {
+    /// Run `hook` on the combined result of `validate_dataset_with_options` (and
+    /// `validate_dataset_with_validators`) before it is returned, e.g. to add issues from
+    /// another tool, rewrite categories or drop accepted issues. Hooks run in the order
+    /// they were added. `validate_dataset_iter` never holds a whole result, so it doesn't
+    /// run them.
+    pub fn with_post_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(&mut ValidationResult) + Send + 'static,
+    {
+        self.post_hooks.0.push(Arc::new(Mutex::new(hook)));
+        self
+    }
+}
+
+/// A closure added with `ValidationOptions::with_post_hook`.
+pub type PostHook = Arc<Mutex<dyn FnMut(&mut ValidationResult) + Send>>;
+
+/// The hooks added with `ValidationOptions::with_post_hook`. Clones of the options share
+/// them, so a hook keeping state sees every run made with any clone.
+#[derive(Clone, Default)]
+pub struct PostHooks(Vec<PostHook>);
+
+impl PostHooks {
+    /// Run every hook on `result`, in order.
+    pub fn run(&self, result: &mut ValidationResult) {
+        for hook in &self.0 {
+            // A hook that panicked earlier leaves the lock poisoned; keep running it
+            let mut hook = hook.lock().unwrap_or_else(|e| e.into_inner());
+            hook(result);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Debug for PostHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PostHooks({})", self.0.len())
+    }
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            missing_store_id_level: ValidationLevel::Warning,
+            store_ref_field: Some("store_ref".to_string()),
+            empty_dir_level: Some(ValidationLevel::Warning),
+            brand_without_store_level: None,
+            store_brand_coverage: false,
+            include_passing: false,
+            id_format: Some(Regex::new(DEFAULT_ID_PATTERN).expect("valid default id pattern")),
+            sizes_version: None,
+            availability_field: "available".to_string(),
+            weight_range: Some(DEFAULT_WEIGHT_RANGE),
+            diameter_consistency: false,
+            path_length_limits: Some(DEFAULT_PATH_LENGTH_LIMITS),
+            purchase_link_fields: vec!["url".to_string()],
+            max_purchase_links: None,
+            whitespace_fields: vec!["id".to_string(), "name".to_string()],
+            field_length_limits: DEFAULT_FIELD_LENGTH_LIMITS
+                .iter()
+                .map(|(field, max)| (field.to_string(), *max))
+                .collect(),
+            number_fields: DEFAULT_NUMBER_FIELDS.iter().map(|f| f.to_string()).collect(),
+            text_fields: Vec::new(),
+            conditional_rules: Vec::new(),
+            schema_preflight: false,
+            fail_fast: false,
+            logo: LogoOptions::default(),
+            json: JsonOptions::default(),
+            gtin: GtinOptions::default(),
+            post_hooks: PostHooks::default(),
+        }
+    }
+}
+
+/// Opt-in logo checks on top of the naming and dimension rules `validate_logo` always
+/// applies, plus the decode limits used for untrusted raster files.
+#[derive(Clone, Debug)]
+pub struct LogoOptions {
+    /// Warn when a PNG logo has no transparent pixels, i.e. it sits on a flat background.
+    pub require_transparency: bool,
+    /// Edge lengths a square raster logo may have (e.g. `[128, 256, 512]`), for rendering
+    /// pipelines that expect standard sizes. `None` accepts any size within the min/max range.
+    pub allowed_sizes: Option<Vec<u32>>,
+    /// Warn when a PNG logo uses more than 8 bits per sample (16-bit PNGs bloat files).
+    pub warn_high_bit_depth: bool,
+    /// Largest width x height a raster logo may declare in its header. Bigger files are
+    /// reported as a "Logo" error without being decoded, and the decoder's allocations are
+    /// capped to match. `None` only keeps the `image` crate's own 512 MiB allocation limit.
+    pub max_decode_pixels: Option<u64>,
+    /// Self-test for the fast PNG header reader (`parse_png_header`): warn when the
+    /// dimensions it reads differ from what the `image` decoder produced. Meant for CI
+    /// runs that want to catch header-parsing bugs; off by default.
+    pub verify_header_dimensions: bool,
+    /// Extensions a logo file may have: the file must be named `logo.<ext>`. Defaults to
+    /// `util::DEFAULT_LOGO_EXTENSIONS`; add "avif" or "heic" to accept those formats.
+    /// Their size is read from the container, since this build can't decode them.
+    pub allowed_extensions: Vec<String>,
+    /// Warn when a PNG/JPEG logo's metadata (see `raster_metadata`) adds up to more than
+    /// this many bytes, e.g. an embedded color profile or EXIF block. `None` (the
+    /// default) skips the check; `util::DEFAULT_MAX_LOGO_METADATA` is a sensible limit.
+    pub max_metadata_bytes: Option<u64>,
+    /// Warn when the entities of one brand reference logos in different formats (e.g.
+    /// both `logo.svg` and `logo.png`). Off by default.
+    pub consistent_brand_format: bool,
+    /// Size rules for brand logos.
+    pub brand: LogoConstraints,
+    /// Size rules for store logos; the same as for brands by default.
+    pub store: LogoConstraints,
+}
+
+impl LogoOptions {
+    /// The size rules for logos of `context`.
+    pub fn constraints(&self, context: LogoContext) -> &LogoConstraints {
+        match context {
+            LogoContext::Brand => &self.brand,
+            LogoContext::Store => &self.store,
+        }
+    }
+}
+
+/// Size rules for raster logos of one kind of entity (see `LogoContext`).
+#[derive(Clone, Debug)]
+pub struct LogoConstraints {
+    /// Smallest width and height allowed, in pixels.
+    pub min_size: u32,
+    /// Largest width and height allowed, in pixels.
+    pub max_size: u32,
+    /// Require width == height. Turning this off allows e.g. wide store banners.
+    pub require_square: bool,
+    /// How many pixels width and height may differ by when `require_square` is set.
+    /// Logos within it (e.g. 256x255 after resampling) get a warning instead of an error;
+    /// 0, the default, requires an exact square.
+    pub square_tolerance: u32,
+}
+
+impl Default for LogoConstraints {
+    fn default() -> Self {
+        Self {
+            min_size: LOGO_MIN_SIZE,
+            max_size: LOGO_MAX_SIZE,
+            require_square: true,
+            square_tolerance: 0,
+        }
+    }
+}
+
+impl Default for LogoOptions {
+    fn default() -> Self {
+        Self {
+            require_transparency: false,
+            allowed_sizes: None,
+            warn_high_bit_depth: false,
+            max_decode_pixels: Some(DEFAULT_MAX_LOGO_PIXELS),
+            verify_header_dimensions: false,
+            allowed_extensions: DEFAULT_LOGO_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+            max_metadata_bytes: None,
+            consistent_brand_format: false,
+            brand: LogoConstraints::default(),
+            store: LogoConstraints::default(),
+        }
+    }
+}
+
+/// Settings for `validate_json_with_options`.
+#[derive(Clone, Debug)]
+pub struct JsonOptions {
+    /// Maximum length, in characters, of the jsonschema part of an error message.
+    /// Longer messages (e.g. a failed `oneOf` listing every branch) end in "…[truncated]".
+    /// The instance path is always kept in full. `None` keeps full messages.
+    pub max_message_len: Option<usize>,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self {
+            max_message_len: Some(DEFAULT_MAX_MESSAGE_LEN),
+        }
+    }
+}
+
+/// Barcode formats `validate_gtin_ean_with_options` accepts in each sizes.json field.
+#[derive(Clone, Debug)]
+pub struct GtinOptions {
+    /// Formats allowed in `gtin` (default: GTIN-12 and GTIN-13).
+    pub gtin_formats: Vec<GtinFormat>,
+    /// Formats allowed in `ean` (default: GTIN-13 only).
+    pub ean_formats: Vec<GtinFormat>,
+    /// Warn about sizes that have neither a `gtin` nor an `ean` (off by default).
+    pub require_barcode: bool,
+}
+
+impl Default for GtinOptions {
+    fn default() -> Self {
+        Self {
+            gtin_formats: vec![GtinFormat::Gtin12, GtinFormat::Gtin13],
+            ean_formats: vec![GtinFormat::Gtin13],
+            require_barcode: false,
+        }
+    }
+}
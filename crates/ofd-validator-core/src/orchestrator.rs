@@ -1,21 +1,29 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use rayon::prelude::*;
 
+use crate::codes;
+use crate::options::{LogoOptions, ValidationOptions};
+use crate::pipeline::{builtin_validators, Validator};
 use crate::schema_cache::SchemaCache;
-use crate::types::ValidationResult;
+use crate::types::{ValidationError, ValidationLevel, ValidationResult};
 use crate::validators;
 use crate::validators::missing_files::FileManifest;
+use crate::validators::LogoContext;
+
+/// (path_label, filename, raw bytes, logo_name from JSON, brand or store logo)
+pub type LogoEntry = (String, String, Vec<u8>, Option<String>, LogoContext);
 
 /// A pre-loaded dataset ready for validation.
 /// All file contents are already in memory — no filesystem access during validation.
 pub struct DataSet {
     /// (path_label, schema_name, parsed JSON)
     pub json_entries: Vec<(String, String, Value)>,
-    /// (path_label, filename, raw bytes, logo_name from JSON)
-    pub logo_entries: Vec<(String, String, Vec<u8>, Option<String>)>,
+    /// Source text of the JSON entries read from files, by path label, so schema failures
+    /// carry a line and column. Entries from pending changes have none.
+    pub json_sources: HashMap<String, String>,
+    pub logo_entries: Vec<LogoEntry>,
     /// (path_label, actual_folder_name, json_data from the folder's JSON file, json_key)
     pub folder_entries: Vec<(String, String, Value, String)>,
     /// (path_label, parsed sizes.json)
@@ -26,6 +34,93 @@ pub struct DataSet {
     pub file_manifest: FileManifest,
     /// Compiled schema cache
     pub schema_cache: SchemaCache,
+    /// Problems found while loading: unreadable files and directories ("IO" errors) and
+    /// files that only parsed as JSON5
+    pub load_issues: Vec<crate::types::ValidationError>,
+    /// Set by `retain_changed`: the entries cover only changed files, so the checks that
+    /// need every sizes.json of a brand (brands without a store, stores not listing a
+    /// brand) are skipped.
+    pub changed_only: bool,
+}
+
+/// The validation work a DataSet represents, without file contents.
+/// Serializes to JSON for external orchestration.
+#[derive(Serialize)]
+pub struct TaskList {
+    pub json: Vec<JsonTask>,
+    pub logos: Vec<LogoTask>,
+    pub folders: Vec<FolderTask>,
+    pub sizes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct JsonTask {
+    pub path: String,
+    pub schema: String,
+}
+
+#[derive(Serialize)]
+pub struct LogoTask {
+    pub path: String,
+    pub filename: String,
+    pub logo_name: Option<String>,
+    pub context: LogoContext,
+    /// False when the referenced logo file could not be read
+    pub found: bool,
+}
+
+#[derive(Serialize)]
+pub struct FolderTask {
+    pub path: String,
+    pub folder_name: String,
+    pub json_key: String,
+}
+
+impl DataSet {
+    /// Validate an in-memory JSON value (e.g. a brand object built by the caller) against
+    /// one of this dataset's schemas. The value is not added to the dataset.
+    pub fn validate_value(&self, data: &Value, schema_name: &str) -> ValidationResult {
+        self.schema_cache.validate_value(data, schema_name)
+    }
+
+    /// Number of files validated from this dataset: JSON files (including sizes.json) and
+    /// logos. sizes.json files outside the variant level only feed cross-file checks and
+    /// aren't counted.
+    pub fn file_count(&self) -> usize {
+        self.json_entries.len() + self.logo_entries.len()
+    }
+
+    /// List the tasks `validate_dataset` would run on this DataSet, without running them.
+    pub fn task_list(&self) -> TaskList {
+        TaskList {
+            json: self.json_entries
+                .iter()
+                .map(|(path, schema_name, _)| JsonTask {
+                    path: path.clone(),
+                    schema: schema_name.clone(),
+                })
+                .collect(),
+            logos: self.logo_entries
+                .iter()
+                .map(|(path, filename, bytes, logo_name, context)| LogoTask {
+                    path: path.clone(),
+                    filename: filename.clone(),
+                    logo_name: logo_name.clone(),
+                    context: *context,
+                    found: !bytes.is_empty(),
+                })
+                .collect(),
+            folders: self.folder_entries
+                .iter()
+                .map(|(path, folder_name, _, json_key)| FolderTask {
+                    path: path.clone(),
+                    folder_name: folder_name.clone(),
+                    json_key: json_key.clone(),
+                })
+                .collect(),
+            sizes: self.sizes_entries.iter().map(|(path, _)| path.clone()).collect(),
+        }
+    }
 }
 
 // --- Types for applying pending changes from the WebUI ---
@@ -73,12 +168,11 @@ fn entity_path_to_fs_info(
     let parts: Vec<&str> = entity_path.split('/').collect();
 
     let (json_path, folder_path, folder_name, folder_json_key) = match parts.as_slice() {
-        ["stores", slug] => (
-            stores_dir.join(slug).join("store.json"),
-            stores_dir.join(slug),
-            slug.to_string(),
-            "id".to_string(),
-        ),
+        // Stores may sit in grouping folders, e.g. "stores/eu/prusa"
+        ["stores", groups @ .., slug] if !slug.is_empty() => {
+            let folder = groups.iter().fold(stores_dir.to_path_buf(), |dir, group| dir.join(group)).join(slug);
+            (folder.join("store.json"), folder, slug.to_string(), "id".to_string())
+        }
         ["brands", slug] => (
             data_dir.join(slug).join("brand.json"),
             data_dir.join(slug),
@@ -140,6 +234,10 @@ impl DataSet {
     /// `changes_json` should be a JSON string with the format:
     /// `{ "changes": [{ "entity": { "path": "...", "type": "..." }, "operation": "create"|"update"|"delete", "data": {...} }] }`
     pub fn apply_changes(&mut self, changes_json: &str, data_dir: &Path, stores_dir: &Path) {
+        // Match the normalized paths recorded by from_directories
+        let data_dir = &crate::util::normalize_dir(data_dir);
+        let stores_dir = &crate::util::normalize_dir(stores_dir);
+
         let payload: ChangesPayload = match serde_json::from_str(changes_json) {
             Ok(p) => p,
             Err(e) => {
@@ -166,7 +264,8 @@ impl DataSet {
                         None => continue,
                     };
 
-                    // Update or add json_entries
+                    // Update or add json_entries; the file's text no longer matches the data
+                    self.json_sources.remove(&info.json_path);
                     if let Some(existing) = self.json_entries.iter_mut().find(|(p, _, _)| *p == info.json_path) {
                         existing.2 = data.clone();
                     } else {
@@ -217,14 +316,14 @@ impl DataSet {
                     // Remove from folder_entries
                     self.folder_entries.retain(|(p, _, _, _)| *p != info.folder_path);
                     // Remove logo entries for this folder
-                    self.logo_entries.retain(|(p, _, _, _)| !p.starts_with(&info.folder_path));
+                    self.logo_entries.retain(|(p, _, _, _, _)| !p.starts_with(&info.folder_path));
                     // Remove sizes entries under this folder
                     self.sizes_entries.retain(|(p, _)| !p.starts_with(&info.folder_path));
 
                     // For stores: remove from valid_store_ids
                     if info.schema_name == "store" {
                         // Extract the ID from the path (last component)
-                        if let Some(slug) = change.entity.path.split('/').last() {
+                        if let Some(slug) = change.entity.path.split('/').next_back() {
                             self.valid_store_ids.remove(slug);
                         }
                     }
@@ -233,7 +332,7 @@ impl DataSet {
                     let prefix = format!("{}/", info.folder_path);
                     self.json_entries.retain(|(p, _, _)| !p.starts_with(&prefix));
                     self.folder_entries.retain(|(p, _, _, _)| !p.starts_with(&prefix));
-                    self.logo_entries.retain(|(p, _, _, _)| !p.starts_with(&prefix));
+                    self.logo_entries.retain(|(p, _, _, _, _)| !p.starts_with(&prefix));
                     self.sizes_entries.retain(|(p, _)| !p.starts_with(&prefix));
                 }
                 _ => {}
@@ -245,332 +344,477 @@ impl DataSet {
 #[cfg(feature = "filesystem")]
 impl DataSet {
     /// Build a DataSet by walking the filesystem.
+    ///
+    /// The directories are normalized first (see `util::normalize_dir`), so path labels
+    /// are identical however the directories were spelled.
     pub fn from_directories(
         data_dir: &std::path::Path,
         stores_dir: &std::path::Path,
         schemas_dir: &std::path::Path,
     ) -> Self {
-        use crate::util::load_json;
-        use walkdir::WalkDir;
+        Self::from_directories_with_retry(
+            data_dir,
+            stores_dir,
+            schemas_dir,
+            crate::producer::RetryPolicy::default(),
+        )
+    }
 
-        let schema_cache = SchemaCache::from_directory(schemas_dir);
-        let file_manifest = validators::missing_files::build_file_manifest(data_dir, stores_dir);
-
-        let mut json_entries = Vec::new();
-        let mut logo_entries = Vec::new();
-        let mut folder_entries = Vec::new();
-        let mut sizes_entries = Vec::new();
-        let mut valid_store_ids = HashSet::new();
-
-        // Walk brand hierarchy
-        if let Ok(brands) = std::fs::read_dir(data_dir) {
-            for brand_entry in brands.filter_map(|e| e.ok()) {
-                let brand_dir = brand_entry.path();
-                if !brand_dir.is_dir() {
-                    continue;
-                }
+    /// Like `from_directories`, but with a custom policy for retrying transient read errors.
+    /// Reads that still fail are recorded in `load_issues` and reported as "IO" errors.
+    pub fn from_directories_with_retry(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schemas_dir: &std::path::Path,
+        retry: crate::producer::RetryPolicy,
+    ) -> Self {
+        let schema_cache = SchemaCache::from_directory(&crate::util::normalize_dir(schemas_dir));
+        Self::load_directories(data_dir, stores_dir, schema_cache, retry, Default::default())
+    }
 
-                let brand_file = brand_dir.join("brand.json");
-                if brand_file.exists() {
-                    if let Some(data) = load_json(&brand_file) {
-                        json_entries.push((
-                            brand_file.to_string_lossy().to_string(),
-                            "brand".to_string(),
-                            data.clone(),
-                        ));
+    /// Like `from_directories`, discovering data files according to `layout`, e.g.
+    /// `LayoutStrategy::Flat` for forks keeping every data file in one directory. The
+    /// stores directory is walked as usual.
+    pub fn from_directories_with_layout(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schemas_dir: &std::path::Path,
+        layout: crate::producer::LayoutStrategy,
+    ) -> Self {
+        let schema_cache = SchemaCache::from_directory(&crate::util::normalize_dir(schemas_dir));
+        Self::load_directories(data_dir, stores_dir, schema_cache, Default::default(), layout)
+    }
 
-                        // Logo task from brand.json
-                        if let Some(logo_name) = data.get("logo").and_then(|v| v.as_str()) {
-                            let logo_path = brand_dir.join(logo_name);
-                            if let Ok(bytes) = std::fs::read(&logo_path) {
-                                let filename = logo_path.file_name()
-                                    .map(|f| f.to_string_lossy().to_string())
-                                    .unwrap_or_default();
-                                logo_entries.push((
-                                    logo_path.to_string_lossy().to_string(),
-                                    filename,
-                                    bytes,
-                                    Some(logo_name.to_string()),
-                                ));
-                            } else {
-                                // Push empty bytes so the validator can report "file not found"
-                                logo_entries.push((
-                                    logo_path.to_string_lossy().to_string(),
-                                    logo_name.to_string(),
-                                    Vec::new(),
-                                    Some(logo_name.to_string()),
-                                ));
-                            }
-                        }
+    /// Like `from_directories`, but using `schema_cache` instead of loading the schemas
+    /// again. Build the cache once and pass a clone for each dataset when validating many
+    /// datasets that share schemas; clones share the parsed schemas.
+    pub fn from_directories_with_cache(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schema_cache: SchemaCache,
+    ) -> Self {
+        Self::load_directories(data_dir, stores_dir, schema_cache, Default::default(), Default::default())
+    }
 
-                        // Folder name task
-                        let folder_name = brand_dir.file_name()
-                            .map(|f| f.to_string_lossy().to_string())
-                            .unwrap_or_default();
-                        folder_entries.push((
-                            brand_dir.to_string_lossy().to_string(),
-                            folder_name,
-                            data,
-                            "id".to_string(),
-                        ));
-                    }
-                }
+    /// `from_directories_with_cache` discovering data files according to `layout` (see
+    /// `from_directories_with_layout`), e.g. with a cache built by `SchemaCache::with_mapping`.
+    pub fn from_directories_with_cache_and_layout(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schema_cache: SchemaCache,
+        layout: crate::producer::LayoutStrategy,
+    ) -> Self {
+        Self::load_directories(data_dir, stores_dir, schema_cache, Default::default(), layout)
+    }
 
-                if let Ok(materials) = std::fs::read_dir(&brand_dir) {
-                    for material_entry in materials.filter_map(|e| e.ok()) {
-                        let material_dir = material_entry.path();
-                        if !material_dir.is_dir() {
-                            continue;
-                        }
+    fn load_directories(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schema_cache: SchemaCache,
+        retry: crate::producer::RetryPolicy,
+        layout: crate::producer::LayoutStrategy,
+    ) -> Self {
+        use crate::producer::{LayoutStrategy, TaskProducer};
+        use crate::util::normalize_dir;
+
+        let data_dir = &normalize_dir(data_dir);
+        let stores_dir = &normalize_dir(stores_dir);
+        // A flat layout has no hierarchy whose required files could be missing
+        let file_manifest = match layout {
+            LayoutStrategy::Nested => validators::missing_files::build_file_manifest(data_dir, stores_dir),
+            LayoutStrategy::Flat => validators::missing_files::build_store_manifest(stores_dir),
+        };
 
-                        let material_file = material_dir.join("material.json");
-                        if material_file.exists() {
-                            if let Some(data) = load_json(&material_file) {
-                                json_entries.push((
-                                    material_file.to_string_lossy().to_string(),
-                                    "material".to_string(),
-                                    data.clone(),
-                                ));
-
-                                let folder_name = material_dir.file_name()
-                                    .map(|f| f.to_string_lossy().to_string())
-                                    .unwrap_or_default();
-                                folder_entries.push((
-                                    material_dir.to_string_lossy().to_string(),
-                                    folder_name,
-                                    data,
-                                    "material".to_string(),
-                                ));
-                            }
-                        }
+        let mut dataset = DataSet {
+            json_entries: Vec::new(),
+            json_sources: HashMap::new(),
+            logo_entries: Vec::new(),
+            folder_entries: Vec::new(),
+            sizes_entries: Vec::new(),
+            valid_store_ids: HashSet::new(),
+            file_manifest,
+            schema_cache,
+            load_issues: Vec::new(),
+            changed_only: false,
+        };
+        TaskProducer::new(data_dir, stores_dir)
+            .with_retry(retry)
+            .with_layout(layout)
+            .for_each(|task| dataset.push_task(task));
+        dataset
+    }
 
-                        if let Ok(filaments) = std::fs::read_dir(&material_dir) {
-                            for filament_entry in filaments.filter_map(|e| e.ok()) {
-                                let filament_dir = filament_entry.path();
-                                if !filament_dir.is_dir() {
-                                    continue;
-                                }
-
-                                let filament_file = filament_dir.join("filament.json");
-                                if filament_file.exists() {
-                                    if let Some(data) = load_json(&filament_file) {
-                                        json_entries.push((
-                                            filament_file.to_string_lossy().to_string(),
-                                            "filament".to_string(),
-                                            data.clone(),
-                                        ));
-
-                                        let folder_name = filament_dir.file_name()
-                                            .map(|f| f.to_string_lossy().to_string())
-                                            .unwrap_or_default();
-                                        folder_entries.push((
-                                            filament_dir.to_string_lossy().to_string(),
-                                            folder_name,
-                                            data,
-                                            "id".to_string(),
-                                        ));
-                                    }
-                                }
-
-                                if let Ok(variants) = std::fs::read_dir(&filament_dir) {
-                                    for variant_entry in variants.filter_map(|e| e.ok()) {
-                                        let variant_dir = variant_entry.path();
-                                        if !variant_dir.is_dir() {
-                                            continue;
-                                        }
-
-                                        let variant_file = variant_dir.join("variant.json");
-                                        if variant_file.exists() {
-                                            if let Some(data) = load_json(&variant_file) {
-                                                json_entries.push((
-                                                    variant_file.to_string_lossy().to_string(),
-                                                    "variant".to_string(),
-                                                    data.clone(),
-                                                ));
-
-                                                let folder_name = variant_dir.file_name()
-                                                    .map(|f| f.to_string_lossy().to_string())
-                                                    .unwrap_or_default();
-                                                folder_entries.push((
-                                                    variant_dir.to_string_lossy().to_string(),
-                                                    folder_name,
-                                                    data,
-                                                    "id".to_string(),
-                                                ));
-                                            }
-                                        }
-
-                                        let sizes_file = variant_dir.join("sizes.json");
-                                        if sizes_file.exists() {
-                                            if let Some(data) = load_json(&sizes_file) {
-                                                json_entries.push((
-                                                    sizes_file.to_string_lossy().to_string(),
-                                                    "sizes".to_string(),
-                                                    data.clone(),
-                                                ));
-                                                sizes_entries.push((
-                                                    sizes_file.to_string_lossy().to_string(),
-                                                    data,
-                                                ));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    /// Build a DataSet from the stores directory alone, for reviewing store edits
+    /// separately from brand data.
+    ///
+    /// Running `validate_dataset` on it covers store.json schemas, store folder names,
+    /// store logos, store metadata and missing store.json files. The purchase-link checks
+    /// have no sizes files to look at, so they pass trivially.
+    pub fn from_stores_directory(
+        stores_dir: &std::path::Path,
+        schemas_dir: &std::path::Path,
+    ) -> Self {
+        use crate::producer::TaskProducer;
+        use crate::util::normalize_dir;
+
+        let stores_dir = &normalize_dir(stores_dir);
+
+        let mut dataset = DataSet {
+            json_entries: Vec::new(),
+            json_sources: HashMap::new(),
+            logo_entries: Vec::new(),
+            folder_entries: Vec::new(),
+            sizes_entries: Vec::new(),
+            valid_store_ids: HashSet::new(),
+            file_manifest: validators::missing_files::build_store_manifest(stores_dir),
+            schema_cache: SchemaCache::from_directory(&normalize_dir(schemas_dir)),
+            load_issues: Vec::new(),
+            changed_only: false,
+        };
+        TaskProducer::stores_only(stores_dir).for_each(|task| dataset.push_task(task));
+        dataset
+    }
 
-        // Walk stores
-        if let Ok(stores) = std::fs::read_dir(stores_dir) {
-            for store_entry in stores.filter_map(|e| e.ok()) {
-                let store_dir = store_entry.path();
-                if !store_dir.is_dir() {
-                    continue;
-                }
+    /// Build a DataSet covering only the brand `brand` under `data_dir`, matched by folder
+    /// name or by the `id` or `name` in its brand.json (case-insensitively), for checking
+    /// one brand without walking the whole database.
+    ///
+    /// Store directories are read only for their ids, so purchase links are still checked
+    /// against every store while store files themselves aren't validated. When no brand
+    /// matches, `file_manifest.brands` is empty and validation passes trivially.
+    pub fn from_brand_directory(
+        data_dir: &std::path::Path,
+        stores_dir: &std::path::Path,
+        schemas_dir: &std::path::Path,
+        brand: &str,
+    ) -> Self {
+        use crate::producer::TaskProducer;
+        use crate::util::{brand_dir_matches, normalize_dir};
+
+        let data_dir = &normalize_dir(data_dir);
+        let stores_dir = &normalize_dir(stores_dir);
+
+        let mut file_manifest = validators::missing_files::build_file_manifest(data_dir, stores_dir);
+        file_manifest.brands.retain(|b| brand_dir_matches(std::path::Path::new(&b.path), brand));
+        // Kept so path-style store references resolve; missing store.json isn't this brand's problem
+        file_manifest.stores.retain(|s| s.has_store_json);
+        let brand_paths: Vec<String> = file_manifest.brands.iter().map(|b| b.path.clone()).collect();
+        file_manifest
+            .orphan_sizes_dirs
+            .retain(|dir| brand_paths.iter().any(|b| std::path::Path::new(dir).starts_with(b)));
+
+        let mut dataset = DataSet {
+            json_entries: Vec::new(),
+            json_sources: HashMap::new(),
+            logo_entries: Vec::new(),
+            folder_entries: Vec::new(),
+            sizes_entries: Vec::new(),
+            valid_store_ids: HashSet::new(),
+            file_manifest,
+            schema_cache: SchemaCache::from_directory(&normalize_dir(schemas_dir)),
+            load_issues: Vec::new(),
+            changed_only: false,
+        };
+        TaskProducer::new(data_dir, stores_dir)
+            .only_brand(brand)
+            .for_each(|task| dataset.push_task(task));
+        dataset
+    }
 
-                let store_file = store_dir.join("store.json");
-                if store_file.exists() {
-                    if let Some(data) = load_json(&store_file) {
-                        json_entries.push((
-                            store_file.to_string_lossy().to_string(),
-                            "store".to_string(),
-                            data.clone(),
-                        ));
+    /// Collect auxiliary JSON files under `data_dir` that the hierarchy walk doesn't
+    /// pick up (e.g. a top-level `index.json`), adding them to `json_entries`.
+    ///
+    /// `extra` maps glob patterns, matched against paths relative to `data_dir`, to schema
+    /// names (e.g. `("*.index.json", "index")`). The first matching pattern wins and files
+    /// already collected are skipped. Register the schemas themselves with
+    /// `SchemaCache::with_mapping`.
+    pub fn add_extra_json(
+        &mut self,
+        data_dir: &std::path::Path,
+        extra: &[(&str, &str)],
+    ) -> Result<(), glob::PatternError> {
+        use crate::util::{json5_warning, normalize_dir, parse_json_relaxed};
+        use walkdir::WalkDir;
 
-                        if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
-                            valid_store_ids.insert(id.to_string());
-                        }
+        let patterns = extra
+            .iter()
+            .map(|(pattern, schema_name)| Ok((glob::Pattern::new(pattern)?, *schema_name)))
+            .collect::<Result<Vec<_>, glob::PatternError>>()?;
+        if patterns.is_empty() {
+            return Ok(());
+        }
 
-                        // Store logo
-                        if let Some(logo_name) = data.get("logo").and_then(|v| v.as_str()) {
-                            let logo_path = store_dir.join(logo_name);
-                            if let Ok(bytes) = std::fs::read(&logo_path) {
-                                let filename = logo_path.file_name()
-                                    .map(|f| f.to_string_lossy().to_string())
-                                    .unwrap_or_default();
-                                logo_entries.push((
-                                    logo_path.to_string_lossy().to_string(),
-                                    filename,
-                                    bytes,
-                                    Some(logo_name.to_string()),
-                                ));
-                            } else {
-                                logo_entries.push((
-                                    logo_path.to_string_lossy().to_string(),
-                                    logo_name.to_string(),
-                                    Vec::new(),
-                                    Some(logo_name.to_string()),
-                                ));
-                            }
-                        }
+        let data_dir = normalize_dir(data_dir);
+        let known: HashSet<String> = self.json_entries.iter().map(|(p, _, _)| p.clone()).collect();
 
-                        // Store folder name
-                        let folder_name = store_dir.file_name()
-                            .map(|f| f.to_string_lossy().to_string())
-                            .unwrap_or_default();
-                        folder_entries.push((
-                            store_dir.to_string_lossy().to_string(),
-                            folder_name,
-                            data,
-                            "id".to_string(),
-                        ));
-                    }
+        for entry in WalkDir::new(&data_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = match entry.path().strip_prefix(&data_dir) {
+                Ok(r) => r,
+                Err(_) => continue,
+            };
+            let Some((_, schema_name)) = patterns.iter().find(|(p, _)| p.matches_path(relative)) else {
+                continue;
+            };
+
+            let path_str = entry.path().to_string_lossy().to_string();
+            if known.contains(&path_str) {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            if let Some((data, used_json5)) = parse_json_relaxed(&content, entry.path()) {
+                if used_json5 {
+                    self.load_issues.push(json5_warning(&path_str));
                 }
+                self.json_sources.insert(path_str.clone(), content);
+                self.json_entries.push((path_str, schema_name.to_string(), data));
             }
         }
 
-        // Also collect sizes.json via WalkDir for gtin/store_id validators
-        // (in case some were missed by the nested loop above)
-        // Actually the nested loop above already collects all sizes.json entries,
-        // so we don't need WalkDir here. But let's also catch any sizes.json
-        // that might exist at unexpected locations.
-        for entry in WalkDir::new(data_dir).into_iter().filter_map(|e| e.ok()) {
-            if entry.file_name() == "sizes.json" {
-                let path_str = entry.path().to_string_lossy().to_string();
-                // Only add if not already collected
-                if !sizes_entries.iter().any(|(p, _)| p == &path_str) {
-                    if let Some(data) = load_json(entry.path()) {
-                        sizes_entries.push((path_str, data));
-                    }
+        Ok(())
+    }
+
+    /// Keep only the entries for `changed` files (e.g. from `git::changed_files`), so
+    /// validation covers just what a branch touched.
+    ///
+    /// A folder is kept when its JSON file changed, and a logo when the logo itself or
+    /// the brand.json/store.json referencing it changed. The file manifest is trimmed to
+    /// directories containing a changed file. Store IDs stay complete, so purchase links
+    /// in changed sizes files are still checked against every store.
+    ///
+    /// Paths are compared with symlinks resolved, since git reports them that way while
+    /// the loaders keep the directories as given. When no entry is left, a "Scope"
+    /// warning says that nothing was validated.
+    pub fn retain_changed(&mut self, changed: &[std::path::PathBuf]) {
+        use std::path::{Path, PathBuf};
+
+        // Paths that don't exist (e.g. in-memory labels) are compared as they are
+        let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let changed: HashSet<PathBuf> = changed.iter().map(|p| canonical(p)).collect();
+        let is_changed = |path: &str| changed.contains(&canonical(Path::new(path)));
+        let touches = |dir: &str| {
+            let dir = canonical(Path::new(dir));
+            changed.iter().any(|p| p.starts_with(&dir))
+        };
+
+        self.json_entries.retain(|(path, _, _)| is_changed(path));
+        self.sizes_entries.retain(|(path, _)| is_changed(path));
+        self.folder_entries.retain(|(dir, _, _, _)| {
+            let dir = canonical(Path::new(dir));
+            changed.iter().any(|p| p.parent() == Some(dir.as_path()) && p.extension().is_some_and(|e| e == "json"))
+        });
+        self.logo_entries.retain(|(path, _, _, _, _)| {
+            let path = canonical(Path::new(path));
+            changed.contains(&path)
+                || changed.iter().any(|p| {
+                    p.file_name().is_some_and(|f| f == "brand.json" || f == "store.json")
+                        && p.parent().is_some_and(|dir| path.starts_with(dir))
+                })
+        });
+        self.load_issues.retain(|issue| issue.path.as_deref().is_none_or(is_changed));
+
+        let manifest = &mut self.file_manifest;
+        manifest.brands.retain(|b| touches(&b.path));
+        for brand in &mut manifest.brands {
+            brand.materials.retain(|m| touches(&m.path));
+            for material in &mut brand.materials {
+                material.filaments.retain(|f| touches(&f.path));
+                for filament in &mut material.filaments {
+                    filament.variants.retain(|v| touches(&v.path));
                 }
             }
         }
+        manifest.stores.retain(|s| touches(&s.path));
+        manifest.orphan_sizes_dirs.retain(|dir| touches(dir));
+        self.changed_only = true;
+
+        let nothing_left = self.json_entries.is_empty()
+            && self.sizes_entries.is_empty()
+            && self.folder_entries.is_empty()
+            && self.logo_entries.is_empty()
+            && manifest.brands.is_empty()
+            && manifest.stores.is_empty()
+            && manifest.orphan_sizes_dirs.is_empty();
+        if nothing_left {
+            let message = match changed.len() {
+                0 => "No files changed; nothing was validated".to_string(),
+                n => format!("None of the {} changed files is part of the dataset; nothing was validated", n),
+            };
+            self.load_issues.push(ValidationError::warning(codes::SCOPE, message, None));
+        }
+    }
+
+    /// Add a task emitted by a `TaskProducer` to the matching entry list.
+    pub fn push_task(&mut self, task: crate::producer::Task) {
+        use crate::producer::Task;
+
+        match task {
+            Task::Json { path, schema_name, data, source } => {
+                self.json_sources.insert(path.clone(), source);
+                self.json_entries.push((path, schema_name, data));
+            }
+            Task::Logo { path, filename, bytes, logo_name, context } => {
+                self.logo_entries.push((path, filename, bytes, logo_name, context))
+            }
+            Task::Folder { path, folder_name, data, json_key } => {
+                self.folder_entries.push((path, folder_name, data, json_key))
+            }
+            Task::Sizes { path, data } => self.sizes_entries.push((path, data)),
+            Task::StoreId(id) => {
+                self.valid_store_ids.insert(id);
+            }
+            Task::LoadIssue(issue) => self.load_issues.push(issue),
+        }
+    }
+}
 
-        DataSet {
-            json_entries,
-            logo_entries,
-            folder_entries,
-            sizes_entries,
-            valid_store_ids,
-            file_manifest,
-            schema_cache,
+/// Schema-validate one JSON entry. `source` is the text `data` was parsed from, if it
+/// came from a file; failures are then validated again against it so they carry a line
+/// and column.
+pub(crate) fn validate_json_entry(
+    path: &str,
+    schema_name: &str,
+    data: &Value,
+    source: Option<&str>,
+    schema_cache: &SchemaCache,
+    options: &crate::options::JsonOptions,
+) -> ValidationResult {
+    let result = validators::validate_json_with_options(data, schema_name, schema_cache, Some(path), options);
+    match source {
+        Some(source) if !result.errors.is_empty() => {
+            validators::validate_json_source(data, source, schema_name, schema_cache, Some(path), options)
         }
+        _ => result,
+    }
+}
+
+/// Validate a single logo entry, reporting empty content as a missing file.
+pub(crate) fn validate_logo_entry(
+    path: &str,
+    filename: &str,
+    bytes: &[u8],
+    logo_name: Option<&str>,
+    context: LogoContext,
+    options: &LogoOptions,
+) -> ValidationResult {
+    if bytes.is_empty() {
+        // File was not found on disk
+        let mut r = ValidationResult::default();
+        r.add(crate::types::ValidationError::error(
+            codes::LOGO,
+            "Logo file not found",
+            Some(path.to_string()),
+        ));
+        r
+    } else {
+        validators::validate_logo_in_context(bytes, filename, logo_name, Some(path), context, options)
+    }
+}
+
+/// Run the per-file checks for one streamed task, so validation can start while a
+/// `TaskProducer` is still walking.
+///
+/// Returns `None` for tasks that only feed dataset-wide checks (sizes files and store
+/// IDs); collect those into a `DataSet` and run `validate_dataset_with_options` for the
+/// cross-file validators.
+#[cfg(feature = "filesystem")]
+pub fn validate_task(
+    task: &crate::producer::Task,
+    schema_cache: &SchemaCache,
+    options: &ValidationOptions,
+) -> Option<ValidationResult> {
+    use crate::producer::Task;
+
+    match task {
+        Task::Json { path, schema_name, data, source } => {
+            Some(validate_json_entry(path, schema_name, data, Some(source), schema_cache, &options.json))
+        }
+        Task::Logo { path, filename, bytes, logo_name, context } => {
+            Some(validate_logo_entry(path, filename, bytes, logo_name.as_deref(), *context, &options.logo))
+        }
+        Task::Folder { path, folder_name, data, json_key } => {
+            Some(validators::validate_folder_name(folder_name, data, json_key, Some(path)))
+        }
+        Task::LoadIssue(issue) => {
+            let mut r = ValidationResult::default();
+            r.add(issue.clone());
+            Some(r)
+        }
+        Task::Sizes { .. } | Task::StoreId(_) => None,
     }
 }
 
 /// Run all validations on a pre-loaded DataSet.
 pub fn validate_dataset(dataset: &DataSet) -> ValidationResult {
-    let mut result = ValidationResult::default();
+    validate_dataset_with_options(dataset, &ValidationOptions::default())
+}
 
-    // 1. Missing files
-    result.merge_from(&validators::validate_required_files(&dataset.file_manifest));
+/// Run all validations on a pre-loaded DataSet using the given options.
+pub fn validate_dataset_with_options(dataset: &DataSet, options: &ValidationOptions) -> ValidationResult {
+    validate_dataset_with_validators(dataset, options, &[])
+}
 
-    // 2. JSON schema validation (parallel)
-    let json_results: Vec<ValidationResult> = dataset.json_entries
-        .par_iter()
-        .map(|(path, schema_name, data)| {
-            validators::validate_json(data, schema_name, &dataset.schema_cache, Some(path))
-        })
-        .collect();
-    for r in json_results {
-        result.merge_from(&r);
-    }
+/// Load the dataset from `data_dir`, `stores_dir` and `schemas_dir` and validate it,
+/// also returning the loaded `DataSet`, so callers that go on to process the parsed JSON
+/// don't walk and parse the directories a second time.
+#[cfg(feature = "filesystem")]
+pub fn validate_all_with_dataset(
+    data_dir: &Path,
+    stores_dir: &Path,
+    schemas_dir: &Path,
+    options: &ValidationOptions,
+) -> (ValidationResult, DataSet) {
+    let dataset = DataSet::from_directories(data_dir, stores_dir, schemas_dir);
+    let result = validate_dataset_with_options(&dataset, options);
+    (result, dataset)
+}
 
-    // 3. Logo validation (parallel)
-    let logo_results: Vec<ValidationResult> = dataset.logo_entries
-        .par_iter()
-        .map(|(path, filename, bytes, logo_name)| {
-            if bytes.is_empty() {
-                // File was not found on disk
-                let mut r = ValidationResult::default();
-                r.add(crate::types::ValidationError::error(
-                    "Logo",
-                    "Logo file not found",
-                    Some(path.clone()),
-                ));
-                r
-            } else {
-                validators::validate_logo(bytes, filename, logo_name.as_deref(), Some(path))
+/// Like `validate_dataset_with_options`, but yields issues lazily instead of collecting
+/// them into one `ValidationResult`. Each validator runs when the previous one's issues
+/// have been consumed, so only one stage's issues are held in memory at a time, in the
+/// same order `validate_dataset_with_options` reports them. Passing paths are not tracked.
+pub fn validate_dataset_iter<'a>(
+    dataset: &'a DataSet,
+    options: &ValidationOptions,
+) -> impl Iterator<Item = ValidationError> + 'a {
+    let fail_fast = options.fail_fast;
+    let mut failed = false;
+    builtin_validators(options)
+        .into_iter()
+        .map_while(move |validator| {
+            if failed {
+                return None;
             }
+            let errors = validator.validate(dataset).errors;
+            failed = fail_fast && errors.iter().any(|e| e.level == ValidationLevel::Error);
+            Some(errors)
         })
-        .collect();
-    for r in logo_results {
-        result.merge_from(&r);
-    }
+        .flatten()
+}
 
-    // 4. Folder name validation (parallel)
-    let folder_results: Vec<ValidationResult> = dataset.folder_entries
-        .par_iter()
-        .map(|(path, folder_name, json_data, json_key)| {
-            validators::validate_folder_name(folder_name, json_data, json_key, Some(path))
-        })
-        .collect();
-    for r in folder_results {
-        result.merge_from(&r);
+/// Run the built-in validations configured by `options`, then each of `extra` in order,
+/// combining everything into one result. With `options.fail_fast`, stops after the first
+/// validator that reports an error. `options.post_hooks` run on the combined result.
+pub fn validate_dataset_with_validators(
+    dataset: &DataSet,
+    options: &ValidationOptions,
+    extra: &[Box<dyn Validator>],
+) -> ValidationResult {
+    let builtins = builtin_validators(options);
+    let mut results = Vec::new();
+    for validator in builtins.iter().chain(extra) {
+        let result = validator.validate(dataset);
+        let failed = !result.is_valid();
+        results.push(result);
+        if options.fail_fast && failed {
+            break;
+        }
     }
-
-    // 5. Store ID validation
-    let sizes_refs: Vec<(&str, &Value)> = dataset.sizes_entries
-        .iter()
-        .map(|(p, v)| (p.as_str(), v))
-        .collect();
-    result.merge_from(&validators::validate_store_ids(&dataset.valid_store_ids, &sizes_refs));
-
-    // 6. GTIN/EAN validation
-    result.merge_from(&validators::validate_gtin_ean(&sizes_refs));
-
+    let mut result = ValidationResult::merge_all(results);
+    options.post_hooks.run(&mut result);
     result
 }
@@ -0,0 +1,699 @@
+use std::collections::{BTreeMap, HashSet};
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::options::{GtinOptions, JsonOptions, LogoOptions, ValidationOptions};
+use crate::orchestrator::{validate_json_entry, validate_logo_entry, DataSet};
+use crate::types::{ValidationLevel, ValidationResult};
+use crate::validators;
+
+/// A check run over a whole `DataSet`.
+///
+/// The built-in checks are implementations of this trait (see `builtin_validators`);
+/// project-specific checks can be passed to `validate_dataset_with_validators` to run
+/// alongside them.
+pub trait Validator: Send + Sync {
+    /// Short name identifying the check, e.g. "GTIN". Built-ins use it as their key in
+    /// `ValidationResult::passing`.
+    fn name(&self) -> &str;
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult;
+}
+
+/// The built-in validators configured by `options`, in the order `validate_dataset_with_options`
+/// runs them. Opt-in checks that `options` leaves disabled are not included.
+pub fn builtin_validators(options: &ValidationOptions) -> Vec<Box<dyn Validator>> {
+    let include_passing = options.include_passing;
+    let mut list: Vec<Box<dyn Validator>> = vec![
+        Box::new(RequiredFiles { empty_dir_level: options.empty_dir_level }),
+        Box::new(JsonSchemas {
+            json: options.json.clone(),
+            schema_preflight: options.schema_preflight,
+            include_passing,
+        }),
+        Box::new(Logos { logo: options.logo.clone(), include_passing }),
+        Box::new(FolderNames { include_passing }),
+        Box::new(StoreIds {
+            missing_store_id_level: options.missing_store_id_level,
+            store_ref_field: options.store_ref_field.clone(),
+            brand_without_store_level: options.brand_without_store_level,
+            store_brand_coverage: options.store_brand_coverage,
+            include_passing,
+        }),
+        Box::new(PurchaseLinks {
+            fields: options.purchase_link_fields.clone(),
+            max_links: options.max_purchase_links,
+            include_passing,
+        }),
+        Box::new(Gtins { gtin: options.gtin.clone(), include_passing }),
+        Box::new(Availability { field: options.availability_field.clone(), include_passing }),
+        Box::new(StoreMetadata { include_passing }),
+        Box::new(Ids { id_format: options.id_format.clone(), include_passing }),
+        Box::new(MaterialNames { include_passing }),
+        Box::new(BrandNames { include_passing }),
+        Box::new(ColorNames { include_passing }),
+    ];
+    if options.schema_preflight {
+        list.insert(0, Box::new(SchemaPreflight));
+    }
+    if let Some(range) = options.weight_range {
+        list.push(Box::new(WeightRange { range, include_passing }));
+    }
+    if options.diameter_consistency {
+        list.push(Box::new(Diameters { include_passing }));
+    }
+    if let Some(limits) = options.path_length_limits {
+        list.push(Box::new(PathLengths { limits, include_passing }));
+    }
+    if let Some(pattern) = &options.sizes_version {
+        list.push(Box::new(SizesVersion { pattern: pattern.clone(), include_passing }));
+    }
+    list.push(Box::new(Whitespace { fields: options.whitespace_fields.clone(), include_passing }));
+    list.push(Box::new(FieldLengths { limits: options.field_length_limits.clone(), include_passing }));
+    list.push(Box::new(NumericFormat {
+        number_fields: options.number_fields.clone(),
+        text_fields: options.text_fields.clone(),
+        include_passing,
+    }));
+    if !options.conditional_rules.is_empty() {
+        list.push(Box::new(ConditionalRules { rules: options.conditional_rules.clone(), include_passing }));
+    }
+    list
+}
+
+/// Run only the JSON schema stage of `validate_dataset_with_options`: a schema that is
+/// missing or fails to compile is reported once, and the files using it are skipped.
+pub fn validate_json_schemas(dataset: &DataSet, json: &JsonOptions) -> ValidationResult {
+    JsonSchemas { json: json.clone(), schema_preflight: false, include_passing: false }.validate(dataset)
+}
+
+/// Paths from `entries` that have no issue reported against them in `stage`.
+fn unflagged_paths(entries: &[(&str, &Value)], stage: &ValidationResult) -> Vec<String> {
+    let flagged: HashSet<&str> = stage.errors.iter().filter_map(|e| e.path.as_deref()).collect();
+    entries
+        .iter()
+        .filter(|(p, _)| !flagged.contains(p))
+        .map(|(p, _)| p.to_string())
+        .collect()
+}
+
+/// Record the unflagged `entries` as passing `name`, when requested.
+fn with_passing(
+    mut result: ValidationResult,
+    name: &str,
+    entries: &[(&str, &Value)],
+    include_passing: bool,
+) -> ValidationResult {
+    if include_passing {
+        let passing = unflagged_paths(entries, &result);
+        result.add_passing(name, passing);
+    }
+    result
+}
+
+fn sizes_refs(dataset: &DataSet) -> Vec<(&str, &Value)> {
+    dataset.sizes_entries.iter().map(|(p, v)| (p.as_str(), v)).collect()
+}
+
+/// JSON entries validated against one of `schemas`.
+fn json_refs<'a>(dataset: &'a DataSet, schemas: &[&str]) -> Vec<(&'a str, &'a Value)> {
+    dataset
+        .json_entries
+        .iter()
+        .filter(|(_, schema_name, _)| schemas.contains(&schema_name.as_str()))
+        .map(|(p, _, v)| (p.as_str(), v))
+        .collect()
+}
+
+/// Schema files that are missing or unreadable (opt-in).
+struct SchemaPreflight;
+
+impl Validator for SchemaPreflight {
+    fn name(&self) -> &str {
+        "Schema"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        dataset.schema_cache.preflight()
+    }
+}
+
+/// Missing files and empty material/filament directories, plus anything that went wrong
+/// while loading.
+struct RequiredFiles {
+    empty_dir_level: Option<ValidationLevel>,
+}
+
+impl Validator for RequiredFiles {
+    fn name(&self) -> &str {
+        "Missing File"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let mut result = validators::validate_required_files(&dataset.file_manifest);
+        if let Some(level) = self.empty_dir_level {
+            result.merge_from(&validators::validate_empty_directories(&dataset.file_manifest, level));
+        }
+        for issue in &dataset.load_issues {
+            result.add(issue.clone());
+        }
+        result
+    }
+}
+
+/// JSON schema validation (parallel), plus problems with the schemas themselves such as
+/// duplicate `$id`s.
+struct JsonSchemas {
+    json: JsonOptions,
+    schema_preflight: bool,
+    include_passing: bool,
+}
+
+impl Validator for JsonSchemas {
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let cache = &dataset.schema_cache;
+        let schema_names = dataset.json_entries.iter().map(|(_, s, _)| s.as_str());
+        // Files whose schema the preflight already reported missing are skipped quietly
+        let preflighted: HashSet<&str> = match self.schema_preflight {
+            true => schema_names
+                .clone()
+                .filter(|name| cache.get(name).is_none() && cache.is_preflighted(name))
+                .collect(),
+            false => HashSet::new(),
+        };
+        // Any other missing or broken schema is reported a single time and the files
+        // using it are skipped, rather than repeating its error per file
+        let (unusable, schema_errors) =
+            validators::unusable_schemas(schema_names.filter(|name| !preflighted.contains(name)), cache);
+
+        let checked: Vec<&(String, String, Value)> = dataset.json_entries
+            .iter()
+            .filter(|(_, schema_name, _)| {
+                !unusable.contains(schema_name.as_str()) && !preflighted.contains(schema_name.as_str())
+            })
+            .collect();
+        let results: Vec<ValidationResult> = checked
+            .par_iter()
+            .map(|(path, schema_name, data)| {
+                let source = dataset.json_sources.get(path.as_str()).map(String::as_str);
+                validate_json_entry(path, schema_name, data, source, &dataset.schema_cache, &self.json)
+            })
+            .collect();
+        let mut passing = Vec::new();
+        if self.include_passing {
+            passing = checked.iter()
+                .zip(&results)
+                .filter(|(_, r)| r.errors.is_empty())
+                .map(|((path, _, _), _)| path.clone())
+                .collect();
+        }
+        let mut result = ValidationResult::merge_all(results);
+        result.add_passing(self.name(), passing);
+        result.merge_from(&schema_errors);
+        // Already part of the preflight when that runs
+        if !self.schema_preflight {
+            for warning in dataset.schema_cache.warnings() {
+                result.add(warning);
+            }
+        }
+        result
+    }
+}
+
+/// Logo validation (parallel).
+struct Logos {
+    logo: LogoOptions,
+    include_passing: bool,
+}
+
+impl Validator for Logos {
+    fn name(&self) -> &str {
+        "Logo"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let results: Vec<ValidationResult> = dataset.logo_entries
+            .par_iter()
+            .map(|(path, filename, bytes, logo_name, context)| {
+                validate_logo_entry(path, filename, bytes, logo_name.as_deref(), *context, &self.logo)
+            })
+            .collect();
+        let mut passing = Vec::new();
+        if self.include_passing {
+            passing = dataset.logo_entries.iter()
+                .zip(&results)
+                .filter(|(_, r)| r.errors.is_empty())
+                .map(|((path, _, _, _, _), _)| path.clone())
+                .collect();
+        }
+        let mut result = ValidationResult::merge_all(results);
+        result.add_passing(self.name(), passing);
+        if self.logo.consistent_brand_format {
+            let entries: Vec<(&str, &Value)> =
+                dataset.json_entries.iter().map(|(path, _, data)| (path.as_str(), data)).collect();
+            result.merge_from(&validators::validate_logo_format_consistency(
+                &json_refs(dataset, &["brand"]),
+                &entries,
+            ));
+        }
+        result
+    }
+}
+
+/// Folder name validation (parallel).
+struct FolderNames {
+    include_passing: bool,
+}
+
+impl Validator for FolderNames {
+    fn name(&self) -> &str {
+        "Folder"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let results: Vec<ValidationResult> = dataset.folder_entries
+            .par_iter()
+            .map(|(path, folder_name, json_data, json_key)| {
+                validators::validate_folder_name(folder_name, json_data, json_key, Some(path))
+            })
+            .collect();
+        let mut passing = Vec::new();
+        if self.include_passing {
+            passing = dataset.folder_entries.iter()
+                .zip(&results)
+                .filter(|(_, r)| r.errors.is_empty())
+                .map(|((path, _, _, _), _)| path.clone())
+                .collect();
+        }
+        let mut result = ValidationResult::merge_all(results);
+        result.add_passing(self.name(), passing);
+        result
+    }
+}
+
+/// Store ID validation, including path-style store references, plus stores that don't
+/// carry the owning brand and brands no store sells (both opt-in).
+struct StoreIds {
+    missing_store_id_level: ValidationLevel,
+    store_ref_field: Option<String>,
+    brand_without_store_level: Option<ValidationLevel>,
+    store_brand_coverage: bool,
+    include_passing: bool,
+}
+
+impl Validator for StoreIds {
+    fn name(&self) -> &str {
+        "StoreID"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let mut result = validators::validate_store_ids(&dataset.valid_store_ids, &sizes);
+        result.merge_from(&validators::validate_missing_store_ids(
+            &sizes,
+            self.missing_store_id_level,
+            self.store_ref_field.as_deref(),
+        ));
+        if let Some(field) = &self.store_ref_field {
+            let store_dirs: Vec<&str> = dataset.file_manifest.stores.iter().map(|s| s.path.as_str()).collect();
+            result.merge_from(&validators::validate_store_refs(&store_dirs, &sizes, field));
+        }
+        // A brand's other sizes.json files are missing from a changed-only dataset
+        if !dataset.changed_only {
+            let brands = json_refs(dataset, &["brand"]);
+            if self.store_brand_coverage {
+                result.merge_from(&validators::validate_store_brand_coverage(
+                    &json_refs(dataset, &["store"]),
+                    &brands,
+                    &sizes,
+                ));
+            }
+            if let Some(level) = self.brand_without_store_level {
+                result.merge_from(&validators::validate_brand_has_store(
+                    &brands,
+                    &sizes,
+                    self.store_ref_field.as_deref(),
+                    level,
+                ));
+            }
+        }
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Purchase link completeness and duplicates, plus the per-size link cap (opt-in).
+struct PurchaseLinks {
+    fields: Vec<String>,
+    max_links: Option<usize>,
+    include_passing: bool,
+}
+
+impl Validator for PurchaseLinks {
+    fn name(&self) -> &str {
+        "PurchaseLink"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let mut result = validators::validate_purchase_link_completeness(&sizes, &self.fields);
+        result.merge_from(&validators::validate_duplicate_purchase_links(&sizes));
+        if let Some(max) = self.max_links {
+            result.merge_from(&validators::validate_purchase_link_count(&sizes, max));
+        }
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// GTIN/EAN validation, including codes shared across brands.
+struct Gtins {
+    gtin: GtinOptions,
+    include_passing: bool,
+}
+
+impl Validator for Gtins {
+    fn name(&self) -> &str {
+        "GTIN"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let mut result = validators::validate_gtin_ean_with_options(&sizes, &self.gtin);
+        result.merge_from(&validators::validate_gtin_brand_conflict(&json_refs(dataset, &["brand"]), &sizes));
+        if self.gtin.require_barcode {
+            result.merge_from(&validators::validate_barcode_presence(&sizes));
+        }
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Availability vs purchase links.
+struct Availability {
+    field: String,
+    include_passing: bool,
+}
+
+impl Validator for Availability {
+    fn name(&self) -> &str {
+        "Availability"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let result = validators::validate_availability(&sizes, &self.field);
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Implausible size weights (opt-out).
+struct WeightRange {
+    range: (f64, f64),
+    include_passing: bool,
+}
+
+impl Validator for WeightRange {
+    fn name(&self) -> &str {
+        "Weight"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let result = validators::validate_weight_range(&sizes, self.range);
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Sizes within one sizes.json listing different diameters (opt-in).
+struct Diameters {
+    include_passing: bool,
+}
+
+impl Validator for Diameters {
+    fn name(&self) -> &str {
+        "Diameter"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let result = validators::validate_variant_diameter_consistency(&sizes);
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Folder names and paths too long for some filesystems.
+struct PathLengths {
+    limits: (usize, usize),
+    include_passing: bool,
+}
+
+impl Validator for PathLengths {
+    fn name(&self) -> &str {
+        "Path"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let folders: Vec<(&str, &Value)> =
+            dataset.folder_entries.iter().map(|(path, _, data, _)| (path.as_str(), data)).collect();
+        let paths: Vec<&str> = folders.iter().map(|(path, _)| *path).collect();
+        let result = validators::validate_path_lengths(&paths, self.limits.0, self.limits.1);
+        with_passing(result, self.name(), &folders, self.include_passing)
+    }
+}
+
+/// Store metadata validation.
+struct StoreMetadata {
+    include_passing: bool,
+}
+
+impl Validator for StoreMetadata {
+    fn name(&self) -> &str {
+        "Store"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let stores = json_refs(dataset, &["store"]);
+        let result = validators::validate_store_metadata(&stores);
+        with_passing(result, self.name(), &stores, self.include_passing)
+    }
+}
+
+/// ID validation: slug safety, plus the configured format for ids that pass it.
+struct Ids {
+    id_format: Option<Regex>,
+    include_passing: bool,
+}
+
+impl Validator for Ids {
+    fn name(&self) -> &str {
+        "ID"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let entries = json_refs(dataset, &["store", "brand", "filament", "variant"]);
+        let mut result = validators::validate_id_slug(&entries);
+        if let Some(pattern) = &self.id_format {
+            let flagged: HashSet<&str> = result.errors.iter().filter_map(|e| e.path.as_deref()).collect();
+            let clean: Vec<(&str, &Value)> =
+                entries.iter().filter(|(path, _)| !flagged.contains(path)).copied().collect();
+            result.merge_from(&validators::validate_id_format(&clean, pattern));
+        }
+        with_passing(result, self.name(), &entries, self.include_passing)
+    }
+}
+
+/// Material naming vs material_types; skipped when that schema isn't loaded.
+struct MaterialNames {
+    include_passing: bool,
+}
+
+impl Validator for MaterialNames {
+    fn name(&self) -> &str {
+        "Material"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let Some(types_schema) = dataset.schema_cache.get("material_types") else {
+            return ValidationResult::default();
+        };
+        let canonical = validators::material_type_names(types_schema);
+        let materials = json_refs(dataset, &["material"]);
+        let result = validators::validate_material_names(&materials, &canonical);
+        with_passing(result, self.name(), &materials, self.include_passing)
+    }
+}
+
+/// Brands sharing a display name.
+struct BrandNames {
+    include_passing: bool,
+}
+
+impl Validator for BrandNames {
+    fn name(&self) -> &str {
+        "Brand"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let brands = json_refs(dataset, &["brand"]);
+        let result = validators::validate_brand_name_uniqueness(&brands);
+        with_passing(result, self.name(), &brands, self.include_passing)
+    }
+}
+
+/// Variant color names spelled inconsistently across the dataset.
+struct ColorNames {
+    include_passing: bool,
+}
+
+impl Validator for ColorNames {
+    fn name(&self) -> &str {
+        "Color"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let variants = json_refs(dataset, &["variant"]);
+        let result = validators::validate_color_name_consistency(&variants);
+        with_passing(result, self.name(), &variants, self.include_passing)
+    }
+}
+
+/// sizes.json version marker (opt-in).
+struct SizesVersion {
+    pattern: Regex,
+    include_passing: bool,
+}
+
+impl Validator for SizesVersion {
+    fn name(&self) -> &str {
+        "Version"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let sizes = sizes_refs(dataset);
+        let result = validators::validate_required_version(&sizes, &self.pattern);
+        with_passing(result, self.name(), &sizes, self.include_passing)
+    }
+}
+
+/// Whitespace in string values.
+struct Whitespace {
+    fields: Vec<String>,
+    include_passing: bool,
+}
+
+impl Validator for Whitespace {
+    fn name(&self) -> &str {
+        "Whitespace"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let entries: Vec<(&str, &Value)> = dataset.json_entries
+            .iter()
+            .map(|(p, _, v)| (p.as_str(), v))
+            .collect();
+        let result = validators::validate_whitespace_in_values(&entries, &self.fields);
+        with_passing(result, self.name(), &entries, self.include_passing)
+    }
+}
+
+/// Overlong string values.
+struct FieldLengths {
+    limits: BTreeMap<String, usize>,
+    include_passing: bool,
+}
+
+impl Validator for FieldLengths {
+    fn name(&self) -> &str {
+        "Content"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let entries: Vec<(&str, &Value)> = dataset.json_entries
+            .iter()
+            .map(|(p, _, v)| (p.as_str(), v))
+            .collect();
+        let result = validators::validate_field_lengths(&entries, &self.limits);
+        with_passing(result, self.name(), &entries, self.include_passing)
+    }
+}
+
+/// Opt-in if/then rules between fields.
+struct ConditionalRules {
+    rules: Vec<validators::ConditionalRule>,
+    include_passing: bool,
+}
+
+impl Validator for ConditionalRules {
+    fn name(&self) -> &str {
+        "Conditional"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let entries: Vec<(&str, &str, &Value)> = dataset.json_entries
+            .iter()
+            .map(|(p, s, v)| (p.as_str(), s.as_str(), v))
+            .collect();
+        let sizes = sizes_refs(dataset);
+        let result = validators::validate_conditional_rules(&entries, &sizes, &self.rules);
+        // The files some rule applies to
+        let mut checked: Vec<(&str, &Value)> = entries
+            .iter()
+            .filter(|(_, schema, _)| *schema != "sizes" && self.rules.iter().any(|r| r.schema == *schema))
+            .map(|(path, _, data)| (*path, *data))
+            .collect();
+        if self.rules.iter().any(|r| r.schema == "sizes") {
+            checked.extend(sizes);
+        }
+        with_passing(result, self.name(), &checked, self.include_passing)
+    }
+}
+
+/// Numbers stored as strings and vice versa.
+struct NumericFormat {
+    number_fields: Vec<String>,
+    text_fields: Vec<String>,
+    include_passing: bool,
+}
+
+impl Validator for NumericFormat {
+    fn name(&self) -> &str {
+        "Format"
+    }
+
+    fn validate(&self, dataset: &DataSet) -> ValidationResult {
+        let entries: Vec<(&str, &Value)> = dataset.json_entries
+            .iter()
+            .map(|(p, _, v)| (p.as_str(), v))
+            .collect();
+        let result = validators::validate_numeric_format(&entries, &self.number_fields, &self.text_fields);
+        with_passing(result, self.name(), &entries, self.include_passing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn missing_schema_is_reported_once_for_all_its_files() {
+        let files: BTreeMap<String, Vec<u8>> = [
+            ("data/acme/brand.json", br#"{"id": "acme", "name": "Acme"}"#.to_vec()),
+            ("data/other/brand.json", br#"{"id": "other", "name": "Other"}"#.to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| (path.to_string(), bytes))
+        .collect();
+        let dataset = DataSet::from_memory(files);
+
+        let result = validate_json_schemas(&dataset, &JsonOptions::default());
+        assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+        assert_eq!(result.errors[0].category, crate::codes::SCHEMA);
+        assert!(result.errors[0].message.contains("2 files"), "{}", result.errors[0].message);
+    }
+}
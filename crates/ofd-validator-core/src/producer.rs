@@ -0,0 +1,505 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::codes;
+use crate::types::ValidationError;
+use crate::util::{
+    brand_dir_matches, file_name_on_disk, find_store_dirs, json5_warning, logo_asset_dir, logo_escape_error,
+    logo_ref_escapes, normalize_dir, normalize_logo_ref, parse_json_relaxed,
+};
+use crate::validators::LogoContext;
+
+/// A unit of validation work discovered while walking the data and stores directories.
+/// Each variant maps onto one of the `DataSet` entry lists.
+#[derive(Debug)]
+pub enum Task {
+    /// A JSON file to validate against `schema_name`; `source` is its text, for locating
+    /// schema failures
+    Json { path: String, schema_name: String, data: Value, source: String },
+    /// A logo referenced by brand.json/store.json; `bytes` is empty when the file can't be read
+    Logo { path: String, filename: String, bytes: Vec<u8>, logo_name: Option<String>, context: LogoContext },
+    /// A folder whose name must match `json_key` in its JSON file
+    Folder { path: String, folder_name: String, data: Value, json_key: String },
+    /// A sizes.json file, checked for GTIN/EAN and store IDs
+    Sizes { path: String, data: Value },
+    /// A store ID declared by a store.json file
+    StoreId(String),
+    /// A problem found while loading rather than validating: a file or directory that
+    /// still couldn't be read after retrying ("IO"), or a file that needed JSON5
+    LoadIssue(ValidationError),
+}
+
+/// How often to retry a directory listing or file read that fails with a transient
+/// error, as happens occasionally on network filesystems.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Total number of tries, including the first one
+    pub attempts: u32,
+    /// Sleep before the first retry; doubled for each retry after that
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Run `op` until it succeeds, fails with a non-transient error, or runs out of attempts.
+    pub fn run<T, F: FnMut() -> io::Result<T>>(&self, mut op: F) -> io::Result<T> {
+        let mut delay = self.delay;
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(e) if attempt < self.attempts && is_transient(&e) => {
+                    thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Errors that may go away by trying again.
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// How the data directory is organized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayoutStrategy {
+    /// `<brand>/<material>/<filament>/<variant>/` folders holding brand.json, material.json,
+    /// filament.json, and variant.json with sizes.json
+    #[default]
+    Nested,
+    /// Every data file directly in the data directory, named `<name>.<schema>.json`
+    /// (e.g. `acme.brand.json` or `acme-pla-red.sizes.json`). There are no folder names
+    /// or required files to check, and brand logos are resolved against the data directory.
+    Flat,
+}
+
+impl std::str::FromStr for LayoutStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "nested" => Ok(Self::Nested),
+            "flat" => Ok(Self::Flat),
+            _ => Err(format!("Unknown layout '{}'; expected 'nested' or 'flat'", name)),
+        }
+    }
+}
+
+impl LayoutStrategy {
+    /// The schema a file in a flat layout is validated against, taken from the name
+    /// segment before `.json`. `None` for files that aren't data files.
+    pub fn flat_schema_name(file_name: &str) -> Option<&'static str> {
+        let (name, schema) = file_name.strip_suffix(".json")?.rsplit_once('.')?;
+        if name.is_empty() {
+            return None;
+        }
+        ["brand", "material", "filament", "variant", "sizes"].into_iter().find(|s| *s == schema)
+    }
+}
+
+/// Walks the data and stores directories, emitting `Task`s as each directory is read
+/// rather than collecting the whole tree first.
+///
+/// Tasks are emitted in the same order `DataSet::from_directories` stores them: the
+/// brand hierarchy, then stores, then any sizes.json outside the expected variant depth.
+pub struct TaskProducer {
+    /// `None` when only the stores subtree is walked
+    data_dir: Option<PathBuf>,
+    stores_dir: PathBuf,
+    retry: RetryPolicy,
+    /// Walk only this brand (see `only_brand`)
+    brand: Option<String>,
+    layout: LayoutStrategy,
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn io_error(path: &Path, what: &str, e: &io::Error) -> Task {
+    Task::LoadIssue(ValidationError::error(
+        codes::IO,
+        format!("Could not read {}: {}", what, e),
+        Some(path.to_string_lossy().to_string()),
+    ))
+}
+
+impl TaskProducer {
+    pub fn new(data_dir: &Path, stores_dir: &Path) -> Self {
+        Self {
+            data_dir: Some(normalize_dir(data_dir)),
+            stores_dir: normalize_dir(stores_dir),
+            retry: RetryPolicy::default(),
+            brand: None,
+            layout: LayoutStrategy::default(),
+        }
+    }
+
+    /// A producer that walks only `stores_dir`, skipping the brand hierarchy.
+    pub fn stores_only(stores_dir: &Path) -> Self {
+        Self {
+            data_dir: None,
+            stores_dir: normalize_dir(stores_dir),
+            retry: RetryPolicy::default(),
+            brand: None,
+            layout: LayoutStrategy::default(),
+        }
+    }
+
+    /// Walk only the brand folder matching `brand` (see `util::brand_dir_matches`). Store
+    /// directories are then only read for their ids, emitted as `Task::StoreId`, so
+    /// purchase links can still be checked against every store.
+    pub fn only_brand(mut self, brand: &str) -> Self {
+        self.brand = Some(brand.to_string());
+        self
+    }
+
+    /// Discover data files according to `layout` instead of the nested hierarchy.
+    /// `only_brand` has no effect on a flat layout.
+    pub fn with_layout(mut self, layout: LayoutStrategy) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Use `retry` instead of `RetryPolicy::default()` for directory listings and file reads.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Walk the tree, calling `emit` for every task as soon as it is discovered.
+    pub fn for_each<F: FnMut(Task)>(&self, mut emit: F) {
+        // sizes.json files already emitted at variant depth
+        let mut seen_sizes: HashSet<String> = HashSet::new();
+
+        let data_dir = self.data_dir.as_deref();
+
+        let brand_dirs = match (data_dir, self.layout) {
+            (Some(dir), LayoutStrategy::Nested) => self.emit_nested(dir, &mut seen_sizes, &mut emit),
+            (Some(dir), LayoutStrategy::Flat) => {
+                self.emit_flat(dir, &mut seen_sizes, &mut emit);
+                Vec::new()
+            }
+            (None, _) => Vec::new(),
+        };
+
+        let store_dirs = find_store_dirs(
+            &self.stores_dir,
+            |dir| self.subdirs(dir, &mut emit),
+            |dir| dir.join("store.json").exists(),
+        );
+        for store_dir in store_dirs {
+            if self.brand.is_some() {
+                let store_id = self
+                    .load_json(&store_dir.join("store.json"), &mut emit)
+                    .and_then(|(data, _)| data.get("id")?.as_str().map(str::to_string));
+                if let Some(id) = store_id {
+                    emit(Task::StoreId(id));
+                }
+                continue;
+            }
+            self.emit_entity(&store_dir, "store.json", "store", "id", Some(LogoContext::Store), &mut emit);
+        }
+
+        // Catch sizes.json files at unexpected locations so gtin/store_id checks still see them
+        let Some(data_dir) = data_dir else {
+            return;
+        };
+        let roots = match self.brand {
+            Some(_) => brand_dirs,
+            None => vec![data_dir.to_path_buf()],
+        };
+        for entry in roots.iter().flat_map(WalkDir::new) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    // Directories the hierarchy walk could list were already retried there
+                    if let (Some(path), Some(err)) = (e.path(), e.io_error()) {
+                        if err.kind() != io::ErrorKind::NotFound {
+                            emit(io_error(path, "directory", err));
+                        }
+                    }
+                    continue;
+                }
+            };
+            if entry.file_name() == "sizes.json" {
+                let path = entry.path().to_string_lossy().to_string();
+                if !seen_sizes.contains(&path) {
+                    if let Some((data, _)) = self.load_json(entry.path(), &mut emit) {
+                        emit(Task::Sizes { path, data });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run the walk on a background thread, returning a channel of tasks.
+    /// At most `bound` tasks are buffered, so a slow consumer throttles the walk
+    /// instead of letting the whole tree pile up in memory.
+    pub fn spawn(self, bound: usize) -> (Receiver<Task>, JoinHandle<()>) {
+        let (tx, rx) = mpsc::sync_channel(bound);
+        let handle = thread::spawn(move || {
+            // If the receiver is dropped the remaining tasks are simply discarded
+            let mut open = true;
+            self.for_each(|task| {
+                if open && tx.send(task).is_err() {
+                    open = false;
+                }
+            });
+        });
+        (rx, handle)
+    }
+
+    /// List the subdirectories of `dir`, retrying transient errors. A missing directory
+    /// yields nothing; any other failure is emitted as an "IO" `LoadIssue`.
+    fn subdirs<F: FnMut(Task)>(&self, dir: &Path, emit: &mut F) -> Vec<PathBuf> {
+        let listing = self.retry.run(|| {
+            std::fs::read_dir(dir)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<io::Result<Vec<PathBuf>>>()
+        });
+        match listing {
+            Ok(paths) => paths.into_iter().filter(|p| p.is_dir()).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                emit(io_error(dir, "directory", &e));
+                Vec::new()
+            }
+        }
+    }
+
+    /// Read and parse a JSON file, retrying transient errors. Read failures other than
+    /// a missing file, and files that needed JSON5, are emitted as a `LoadIssue`;
+    /// unparseable JSON is skipped.
+    fn load_json<F: FnMut(Task)>(&self, path: &Path, emit: &mut F) -> Option<(Value, String)> {
+        match self.retry.run(|| std::fs::read_to_string(path)) {
+            Ok(content) => {
+                let (value, used_json5) = parse_json_relaxed(&content, path)?;
+                if used_json5 {
+                    emit(Task::LoadIssue(json5_warning(&path.to_string_lossy())));
+                }
+                Some((value, content))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                emit(io_error(path, "file", &e));
+                None
+            }
+        }
+    }
+
+    /// Emit the brand/material/filament/variant hierarchy under `data_dir`, returning the
+    /// brand directories walked.
+    fn emit_nested<F: FnMut(Task)>(
+        &self,
+        data_dir: &Path,
+        seen_sizes: &mut HashSet<String>,
+        emit: &mut F,
+    ) -> Vec<PathBuf> {
+        let mut brand_dirs = self.subdirs(data_dir, emit);
+        if let Some(brand) = &self.brand {
+            brand_dirs.retain(|dir| brand_dir_matches(dir, brand));
+        }
+        for brand_dir in &brand_dirs {
+            let brand_data = self.emit_entity(brand_dir, "brand.json", "brand", "id", Some(LogoContext::Brand), emit);
+            // A subfolder holding the brand logo (e.g. "assets/logo.png") is not a material
+            let asset_dir = brand_data.as_ref().and_then(logo_asset_dir);
+
+            for material_dir in self.subdirs(brand_dir, emit) {
+                if asset_dir.is_some_and(|d| material_dir.file_name().is_some_and(|f| f == d)) {
+                    continue;
+                }
+                self.emit_entity(&material_dir, "material.json", "material", "material", None, emit);
+
+                for filament_dir in self.subdirs(&material_dir, emit) {
+                    self.emit_entity(&filament_dir, "filament.json", "filament", "id", None, emit);
+
+                    for variant_dir in self.subdirs(&filament_dir, emit) {
+                        self.emit_entity(&variant_dir, "variant.json", "variant", "id", None, emit);
+
+                        let sizes_file = variant_dir.join("sizes.json");
+                        if sizes_file.exists() {
+                            self.emit_sizes(&sizes_file, seen_sizes, emit);
+                        }
+                    }
+                }
+            }
+        }
+        brand_dirs
+    }
+
+    /// Emit the `<name>.<schema>.json` files directly in `data_dir`, in file name order
+    /// (see `LayoutStrategy::Flat`).
+    fn emit_flat<F: FnMut(Task)>(&self, data_dir: &Path, seen_sizes: &mut HashSet<String>, emit: &mut F) {
+        let listing = self.retry.run(|| {
+            std::fs::read_dir(data_dir)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<io::Result<Vec<PathBuf>>>()
+        });
+        let mut files = match listing {
+            Ok(paths) => paths.into_iter().filter(|p| p.is_file()).collect::<Vec<_>>(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                emit(io_error(data_dir, "directory", &e));
+                Vec::new()
+            }
+        };
+        files.sort();
+
+        for file in files {
+            match LayoutStrategy::flat_schema_name(&file_name_of(&file)) {
+                Some("sizes") => self.emit_sizes(&file, seen_sizes, emit),
+                Some(schema_name) => {
+                    let logo_context = (schema_name == "brand").then_some(LogoContext::Brand);
+                    self.emit_file(&file, data_dir, schema_name, logo_context, emit);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Emit the JSON and sizes tasks for a sizes file.
+    fn emit_sizes<F: FnMut(Task)>(&self, file: &Path, seen_sizes: &mut HashSet<String>, emit: &mut F) {
+        if let Some((data, source)) = self.load_json(file, emit) {
+            let path = file.to_string_lossy().to_string();
+            seen_sizes.insert(path.clone());
+            emit(Task::Json {
+                path: path.clone(),
+                schema_name: "sizes".to_string(),
+                data: data.clone(),
+                source,
+            });
+            emit(Task::Sizes { path, data });
+        }
+    }
+
+    /// Emit the JSON, logo and folder tasks for one entity directory, returning its data.
+    fn emit_entity<F: FnMut(Task)>(
+        &self,
+        dir: &Path,
+        json_file: &str,
+        schema_name: &str,
+        json_key: &str,
+        logo_context: Option<LogoContext>,
+        emit: &mut F,
+    ) -> Option<Value> {
+        let file = dir.join(json_file);
+        if !file.exists() {
+            return None;
+        }
+        let data = self.emit_file(&file, dir, schema_name, logo_context, emit)?;
+
+        emit(Task::Folder {
+            path: dir.to_string_lossy().to_string(),
+            folder_name: file_name_of(dir),
+            data: data.clone(),
+            json_key: json_key.to_string(),
+        });
+        Some(data)
+    }
+
+    /// Emit the JSON task for `file`, plus its store ID and its logo (resolved against
+    /// `dir`), returning the parsed data.
+    fn emit_file<F: FnMut(Task)>(
+        &self,
+        file: &Path,
+        dir: &Path,
+        schema_name: &str,
+        logo_context: Option<LogoContext>,
+        emit: &mut F,
+    ) -> Option<Value> {
+        let (data, source) = self.load_json(file, emit)?;
+
+        emit(Task::Json {
+            path: file.to_string_lossy().to_string(),
+            schema_name: schema_name.to_string(),
+            data: data.clone(),
+            source,
+        });
+
+        if schema_name == "store" {
+            if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+                emit(Task::StoreId(id.to_string()));
+            }
+        }
+
+        if let Some(context) = logo_context {
+            if let Some(logo_name) = data.get("logo").and_then(|v| v.as_str()) {
+                let normalized = normalize_logo_ref(logo_name);
+                // Never read a file outside the entity folder
+                if logo_ref_escapes(&normalized) {
+                    emit(Task::LoadIssue(logo_escape_error(logo_name, Some(&dir.to_string_lossy()))));
+                    return Some(data);
+                }
+                let logo_path = dir.join(normalized);
+                let task = match self.retry.run(|| std::fs::read(&logo_path)) {
+                    Ok(bytes) => Task::Logo {
+                        path: logo_path.to_string_lossy().to_string(),
+                        filename: file_name_on_disk(&logo_path),
+                        bytes,
+                        logo_name: Some(logo_name.to_string()),
+                        context,
+                    },
+                    // Empty bytes so the validator can report "file not found"
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => Task::Logo {
+                        path: logo_path.to_string_lossy().to_string(),
+                        filename: logo_name.to_string(),
+                        bytes: Vec::new(),
+                        logo_name: Some(logo_name.to_string()),
+                        context,
+                    },
+                    Err(e) => io_error(&logo_path, "file", &e),
+                };
+                emit(task);
+            }
+        }
+
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tries_until(kind: io::ErrorKind) -> u32 {
+        let policy = RetryPolicy { attempts: 3, delay: Duration::ZERO };
+        let mut tries = 0;
+        let _ = policy.run(|| -> io::Result<()> {
+            tries += 1;
+            Err(io::Error::from(kind))
+        });
+        tries
+    }
+
+    #[test]
+    fn retries_only_transient_errors() {
+        assert_eq!(tries_until(io::ErrorKind::Interrupted), 3);
+        assert_eq!(tries_until(io::ErrorKind::TimedOut), 3);
+        assert_eq!(tries_until(io::ErrorKind::NotFound), 1);
+        assert_eq!(tries_until(io::ErrorKind::Other), 1);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::types::{ValidationError, ValidationResult};
+
+/// Column headers written by `write_csv`.
+pub const CSV_HEADERS: [&str; 5] = ["level", "category", "code", "path", "message"];
+
+/// Write every issue in `result` as a CSV row, for filtering and sorting in a spreadsheet.
+///
+/// Columns are `level,category,code,path,message`. `code` is the category's stable
+/// identifier (`codes::identifier`, e.g. "MISSING_FILE"), empty for categories outside
+/// the registry; `path` is empty for issues without one.
+pub fn write_csv<W: Write>(result: &ValidationResult, writer: W) -> csv::Result<()> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer.write_record(CSV_HEADERS)?;
+    for error in &result.errors {
+        let code = crate::codes::identifier(&error.category).unwrap_or_default();
+        csv_writer.write_record([
+            error.level.to_string().as_str(),
+            error.category.as_str(),
+            code.as_str(),
+            error.path.as_deref().unwrap_or(""),
+            error.message.as_str(),
+        ])?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// `write_csv` into a string.
+pub fn to_csv(result: &ValidationResult) -> String {
+    let mut buf = Vec::new();
+    // Writing to a Vec can't fail
+    write_csv(result, &mut buf).expect("writing CSV to memory failed");
+    String::from_utf8(buf).expect("CSV output is valid UTF-8")
+}
+
+/// One issue in the grouped JSON report; `level` is "ERROR" or "WARNING" as in `to_dict`.
+#[derive(Serialize)]
+struct JsonIssue<'a> {
+    level: String,
+    category: &'a str,
+    message: &'a str,
+    path: Option<&'a str>,
+}
+
+impl<'a> From<&'a ValidationError> for JsonIssue<'a> {
+    fn from(e: &'a ValidationError) -> Self {
+        Self {
+            level: e.level.to_string(),
+            category: &e.category,
+            message: &e.message,
+            path: e.path.as_deref(),
+        }
+    }
+}
+
+/// Envelope written by `to_json_grouped`.
+#[derive(Serialize)]
+struct GroupedReport<'a> {
+    is_valid: bool,
+    error_count: usize,
+    warning_count: usize,
+    by_category: BTreeMap<&'a str, Vec<JsonIssue<'a>>>,
+}
+
+/// Render `result` as pretty-printed JSON with the issues grouped by category:
+/// `{"is_valid", "error_count", "warning_count", "by_category": {"Logo": [...], ...}}`.
+/// Categories are sorted; issues keep their original order within each category.
+pub fn to_json_grouped(result: &ValidationResult) -> String {
+    let summary = result.summary();
+    let report = GroupedReport {
+        is_valid: summary.errors == 0,
+        error_count: summary.errors,
+        warning_count: summary.warnings,
+        by_category: result
+            .group_by_category()
+            .into_iter()
+            .map(|(category, errors)| (category, errors.into_iter().map(JsonIssue::from).collect()))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&report).expect("serializing the report to JSON failed")
+}
+
+/// A small pass/fail status for CI artifacts and dashboards, separate from the full report.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StatusSummary {
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub is_valid: bool,
+    /// JSON files and logos checked (see `DataSet::file_count`)
+    pub files_validated: usize,
+    pub duration_ms: u128,
+}
+
+impl StatusSummary {
+    pub fn new(result: &ValidationResult, files_validated: usize, duration: Duration) -> Self {
+        let summary = result.summary();
+        Self {
+            error_count: summary.errors,
+            warning_count: summary.warnings,
+            is_valid: summary.errors == 0,
+            files_validated,
+            duration_ms: duration.as_millis(),
+        }
+    }
+
+    /// `{"error_count", "warning_count", "is_valid", "files_validated", "duration_ms"}`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing the summary to JSON failed")
+    }
+}
@@ -13,73 +13,354 @@ pub const SCHEMA_FILES: &[(&str, &str)] = &[
     ("sizes", "sizes_schema.json"),
 ];
 
+/// Combine the default `SCHEMA_FILES` with `extra` (name, filename) pairs.
+/// An extra entry overrides the default of the same name, or adds a new schema.
+pub fn merged_schema_files<'a>(extra: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+    let mut mapping: Vec<(&str, &str)> = SCHEMA_FILES.to_vec();
+    for (name, filename) in extra {
+        match mapping.iter_mut().find(|(n, _)| n == name) {
+            Some(existing) => existing.1 = filename,
+            None => mapping.push((name, filename)),
+        }
+    }
+    mapping
+}
+
+/// Fetches a `$ref` target the cache doesn't hold, e.g. an `https://` schema.
+/// Receives the URI without its fragment and returns the parsed schema or a reason.
+pub type RefResolver =
+    Arc<dyn Fn(&str) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> + Send + Sync>;
+
+/// Where a directory-backed cache was loaded from, so schemas can be reloaded.
+#[cfg(feature = "filesystem")]
+#[derive(Clone)]
+struct SchemaSource {
+    schemas_dir: std::path::PathBuf,
+    /// (name, filename) pairs inside `schemas_dir`
+    files: Vec<(String, String)>,
+}
+
 #[derive(Clone)]
 pub struct SchemaCache {
     /// Schemas indexed by name (e.g. "brand", "material")
     schemas_by_name: HashMap<String, Arc<Value>>,
     /// All schemas indexed by various URI keys for $ref resolution
     schemas_by_uri: HashMap<String, Arc<Value>>,
+    /// Set for caches built from a directory; `None` for `from_map`
+    #[cfg(feature = "filesystem")]
+    source: Option<SchemaSource>,
+    /// Fallback for `$ref` URIs not found in the cache; `None` makes them an error
+    ref_resolver: Option<RefResolver>,
 }
 
 impl SchemaCache {
     /// Create a schema cache from a directory on the filesystem.
     #[cfg(feature = "filesystem")]
     pub fn from_directory(schemas_dir: &std::path::Path) -> Self {
-        use crate::util::load_json;
+        Self::with_mapping(schemas_dir, &[])
+    }
 
-        let mut schemas_by_name = HashMap::new();
-        let mut schemas_by_uri = HashMap::new();
+    /// Create a schema cache from a directory, using `extra` (name, filename) pairs to
+    /// override default schema filenames or register additional schemas.
+    #[cfg(feature = "filesystem")]
+    pub fn with_mapping(schemas_dir: &std::path::Path, extra: &[(&str, &str)]) -> Self {
+        let source = SchemaSource {
+            schemas_dir: crate::util::normalize_dir(schemas_dir),
+            files: merged_schema_files(extra)
+                .into_iter()
+                .map(|(name, filename)| (name.to_string(), filename.to_string()))
+                .collect(),
+        };
+
+        let mut cache = Self {
+            schemas_by_name: HashMap::new(),
+            schemas_by_uri: HashMap::new(),
+            source: None,
+            ref_resolver: None,
+        };
+        for (name, filename) in &source.files {
+            cache.load_file(&source.schemas_dir, name, filename);
+        }
+        cache.source = Some(source);
+        cache
+    }
+
+    /// Create a schema cache from pre-loaded schema values.
+    /// Keys in the map should be schema names (e.g. "brand", "material").
+    pub fn from_map(schemas: HashMap<String, Value>) -> Self {
+        let mut cache = Self {
+            schemas_by_name: HashMap::new(),
+            schemas_by_uri: HashMap::new(),
+            #[cfg(feature = "filesystem")]
+            source: None,
+            ref_resolver: None,
+        };
 
         for (name, filename) in SCHEMA_FILES {
-            let path = schemas_dir.join(filename);
-            if let Some(schema) = load_json(&path) {
-                let schema = Arc::new(schema);
+            if let Some(schema_value) = schemas.get(*name) {
+                cache.register(name, filename, schema_value.clone(), None);
+            }
+        }
 
-                schemas_by_name.insert(name.to_string(), Arc::clone(&schema));
+        cache
+    }
 
-                let relpath = format!("{}/{}", schemas_dir.display(), filename);
-                schemas_by_uri.insert(relpath, Arc::clone(&schema));
-                schemas_by_uri.insert(format!("./{}", filename), Arc::clone(&schema));
-                schemas_by_uri.insert(filename.to_string(), Arc::clone(&schema));
+    /// Resolve `$ref` URIs the cache doesn't hold (e.g. external `https://` schemas) with
+    /// `resolver` instead of failing schema compilation. The resolver is called each time a
+    /// schema referencing the URI is compiled, so an HTTP fetcher should cache its responses.
+    pub fn with_ref_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        self.ref_resolver = Some(Arc::new(resolver));
+        self
+    }
 
-                if let Some(id) = schema.get("$id").and_then(|v| v.as_str()) {
-                    schemas_by_uri.insert(id.to_string(), Arc::clone(&schema));
-                }
+    /// The fallback set by `with_ref_resolver`, if any.
+    pub fn ref_resolver(&self) -> Option<&RefResolver> {
+        self.ref_resolver.as_ref()
+    }
+
+    /// Drop the cached copy of `schema_name`, e.g. after its file changed on disk.
+    /// A cache built from a directory reads the file again straight away; a `from_map`
+    /// cache simply forgets the schema.
+    pub fn invalidate(&mut self, schema_name: &str) {
+        self.unregister(schema_name);
+
+        #[cfg(feature = "filesystem")]
+        if let Some(source) = self.source.clone() {
+            if let Some((name, filename)) = source.files.iter().find(|(n, _)| n == schema_name) {
+                self.load_file(&source.schemas_dir, name, filename);
             }
         }
+    }
 
-        Self {
-            schemas_by_name,
-            schemas_by_uri,
+    /// Drop every cached schema. A cache built from a directory reloads them all from
+    /// disk, so a long-lived process can pick up schema edits without building a new cache.
+    pub fn invalidate_all(&mut self) {
+        self.schemas_by_name.clear();
+        self.schemas_by_uri.clear();
+
+        #[cfg(feature = "filesystem")]
+        if let Some(source) = self.source.clone() {
+            for (name, filename) in &source.files {
+                self.load_file(&source.schemas_dir, name, filename);
+            }
         }
     }
 
-    /// Create a schema cache from pre-loaded schema values.
-    /// Keys in the map should be schema names (e.g. "brand", "material").
-    pub fn from_map(schemas: HashMap<String, Value>) -> Self {
-        let mut schemas_by_name = HashMap::new();
-        let mut schemas_by_uri = HashMap::new();
+    /// Load `filename` from `schemas_dir` and register it under `name`, if it parses.
+    #[cfg(feature = "filesystem")]
+    fn load_file(&mut self, schemas_dir: &std::path::Path, name: &str, filename: &str) {
+        if let Some(schema) = crate::util::load_schema_json(&schemas_dir.join(filename)) {
+            self.register(name, filename, schema, Some(schemas_dir));
+        }
+    }
+
+    /// Index `schema` by name, by filename variants and by `$id` for $ref resolution.
+    fn register(&mut self, name: &str, filename: &str, schema: Value, schemas_dir: Option<&std::path::Path>) {
+        let schema = Arc::new(schema);
+
+        self.schemas_by_name.insert(name.to_string(), Arc::clone(&schema));
+
+        if let Some(schemas_dir) = schemas_dir {
+            let relpath = format!("{}/{}", schemas_dir.display(), filename);
+            self.schemas_by_uri.insert(relpath, Arc::clone(&schema));
+        }
+        self.schemas_by_uri.insert(format!("./{}", filename), Arc::clone(&schema));
+        self.schemas_by_uri.insert(filename.to_string(), Arc::clone(&schema));
+
+        if let Some(id) = schema.get("$id").and_then(|v| v.as_str()) {
+            self.schemas_by_uri.insert(id.to_string(), Arc::clone(&schema));
+        }
+    }
+
+    /// Remove `name` and every URI key that points at its schema.
+    fn unregister(&mut self, name: &str) {
+        if let Some(schema) = self.schemas_by_name.remove(name) {
+            self.schemas_by_uri.retain(|_, s| !Arc::ptr_eq(s, &schema));
+        }
+    }
+
+    /// Check that every expected schema is loaded, reporting each one that isn't as a
+    /// "Schema" error: once here rather than on every data file that needs it. Also
+    /// includes the duplicate `$id`s from `warnings`.
+    ///
+    /// A cache built from a directory checks its full mapping, naming the file that is
+    /// missing or unreadable; a `from_map` cache checks the default `SCHEMA_FILES` names.
+    pub fn preflight(&self) -> crate::types::ValidationResult {
+        use crate::types::ValidationError;
+
+        let mut result = crate::types::ValidationResult::default();
+        for warning in self.warnings() {
+            result.add(warning);
+        }
+
+        #[cfg(feature = "filesystem")]
+        if let Some(source) = &self.source {
+            for (name, filename) in &source.files {
+                if self.schemas_by_name.contains_key(name) {
+                    continue;
+                }
+                let path = source.schemas_dir.join(filename);
+                let problem = if path.is_file() {
+                    "could not be read or is not valid JSON"
+                } else {
+                    "is missing"
+                };
+                result.add(ValidationError::error(
+                    crate::codes::SCHEMA,
+                    format!("Schema file '{}' for '{}' {}", filename, name, problem),
+                    Some(path.to_string_lossy().to_string()),
+                ));
+            }
+            return result;
+        }
 
         for (name, filename) in SCHEMA_FILES {
-            if let Some(schema_value) = schemas.get(*name) {
-                let schema = Arc::new(schema_value.clone());
+            if !self.schemas_by_name.contains_key(*name) {
+                result.add(ValidationError::error(
+                    crate::codes::SCHEMA,
+                    format!("Schema '{}' ({}) was not provided", name, filename),
+                    None,
+                ));
+            }
+        }
+        result
+    }
 
-                schemas_by_name.insert(name.to_string(), Arc::clone(&schema));
+    /// Whether `preflight` checks the schema `name`, so it reports it when it's missing.
+    pub fn is_preflighted(&self, name: &str) -> bool {
+        #[cfg(feature = "filesystem")]
+        if let Some(source) = &self.source {
+            return source.files.iter().any(|(n, _)| n == name);
+        }
+        SCHEMA_FILES.iter().any(|(n, _)| *n == name)
+    }
 
-                // Register by filename variants for $ref resolution
-                schemas_by_uri.insert(format!("./{}", filename), Arc::clone(&schema));
-                schemas_by_uri.insert(filename.to_string(), Arc::clone(&schema));
+    /// Problems with the loaded schemas themselves, reported under "Schema":
+    /// - an error for each `$id` shared by more than one schema, since `$ref`s to it
+    ///   resolve to whichever was registered last
+    /// - a warning when schemas declare different `$schema` drafts, whose `$ref` and
+    ///   keyword semantics differ. Schemas without `$schema` are left out.
+    /// - a warning for each `$ref` resolved by suffix that matches several schemas
+    pub fn warnings(&self) -> Vec<crate::types::ValidationError> {
+        let mut issues = self.shared_ids();
+        issues.extend(self.mixed_drafts());
+        issues.extend(self.ambiguous_refs());
+        issues
+    }
 
-                if let Some(id) = schema.get("$id").and_then(|v| v.as_str()) {
-                    schemas_by_uri.insert(id.to_string(), Arc::clone(&schema));
+    /// A warning for each `$ref` that matches no key exactly and whose suffix matches
+    /// several different schemas, naming the one `resolve_ref` picks.
+    fn ambiguous_refs(&self) -> Vec<crate::types::ValidationError> {
+        fn collect_refs<'a>(value: &'a Value, refs: &mut std::collections::BTreeSet<&'a str>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(r)) = map.get("$ref") {
+                        refs.insert(r);
+                    }
+                    map.values().for_each(|v| collect_refs(v, refs));
                 }
+                Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+                _ => {}
             }
         }
 
-        Self {
-            schemas_by_name,
-            schemas_by_uri,
+        let mut names: Vec<&String> = self.schemas_by_name.keys().collect();
+        names.sort_unstable();
+        let mut issues = Vec::new();
+        for name in names {
+            let mut refs = std::collections::BTreeSet::new();
+            collect_refs(&self.schemas_by_name[name], &mut refs);
+            for reference in refs {
+                let base = reference.split('#').next().unwrap_or(reference);
+                let stripped = base.strip_prefix("./").unwrap_or(base);
+                if stripped.is_empty() || self.schemas_by_uri.contains_key(base) || self.schemas_by_uri.contains_key(stripped) {
+                    continue;
+                }
+                let candidates = self.suffix_candidates(stripped);
+                let Some((best_key, best_schema)) = candidates.first() else {
+                    continue;
+                };
+                if candidates.iter().all(|(_, schema)| Arc::ptr_eq(schema, best_schema)) {
+                    continue;
+                }
+                let keys: Vec<&str> = candidates.iter().map(|(key, _)| key.as_str()).collect();
+                issues.push(crate::types::ValidationError::warning(
+                    crate::codes::SCHEMA,
+                    format!(
+                        "$ref '{}' in schema '{}' matches several schemas ('{}'); using '{}'",
+                        reference,
+                        name,
+                        keys.join("', '"),
+                        best_key
+                    ),
+                    None,
+                ));
+            }
+        }
+        issues
+    }
+
+    fn shared_ids(&self) -> Vec<crate::types::ValidationError> {
+        let mut names_by_id: std::collections::BTreeMap<&str, Vec<&str>> = Default::default();
+        for (name, schema) in &self.schemas_by_name {
+            if let Some(id) = schema.get("$id").and_then(|v| v.as_str()) {
+                names_by_id.entry(id).or_default().push(name);
+            }
+        }
+        names_by_id
+            .into_iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(id, mut names)| {
+                names.sort_unstable();
+                crate::types::ValidationError::error(
+                    crate::codes::SCHEMA,
+                    format!(
+                        "Schemas '{}' share the $id '{}'; $refs to it resolve to only one of them",
+                        names.join("', '"),
+                        id
+                    ),
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    fn mixed_drafts(&self) -> Option<crate::types::ValidationError> {
+        let mut schemas: Vec<(&String, &Arc<Value>)> = self.schemas_by_name.iter().collect();
+        schemas.sort_unstable_by_key(|(name, _)| *name);
+        // Normalized draft -> (declaration as first spelled, schema names)
+        let mut by_draft: std::collections::BTreeMap<String, (&str, Vec<&str>)> = Default::default();
+        for (name, schema) in schemas {
+            if let Some(declared) = schema.get("$schema").and_then(|v| v.as_str()) {
+                // "https://...schema#" and "http://...schema" name the same draft
+                let draft = declared.trim().trim_end_matches('#').replacen("https://", "http://", 1);
+                by_draft.entry(draft).or_insert((declared, Vec::new())).1.push(name);
+            }
         }
+        if by_draft.len() < 2 {
+            return None;
+        }
+        let drafts: Vec<String> = by_draft
+            .into_values()
+            .map(|(declared, names)| format!("{} ({})", declared, names.join(", ")))
+            .collect();
+        Some(crate::types::ValidationError::warning(
+            crate::codes::SCHEMA,
+            format!(
+                "Schemas declare different $schema drafts: {}; $ref resolution and keywords differ between drafts",
+                drafts.join(", ")
+            ),
+            None,
+        ))
+    }
+
+    /// Validate an already-parsed JSON value against the named schema, without going
+    /// through a file. Delegates to `validators::validate_json`.
+    pub fn validate_value(&self, data: &Value, schema_name: &str) -> crate::types::ValidationResult {
+        crate::validators::validate_json(data, schema_name, self, None)
     }
 
     pub fn get(&self, schema_name: &str) -> Option<&Value> {
@@ -96,12 +377,121 @@ impl SchemaCache {
             return Some((**schema).clone());
         }
 
-        for (key, schema) in &self.schemas_by_uri {
-            if key.ends_with(stripped) {
-                return Some((**schema).clone());
-            }
+        let (_, schema) = *self.suffix_candidates(stripped).first()?;
+        Some((**schema).clone())
+    }
+
+    /// Keys ending in `suffix`, the fallback for `$ref`s matching no key exactly. Sorted
+    /// longest (most specific) first, ties broken by name, so an ambiguous suffix
+    /// resolves the same way on every run; `warnings` reports such suffixes.
+    fn suffix_candidates(&self, suffix: &str) -> Vec<(&String, &Arc<Value>)> {
+        let mut candidates: Vec<(&String, &Arc<Value>)> = self.schemas_by_uri
+            .iter()
+            .filter(|(key, _)| key.ends_with(suffix))
+            .collect();
+        candidates.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn ambiguous_suffix_ref_resolves_deterministically_with_a_warning() {
+        let schemas = HashMap::from([
+            ("brand".to_string(), json!({"properties": {"kind": {"$ref": "kinds.json#/definitions/kind"}}})),
+            ("material".to_string(), json!({"$id": "https://b.example/kinds.json", "title": "b"})),
+            ("filament".to_string(), json!({"$id": "https://a.example/kinds.json", "title": "a"})),
+        ]);
+        let cache = SchemaCache::from_map(schemas);
+
+        assert_eq!(cache.resolve_ref("kinds.json").unwrap()["title"], "a");
+        let messages: Vec<String> = cache.warnings().into_iter().map(|e| e.message).collect();
+        assert_eq!(
+            messages,
+            ["$ref 'kinds.json#/definitions/kind' in schema 'brand' matches several schemas \
+              ('https://a.example/kinds.json', 'https://b.example/kinds.json'); using 'https://a.example/kinds.json'"]
+        );
+    }
+
+    #[test]
+    fn exact_refs_are_not_ambiguous() {
+        let schemas = HashMap::from([
+            ("brand".to_string(), json!({"$ref": "material_schema.json"})),
+            ("material".to_string(), json!({"$id": "https://b.example/material_schema.json"})),
+        ]);
+        assert!(SchemaCache::from_map(schemas).warnings().is_empty());
+    }
+
+    #[test]
+    fn invalidate_forgets_a_from_map_schema_and_its_uri_keys() {
+        let schemas = HashMap::from([
+            ("brand".to_string(), json!({"$id": "https://example.com/brand.json", "title": "brand"})),
+            ("material".to_string(), json!({"title": "material"})),
+        ]);
+        let mut cache = SchemaCache::from_map(schemas);
+        assert!(cache.resolve_ref("https://example.com/brand.json").is_some());
+
+        cache.invalidate("brand");
+        assert!(cache.get("brand").is_none());
+        for uri in ["brand_schema.json", "./brand_schema.json", "https://example.com/brand.json"] {
+            assert!(cache.resolve_ref(uri).is_none(), "{uri}");
         }
+        assert_eq!(cache.get("material").unwrap()["title"], "material");
+        assert_eq!(cache.resolve_ref("./material_schema.json").unwrap()["title"], "material");
+
+        // Unknown names are a no-op
+        cache.invalidate("unknown");
+        assert!(cache.get("material").is_some());
+    }
+
+    #[test]
+    fn invalidate_all_empties_a_from_map_cache() {
+        let schemas = HashMap::from([
+            ("brand".to_string(), json!({"title": "brand"})),
+            ("material".to_string(), json!({"title": "material"})),
+        ]);
+        let mut cache = SchemaCache::from_map(schemas);
+        cache.invalidate_all();
+        assert!(cache.get("brand").is_none());
+        assert!(cache.get("material").is_none());
+        assert!(cache.resolve_ref("material_schema.json").is_none());
+    }
+
+    #[cfg(feature = "filesystem")]
+    #[test]
+    fn directory_cache_reloads_edited_files() {
+        let dir = std::env::temp_dir().join(format!("ofd-schema-cache-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |filename: &str, schema: Value| std::fs::write(dir.join(filename), schema.to_string()).unwrap();
+        write("brand_schema.json", json!({"$id": "https://example.com/brand-v1.json", "title": "v1"}));
+        write("material_schema.json", json!({"title": "v1"}));
+
+        let mut cache = SchemaCache::from_directory(&dir);
+        assert_eq!(cache.get("brand").unwrap()["title"], "v1");
+
+        write("brand_schema.json", json!({"$id": "https://example.com/brand-v2.json", "title": "v2"}));
+        write("material_schema.json", json!({"title": "v2"}));
+        cache.invalidate("brand");
+        assert_eq!(cache.get("brand").unwrap()["title"], "v2");
+        assert_eq!(cache.resolve_ref("brand_schema.json").unwrap()["title"], "v2");
+        assert!(cache.resolve_ref("https://example.com/brand-v1.json").is_none());
+        assert!(cache.resolve_ref("https://example.com/brand-v2.json").is_some());
+        // Only the invalidated schema was read again
+        assert_eq!(cache.get("material").unwrap()["title"], "v1");
+
+        cache.invalidate_all();
+        assert_eq!(cache.get("material").unwrap()["title"], "v2");
+
+        // A deleted file leaves the schema missing rather than stale
+        std::fs::remove_file(dir.join("material_schema.json")).unwrap();
+        cache.invalidate("material");
+        assert!(cache.get("material").is_none());
 
-        None
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
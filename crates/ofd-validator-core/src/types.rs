@@ -1,6 +1,8 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::Serialize;
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 pub enum ValidationLevel {
     Error,
     Warning,
@@ -21,15 +23,40 @@ pub struct ValidationError {
     pub category: String,
     pub message: String,
     pub path: Option<String>,
+    /// 1-based line of the offending value in the file at `path`, when known (currently
+    /// for schema failures of JSON files whose source text was available).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-based column, in characters, on `line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
 }
 
 impl ValidationError {
+    pub fn new(
+        level: ValidationLevel,
+        category: impl Into<String>,
+        message: impl Into<String>,
+        path: Option<String>,
+    ) -> Self {
+        Self {
+            level,
+            category: category.into(),
+            message: message.into(),
+            path,
+            line: None,
+            column: None,
+        }
+    }
+
     pub fn error(category: impl Into<String>, message: impl Into<String>, path: Option<String>) -> Self {
         Self {
             level: ValidationLevel::Error,
             category: category.into(),
             message: message.into(),
             path,
+            line: None,
+            column: None,
         }
     }
 
@@ -39,8 +66,19 @@ impl ValidationError {
             category: category.into(),
             message: message.into(),
             path,
+            line: None,
+            column: None,
         }
     }
+
+    /// Attach a (line, column) location, see `util::json_pointer_location`.
+    pub fn with_location(mut self, location: Option<(usize, usize)>) -> Self {
+        if let Some((line, column)) = location {
+            self.line = Some(line);
+            self.column = Some(column);
+        }
+        self
+    }
 }
 
 impl std::fmt::Display for ValidationError {
@@ -52,9 +90,23 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+/// Issue counts for a `ValidationResult`, see `ValidationResult::summary`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub errors: usize,
+    pub warnings: usize,
+    pub total: usize,
+    /// (category, issue count), sorted by category
+    pub categories: Vec<(String, usize)>,
+}
+
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
+    /// Paths that were checked without producing any issue, grouped by validator.
+    /// Only populated when `ValidationOptions::include_passing` is set.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub passing: BTreeMap<String, Vec<String>>,
 }
 
 impl ValidationResult {
@@ -62,14 +114,72 @@ impl ValidationResult {
         self.errors.push(error);
     }
 
+    /// Record paths that passed the given validator.
+    pub fn add_passing(&mut self, validator: &str, paths: impl IntoIterator<Item = String>) {
+        let mut paths = paths.into_iter().peekable();
+        if paths.peek().is_some() {
+            self.passing.entry(validator.to_string()).or_default().extend(paths);
+        }
+    }
+
     pub fn merge_from(&mut self, other: &ValidationResult) {
         self.errors.extend(other.errors.iter().cloned());
+        for (validator, paths) in &other.passing {
+            self.add_passing(validator, paths.iter().cloned());
+        }
+    }
+
+    /// Combine many results into one, reserving space for every issue up front so the
+    /// errors vector is allocated once instead of growing stage by stage.
+    pub fn merge_all(results: impl IntoIterator<Item = ValidationResult>) -> ValidationResult {
+        let results: Vec<ValidationResult> = results.into_iter().collect();
+        let mut merged = ValidationResult {
+            errors: Vec::with_capacity(results.iter().map(|r| r.errors.len()).sum()),
+            ..Default::default()
+        };
+        for r in results {
+            merged.errors.extend(r.errors);
+            for (validator, paths) in r.passing {
+                merged.add_passing(&validator, paths);
+            }
+        }
+        merged
+    }
+
+    /// Rewrite error categories using `map` (old label -> new label).
+    /// Categories without an entry are left unchanged.
+    pub fn remap_categories(&mut self, map: &HashMap<String, String>) {
+        for error in &mut self.errors {
+            if let Some(new_category) = map.get(&error.category) {
+                error.category = new_category.clone();
+            }
+        }
     }
 
     pub fn is_valid(&self) -> bool {
         !self.errors.iter().any(|e| e.level == ValidationLevel::Error)
     }
 
+    /// True when no `Error`-level issue has the given category (warnings are allowed),
+    /// e.g. to gate a CI job on logos alone.
+    pub fn category_is_valid(&self, category: &str) -> bool {
+        !self
+            .errors
+            .iter()
+            .any(|e| e.level == ValidationLevel::Error && e.category == category)
+    }
+
+    /// True when an `Error`-level issue has the category `code` (see `codes`), so tests
+    /// can assert on what was found without matching message text.
+    pub fn has_error_with_code(&self, code: &str) -> bool {
+        !self.category_is_valid(code)
+    }
+
+    /// Every issue (errors and warnings) reported against exactly `path`, in order.
+    pub fn errors_for_path(&self, path: &str) -> Vec<&ValidationError> {
+        self.errors.iter().filter(|e| e.path.as_deref() == Some(path)).collect()
+    }
+
     pub fn error_count(&self) -> usize {
         self.errors.iter().filter(|e| e.level == ValidationLevel::Error).count()
     }
@@ -77,4 +187,54 @@ impl ValidationResult {
     pub fn warning_count(&self) -> usize {
         self.errors.iter().filter(|e| e.level == ValidationLevel::Warning).count()
     }
+
+    /// The headline issue for each distinct path, in order of first appearance: the first
+    /// error if the path has any, otherwise its first warning. Issues without a path are
+    /// treated as one more group.
+    pub fn first_error_per_file(&self) -> Vec<&ValidationError> {
+        let mut order: Vec<Option<&str>> = Vec::new();
+        let mut headline: HashMap<Option<&str>, &ValidationError> = HashMap::new();
+        for error in &self.errors {
+            let key = error.path.as_deref();
+            match headline.get(&key) {
+                None => {
+                    order.push(key);
+                    headline.insert(key, error);
+                }
+                Some(current) => {
+                    if current.level == ValidationLevel::Warning && error.level == ValidationLevel::Error {
+                        headline.insert(key, error);
+                    }
+                }
+            }
+        }
+        order.into_iter().map(|key| headline[&key]).collect()
+    }
+
+    /// Issues grouped by category (sorted), keeping their original order within each group.
+    pub fn group_by_category(&self) -> BTreeMap<&str, Vec<&ValidationError>> {
+        let mut groups: BTreeMap<&str, Vec<&ValidationError>> = BTreeMap::new();
+        for error in &self.errors {
+            groups.entry(error.category.as_str()).or_default().push(error);
+        }
+        groups
+    }
+
+    /// Count errors, warnings and issues per category in a single pass over `errors`.
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary {
+            total: self.errors.len(),
+            ..Default::default()
+        };
+        let mut categories: BTreeMap<&str, usize> = BTreeMap::new();
+        for error in &self.errors {
+            match error.level {
+                ValidationLevel::Error => summary.errors += 1,
+                ValidationLevel::Warning => summary.warnings += 1,
+            }
+            *categories.entry(error.category.as_str()).or_default() += 1;
+        }
+        summary.categories = categories.into_iter().map(|(c, n)| (c.to_string(), n)).collect();
+        summary
+    }
 }
@@ -1,3 +1,5 @@
+use std::path::{Component, Path, PathBuf};
+
 use serde_json::Value;
 
 pub const ILLEGAL_CHARACTERS: &[char] = &[
@@ -8,16 +10,565 @@ pub const ILLEGAL_CHARACTERS: &[char] = &[
 pub const LOGO_MIN_SIZE: u32 = 100;
 pub const LOGO_MAX_SIZE: u32 = 400;
 
+/// Default logo file extensions (see `LogoOptions::allowed_extensions`).
+pub const DEFAULT_LOGO_EXTENSIONS: &[&str] = &["png", "jpg", "svg"];
+
+/// Default (component, total) path length limits in bytes: 255 per folder name, as on
+/// most filesystems, and 4096 for the whole path, Linux's `PATH_MAX`.
+pub const DEFAULT_PATH_LENGTH_LIMITS: (usize, usize) = (255, 4096);
+
+/// Suggested `LogoOptions::max_metadata_bytes`: more than a small ICC profile's worth.
+pub const DEFAULT_MAX_LOGO_METADATA: u64 = 16 * 1024;
+
+/// Environment variable capping the worker threads used by the bindings' batch validators
+/// when no explicit `max_workers` is given (e.g. in containerized CI).
+pub const WORKERS_ENV: &str = "OFD_VALIDATOR_WORKERS";
+
+/// Resolve the worker count: `explicit` if set, else a positive integer in `WORKERS_ENV`,
+/// else `None` to keep Rayon's default pool. Unparseable or zero env values are ignored.
+pub fn resolve_workers(explicit: Option<usize>) -> Option<usize> {
+    explicit.filter(|&n| n > 0).or_else(|| {
+        std::env::var(WORKERS_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+    })
+}
+
+/// Default cap on the pixel count of a raster logo before it is decoded
+/// (see `LogoOptions::max_decode_pixels`). Far above `LOGO_MAX_SIZE`, so it only
+/// stops files whose header claims an absurd size.
+pub const DEFAULT_MAX_LOGO_PIXELS: u64 = 4096 * 4096;
+
+/// Largest entry, in bytes, `DataSet::from_zip` reads whatever `ContentLimits` say.
+pub const ZIP_MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest combined size of all entries, in bytes, `DataSet::from_zip` reads whatever
+/// `ContentLimits` say.
+pub const ZIP_MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Default cap on schema validation messages (see `JsonOptions::max_message_len`).
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 500;
+
+/// Suggested cap on purchase links per size (see `ValidationOptions::max_purchase_links`).
+pub const DEFAULT_MAX_PURCHASE_LINKS: usize = 50;
+
+/// Default caps, in characters, on free-text fields (see `ValidationOptions::field_length_limits`).
+pub const DEFAULT_FIELD_LENGTH_LIMITS: &[(&str, usize)] = &[("id", 64), ("name", 128), ("description", 2000)];
+
+/// Default keys whose values must be JSON numbers (see `ValidationOptions::number_fields`).
+pub const DEFAULT_NUMBER_FIELDS: &[&str] = &["diameter", "weight_grams", "density"];
+
+/// Example if/then field rules (see `ValidationOptions::conditional_rules` and
+/// `validators::ConditionalRule` for the format). The one here turns the "Sizes" warning
+/// of `validate_availability` into an error; barcode rules are left out since the GTIN
+/// checks already cover `gtin` and `ean`.
+pub const DEFAULT_CONDITIONAL_RULES: &[&str] = &[
+    "sizes: if available == true then purchase_links nonempty",
+];
+
+/// Default plausible spool weight range in grams (see `ValidationOptions::weight_range`).
+pub const DEFAULT_WEIGHT_RANGE: (f64, f64) = (50.0, 10_000.0);
+
+/// Default slug pattern for entity `id` fields (see `ValidationOptions::id_format`).
+pub const DEFAULT_ID_PATTERN: &str = r"^[a-z0-9][a-z0-9-]*$";
+
+/// Suggested semver-style pattern for sizes.json version markers
+/// (see `ValidationOptions::sizes_version`). The patch component is optional.
+pub const DEFAULT_VERSION_PATTERN: &str = r"^\d+\.\d+(\.\d+)?([-+][0-9A-Za-z.-]+)?$";
+
 pub fn parse_json(content: &str) -> Option<Value> {
     serde_json::from_str(content).ok()
 }
 
+/// Parse a schema file. With the `schema-comments` feature, `//` and `/* */` comments are
+/// stripped first (see `strip_json_comments`); data files never get this leniency.
+pub fn parse_schema_json(content: &str) -> Option<Value> {
+    #[cfg(feature = "schema-comments")]
+    {
+        parse_json(&strip_json_comments(content))
+    }
+    #[cfg(not(feature = "schema-comments"))]
+    {
+        parse_json(content)
+    }
+}
+
+/// `content` with `//` line comments and `/* */` block comments removed. Comment markers
+/// inside strings are left alone, and newlines within block comments are kept so parse
+/// errors still point at the right line.
+pub fn strip_json_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                // Keep the newline ending the comment
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    if next == '\n' {
+                        out.push('\n');
+                    }
+                    prev = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The 1-based (line, column) where the value at the JSON pointer `pointer` (e.g.
+/// "/sizes/0/gtin", as in schema errors) starts in `source`, so editors can jump to it.
+/// The empty pointer is the whole document. `None` if the pointer doesn't resolve or the
+/// source isn't valid JSON on the way there.
+pub fn json_pointer_location(source: &str, pointer: &str) -> Option<(usize, usize)> {
+    let tokens: Vec<String> = match pointer {
+        "" => Vec::new(),
+        _ => pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|t| t.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    };
+    let bytes = source.as_bytes();
+    let mut pos = skip_whitespace(bytes, 0);
+
+    for token in tokens {
+        match bytes.get(pos)? {
+            b'{' => {
+                pos = skip_whitespace(bytes, pos + 1);
+                loop {
+                    let key_end = string_end(bytes, pos)?;
+                    let key: String = serde_json::from_str(&source[pos..key_end]).ok()?;
+                    pos = skip_whitespace(bytes, key_end);
+                    if bytes.get(pos) != Some(&b':') {
+                        return None;
+                    }
+                    pos = skip_whitespace(bytes, pos + 1);
+                    if key == token {
+                        break;
+                    }
+                    pos = skip_whitespace(bytes, value_end(bytes, pos)?);
+                    if bytes.get(pos) != Some(&b',') {
+                        return None;
+                    }
+                    pos = skip_whitespace(bytes, pos + 1);
+                }
+            }
+            b'[' => {
+                let index: usize = token.parse().ok()?;
+                pos = skip_whitespace(bytes, pos + 1);
+                for _ in 0..index {
+                    pos = skip_whitespace(bytes, value_end(bytes, pos)?);
+                    if bytes.get(pos) != Some(&b',') {
+                        return None;
+                    }
+                    pos = skip_whitespace(bytes, pos + 1);
+                }
+                if matches!(bytes.get(pos), None | Some(b']')) {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    let before = &source[..pos];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    Some((line, column))
+}
+
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(|b| b.is_ascii_whitespace()) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Index just past the JSON string starting at `pos`.
+fn string_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    if bytes.get(pos) != Some(&b'"') {
+        return None;
+    }
+    let mut i = pos + 1;
+    loop {
+        match bytes.get(i)? {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+}
+
+/// Index just past the JSON value starting at `pos`.
+fn value_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    match bytes.get(pos)? {
+        b'"' => string_end(bytes, pos),
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            let mut i = pos;
+            loop {
+                match bytes.get(i)? {
+                    b'"' => {
+                        i = string_end(bytes, i)?;
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(i + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+        }
+        _ => {
+            let mut i = pos;
+            while bytes.get(i).is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace()) {
+                i += 1;
+            }
+            Some(i)
+        }
+    }
+}
+
+/// A `logo` reference with Windows-style backslashes turned into '/', so
+/// `assets\logo.png` resolves to the same file on every OS. The validator still
+/// flags the original spelling.
+pub fn normalize_logo_ref(name: &str) -> String {
+    name.replace('\\', "/")
+}
+
+/// The top-level folder of a nested `logo` reference in an entity's JSON (e.g. "assets"
+/// for "assets/logo.png"), or `None` when the logo sits directly in the entity folder.
+/// Walkers skip this folder so it isn't mistaken for a child entity.
+pub fn logo_asset_dir(data: &Value) -> Option<&str> {
+    let logo = data.get("logo")?.as_str()?;
+    logo.split_once(['/', '\\']).map(|(dir, _)| dir)
+}
+
+/// Whether a normalized `logo` reference fails to name a file inside the entity folder
+/// it is resolved against: an absolute path, a drive prefix, or an empty, `.` or `..`
+/// segment. Loaders don't read such a logo at all.
+pub fn logo_ref_escapes(normalized: &str) -> bool {
+    normalized.starts_with('/')
+        || normalized.contains(':')
+        || normalized.split('/').any(|segment| segment.is_empty() || segment == "." || segment == "..")
+}
+
+/// The error for a `logo` reference that `logo_ref_escapes`, reported against the
+/// entity folder `dir`.
+pub fn logo_escape_error(logo_name: &str, dir: Option<&str>) -> crate::types::ValidationError {
+    crate::types::ValidationError::error(
+        crate::codes::LOGO,
+        format!(
+            "Logo path '{}' must be a relative path inside the entity folder (e.g. 'logo.png' or 'assets/logo.png')",
+            logo_name
+        ),
+        dir.map(str::to_string),
+    )
+}
+
+/// Normalize a user-supplied directory so paths derived from it are spelled the same way
+/// however it was given (`data`, `./data`, `data/`): the path is made absolute, `.`
+/// components and trailing separators are dropped and `..` is resolved lexically.
+pub fn normalize_dir(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Deepest level below the stores directory searched for store.json files.
+pub const MAX_STORE_DEPTH: usize = 8;
+
+/// The store directories below `stores_dir`, in listing order. A directory with a
+/// store.json is a store and isn't searched further; one without is a grouping folder
+/// (e.g. `stores/eu/`) if a store.json exists somewhere below it, and otherwise a store
+/// missing its store.json. `subdirs` lists a directory's subdirectories.
+pub fn find_store_dirs<T>(
+    stores_dir: &T,
+    mut subdirs: impl FnMut(&T) -> Vec<T>,
+    has_store_json: impl Fn(&T) -> bool,
+) -> Vec<T> {
+    collect_store_dirs(stores_dir, 1, &mut subdirs, &has_store_json).0
+}
+
+/// (store directories below `dir`, whether any of them has a store.json)
+fn collect_store_dirs<T>(
+    dir: &T,
+    depth: usize,
+    subdirs: &mut impl FnMut(&T) -> Vec<T>,
+    has_store_json: &impl Fn(&T) -> bool,
+) -> (Vec<T>, bool) {
+    let mut stores = Vec::new();
+    let mut found = false;
+    for child in subdirs(dir) {
+        if has_store_json(&child) {
+            stores.push(child);
+            found = true;
+            continue;
+        }
+        if depth < MAX_STORE_DEPTH {
+            let (nested, nested_found) = collect_store_dirs(&child, depth + 1, subdirs, has_store_json);
+            if nested_found {
+                stores.extend(nested);
+                found = true;
+                continue;
+            }
+        }
+        stores.push(child);
+    }
+    (stores, found)
+}
+
+/// `find_store_dirs` on the filesystem; unreadable directories count as empty.
+#[cfg(feature = "filesystem")]
+pub fn store_dirs(stores_dir: &Path) -> Vec<PathBuf> {
+    find_store_dirs(
+        &stores_dir.to_path_buf(),
+        |dir| match std::fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect(),
+            Err(_) => Vec::new(),
+        },
+        |dir| dir.join("store.json").exists(),
+    )
+}
+
+/// (brand folder, brand id) for each brand.json in `brand_entries` (path_label, parsed data).
+pub fn brand_dirs<'a>(brand_entries: &[(&'a str, &'a Value)]) -> Vec<(&'a Path, &'a str)> {
+    brand_entries
+        .iter()
+        .filter_map(|&(path_str, brand)| {
+            let dir = Path::new(path_str).parent()?;
+            Some((dir, brand.get("id")?.as_str()?))
+        })
+        .collect()
+}
+
+/// The id of the brand owning `path`: the one whose folder is the closest ancestor.
+pub fn owning_brand<'a>(brand_dirs: &[(&Path, &'a str)], path: &str) -> Option<&'a str> {
+    brand_dirs
+        .iter()
+        .filter(|(dir, _)| Path::new(path).starts_with(dir))
+        .max_by_key(|(dir, _)| dir.as_os_str().len())
+        .map(|(_, id)| *id)
+}
+
 pub fn cleanse_folder_name(name: &str) -> String {
     name.replace('/', " ").trim().to_string()
 }
 
+/// Parse the contents of the file at `path`, also reporting whether JSON5 was needed.
+///
+/// With the `json5` feature, `.json5` files are parsed as JSON5 and other files fall back
+/// to JSON5 (comments, trailing commas) when strict parsing fails. Without it this is
+/// `parse_json`, and the flag is always false.
+pub fn parse_json_relaxed(content: &str, path: &Path) -> Option<(Value, bool)> {
+    #[cfg(feature = "json5")]
+    {
+        if path.extension().is_none_or(|ext| ext != "json5") {
+            if let Some(value) = parse_json(content) {
+                return Some((value, false));
+            }
+        }
+        json5::from_str::<Value>(content).ok().map(|value| (value, true))
+    }
+    #[cfg(not(feature = "json5"))]
+    {
+        let _ = path;
+        parse_json(content).map(|value| (value, false))
+    }
+}
+
+/// The warning recorded for a data file that only parsed as JSON5.
+pub fn json5_warning(path: &str) -> crate::types::ValidationError {
+    crate::types::ValidationError::warning(
+        crate::codes::JSON,
+        "Parsed as JSON5 (comments or trailing commas); strict JSON tools will reject this file",
+        Some(path.to_string()),
+    )
+}
+
+/// Whether the brand folder `dir` is the brand called `brand`: its folder name, or the
+/// `id` or `name` in its brand.json, compared case-insensitively.
+#[cfg(feature = "filesystem")]
+pub fn brand_dir_matches(dir: &Path, brand: &str) -> bool {
+    let brand = brand.trim().to_lowercase();
+    let folder = dir.file_name().map(|f| f.to_string_lossy().to_lowercase());
+    if folder.as_deref() == Some(brand.as_str()) {
+        return true;
+    }
+    load_json(&dir.join("brand.json")).is_some_and(|data| {
+        ["id", "name"].iter().any(|key| {
+            data.get(key).and_then(|v| v.as_str()).is_some_and(|v| v.trim().to_lowercase() == brand)
+        })
+    })
+}
+
+/// The name the file at `path` is stored under. A case-insensitive filesystem opens
+/// "logo.png" for a file stored as "Logo.PNG"; this returns the stored spelling, or the
+/// name in `path` when the directory can't be listed.
+#[cfg(feature = "filesystem")]
+pub fn file_name_on_disk(path: &Path) -> String {
+    let requested = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+    let Some(entries) = path.parent().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return requested;
+    };
+    let names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    if names.contains(&requested) {
+        return requested;
+    }
+    let lowered = requested.to_lowercase();
+    names.into_iter().find(|name| name.to_lowercase() == lowered).unwrap_or(requested)
+}
+
 #[cfg(feature = "filesystem")]
 pub fn load_json(path: &std::path::Path) -> Option<Value> {
     let content = std::fs::read_to_string(path).ok()?;
     parse_json(&content)
 }
+
+/// `load_json` for schema files, see `parse_schema_json`.
+#[cfg(feature = "filesystem")]
+pub fn load_schema_json(path: &std::path::Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_schema_json(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: &str = r#"{
+  "id": "acme",
+  "sizes": [
+    {"gtin": "123"},
+    {
+      "gtin": "456",
+      "stores": [{"id": "shop"}]
+    }
+  ]
+}"#;
+
+    #[test]
+    fn empty_pointer_is_the_whole_document() {
+        assert_eq!(json_pointer_location("  \n {}", ""), Some((2, 2)));
+    }
+
+    #[test]
+    fn nested_pointers_resolve_through_objects_and_arrays() {
+        assert_eq!(json_pointer_location(SIZES, "/id"), Some((2, 9)));
+        assert_eq!(json_pointer_location(SIZES, "/sizes"), Some((3, 12)));
+        assert_eq!(json_pointer_location(SIZES, "/sizes/0"), Some((4, 5)));
+        assert_eq!(json_pointer_location(SIZES, "/sizes/0/gtin"), Some((4, 14)));
+        assert_eq!(json_pointer_location(SIZES, "/sizes/1/gtin"), Some((6, 15)));
+        assert_eq!(json_pointer_location(SIZES, "/sizes/1/stores/0/id"), Some((7, 25)));
+    }
+
+    #[test]
+    fn unresolvable_pointers_have_no_location() {
+        assert_eq!(json_pointer_location(SIZES, "/missing"), None);
+        assert_eq!(json_pointer_location(SIZES, "/sizes/2"), None);
+        assert_eq!(json_pointer_location(SIZES, "/sizes/first"), None);
+        assert_eq!(json_pointer_location(SIZES, "/id/0"), None);
+        assert_eq!(json_pointer_location(SIZES, "id"), None);
+        assert_eq!(json_pointer_location("[]", "/0"), None);
+    }
+
+    #[test]
+    fn escaped_pointer_tokens_match_keys_with_slashes_and_tildes() {
+        let source = r#"{"a/b": 1, "c~d": 2, "~1": 3}"#;
+        assert_eq!(json_pointer_location(source, "/a~1b"), Some((1, 9)));
+        assert_eq!(json_pointer_location(source, "/c~0d"), Some((1, 19)));
+        // "~01" is "~1" spelled out, not "~" followed by "/"
+        assert_eq!(json_pointer_location(source, "/~01"), Some((1, 28)));
+    }
+
+    #[test]
+    fn keys_are_compared_unescaped() {
+        assert_eq!(json_pointer_location(r#"{"na\"me": 1, "é": 2}"#, "/é"), Some((1, 20)));
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let source = "{\"name\": \"Crème brûlée\", \"id\": 1}";
+        assert_eq!(json_pointer_location(source, "/id"), Some((1, 32)));
+    }
+
+    #[test]
+    fn line_and_block_comments_are_removed() {
+        let source = "{\n  // the brand\n  \"id\": \"acme\", /* inline */ \"name\": \"Acme\"\n}";
+        assert_eq!(strip_json_comments(source), "{\n  \n  \"id\": \"acme\",  \"name\": \"Acme\"\n}");
+        assert_eq!(strip_json_comments("1 /**/ // trailing"), "1  ");
+    }
+
+    #[test]
+    fn block_comments_keep_their_newlines() {
+        let stripped = strip_json_comments("{\n/* one\ntwo\nthree */ \"id\": 1\n}");
+        assert_eq!(stripped, "{\n\n\n \"id\": 1\n}");
+        assert_eq!(parse_json(&stripped), Some(serde_json::json!({"id": 1})));
+    }
+
+    #[test]
+    fn comment_markers_inside_strings_are_kept() {
+        let source = r#"{"url": "https://example.com/*path*/", "note": "a // b"}"#;
+        assert_eq!(strip_json_comments(source), source);
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_end_strings() {
+        let source = r#"{"name": "say \"hi\" // not a comment", "path": "C:\\"} // comment"#;
+        assert_eq!(
+            strip_json_comments(source),
+            r#"{"name": "say \"hi\" // not a comment", "path": "C:\\"} "#
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_runs_to_the_end() {
+        assert_eq!(strip_json_comments("{\"id\": 1}\n/* open\nstill open"), "{\"id\": 1}\n\n");
+        // "/*/" opens a comment without closing it
+        assert_eq!(strip_json_comments("1 /*/ 2"), "1 ");
+    }
+}
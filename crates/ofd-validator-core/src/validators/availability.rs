@@ -0,0 +1,114 @@
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+        Value::Null => false,
+    }
+}
+
+/// Warn about sizes marked available that have no purchase links.
+/// `field` is the availability flag to read (e.g. "available").
+/// `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+pub fn validate_availability(sizes_entries: &[(&str, &Value)], field: &str) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (idx, size) in sizes_arr.iter().enumerate() {
+            if !size.get(field).is_some_and(is_truthy) {
+                continue;
+            }
+
+            let has_links = size
+                .get("purchase_links")
+                .and_then(|v| v.as_array())
+                .is_some_and(|links| !links.is_empty());
+
+            if !has_links {
+                result.add(ValidationError::warning(
+                    codes::SIZES,
+                    format!(
+                        "Size at $[{}] is marked '{}' but has no purchase_links",
+                        idx, field
+                    ),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::ValidationLevel;
+
+    fn issues(sizes: Value, field: &str) -> Vec<(ValidationLevel, String, String)> {
+        validate_availability(&[("sizes.json", &sizes)], field)
+            .errors
+            .into_iter()
+            .map(|e| (e.level, e.category, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn available_sizes_with_links_pass() {
+        let sizes = json!([
+            {"available": true, "purchase_links": [{"store_id": "shop", "url": "https://shop.example/pla"}]},
+            {"available": false},
+            {"available": ""},
+            {"available": 0},
+            {"available": null},
+            {"weight": 1000},
+        ]);
+        assert!(issues(sizes, "available").is_empty());
+    }
+
+    #[test]
+    fn available_size_without_links_is_a_warning() {
+        let sizes = json!([
+            {"available": true},
+            {"available": 1, "purchase_links": []},
+            {"available": "yes", "purchase_links": "https://shop.example/pla"},
+        ]);
+        let expected: Vec<_> = (0..3)
+            .map(|idx| {
+                (
+                    ValidationLevel::Warning,
+                    codes::SIZES.to_string(),
+                    format!("Size at $[{}] is marked 'available' but has no purchase_links", idx),
+                )
+            })
+            .collect();
+        assert_eq!(issues(sizes, "available"), expected);
+    }
+
+    #[test]
+    fn configured_field_is_read() {
+        let sizes = json!([{"available": true}, {"in_stock": true}]);
+        assert_eq!(
+            issues(sizes, "in_stock"),
+            [(
+                ValidationLevel::Warning,
+                codes::SIZES.to_string(),
+                "Size at $[1] is marked 'in_stock' but has no purchase_links".to_string(),
+            )]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about brands whose display `name` matches an earlier brand's, ignoring case and
+/// repeated whitespace: two folders showing up as "Acme" confuse end users even when
+/// their ids differ. `brand_entries` are (path_label, parsed brand.json); brands without
+/// a string `name` are skipped.
+pub fn validate_brand_name_uniqueness(brand_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    // normalized name -> folder of the first brand using it
+    let mut first_by_name: HashMap<String, String> = HashMap::new();
+
+    for (path_str, data) in brand_entries {
+        let Some(name) = data.get("name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let normalized = name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if normalized.is_empty() {
+            continue;
+        }
+        let folder = brand_folder(path_str);
+        match first_by_name.get(&normalized) {
+            Some(first) => result.add(ValidationError::warning(
+                codes::BRAND,
+                format!("Brand name '{}' in {} is also used by the brand in {}", name, folder, first),
+                Some(path_str.to_string()),
+            )),
+            None => {
+                first_by_name.insert(normalized, folder);
+            }
+        }
+    }
+
+    result
+}
+
+fn brand_folder(path_str: &str) -> String {
+    Path::new(path_str)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| path_str.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::ValidationLevel;
+
+    fn issues(brands: &[(&str, Value)]) -> Vec<(ValidationLevel, String, String, Option<String>)> {
+        let entries: Vec<(&str, &Value)> = brands.iter().map(|(path, data)| (*path, data)).collect();
+        validate_brand_name_uniqueness(&entries)
+            .errors
+            .into_iter()
+            .map(|e| (e.level, e.category, e.message, e.path))
+            .collect()
+    }
+
+    #[test]
+    fn distinct_names_pass() {
+        let brands = [
+            ("data/acme/brand.json", json!({"name": "Acme"})),
+            ("data/acme_pro/brand.json", json!({"name": "Acme Pro"})),
+            ("data/nameless/brand.json", json!({"id": "nameless"})),
+            ("data/numbered/brand.json", json!({"name": 5})),
+        ];
+        assert!(issues(&brands).is_empty());
+    }
+
+    #[test]
+    fn names_differing_in_case_and_spacing_are_reported_against_the_first() {
+        let brands = [
+            ("data/acme/brand.json", json!({"name": "Acme Filaments"})),
+            ("data/acme_2/brand.json", json!({"name": "ACME  filaments"})),
+            ("data/acme_3/brand.json", json!({"name": " acme\tFilaments "})),
+        ];
+        assert_eq!(
+            issues(&brands),
+            [
+                (
+                    ValidationLevel::Warning,
+                    codes::BRAND.to_string(),
+                    "Brand name 'ACME  filaments' in data/acme_2 is also used by the brand in data/acme".to_string(),
+                    Some("data/acme_2/brand.json".to_string()),
+                ),
+                (
+                    ValidationLevel::Warning,
+                    codes::BRAND.to_string(),
+                    "Brand name ' acme\tFilaments ' in data/acme_3 is also used by the brand in data/acme".to_string(),
+                    Some("data/acme_3/brand.json".to_string()),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_names_are_not_compared() {
+        let brands = [("data/a/brand.json", json!({"name": "  "})), ("data/b/brand.json", json!({"name": ""}))];
+        assert!(issues(&brands).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about variants whose `color_name` matches other variants' only after ignoring
+/// case and repeated whitespace ("galaxy black" vs "Galaxy Black"), which points at
+/// inconsistent data entry. The spelling used by the most variants is taken as the
+/// reference (the first seen on a tie) and each variant spelling it differently is
+/// reported. `variant_entries` are (path_label, parsed variant.json).
+pub fn validate_color_name_consistency(variant_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    // normalized name -> spellings in first-seen order, each with the variants using it
+    let mut groups: Vec<Vec<(&str, Vec<&str>)>> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+
+    for (path_str, data) in variant_entries {
+        let Some(name) = data.get("color_name").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let normalized = name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if normalized.is_empty() {
+            continue;
+        }
+        let idx = *group_of.entry(normalized).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        let spellings = &mut groups[idx];
+        match spellings.iter_mut().find(|(spelling, _)| *spelling == name) {
+            Some((_, paths)) => paths.push(path_str),
+            None => spellings.push((name, vec![path_str])),
+        }
+    }
+
+    for spellings in groups.iter().filter(|s| s.len() > 1) {
+        let reference = spellings
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, (_, paths))| (paths.len(), std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let (reference_name, reference_paths) = &spellings[reference];
+        for (i, (name, paths)) in spellings.iter().enumerate() {
+            if i == reference {
+                continue;
+            }
+            for path_str in paths {
+                result.add(ValidationError::warning(
+                    codes::COLOR,
+                    format!(
+                        "color_name '{}' is spelled '{}' by {} other variant(s), e.g. {}",
+                        name,
+                        reference_name,
+                        reference_paths.len(),
+                        reference_paths[0]
+                    ),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn issues(variants: &[(&str, &str)]) -> Vec<(String, Option<String>)> {
+        let data: Vec<(&str, Value)> =
+            variants.iter().map(|(path, name)| (*path, json!({"color_name": name}))).collect();
+        let entries: Vec<(&str, &Value)> = data.iter().map(|(path, data)| (*path, data)).collect();
+        validate_color_name_consistency(&entries)
+            .errors
+            .into_iter()
+            .map(|e| (e.message, e.path))
+            .collect()
+    }
+
+    #[test]
+    fn consistent_and_distinct_names_pass() {
+        assert!(issues(&[("a", "Galaxy Black"), ("b", "Galaxy Black"), ("c", "Galaxy Blue")]).is_empty());
+    }
+
+    #[test]
+    fn most_common_spelling_is_the_reference() {
+        assert_eq!(
+            issues(&[("a", "galaxy black"), ("b", "Galaxy Black"), ("c", "Galaxy  Black"), ("d", "Galaxy Black")]),
+            [
+                (
+                    "color_name 'galaxy black' is spelled 'Galaxy Black' by 2 other variant(s), e.g. b".to_string(),
+                    Some("a".to_string()),
+                ),
+                (
+                    "color_name 'Galaxy  Black' is spelled 'Galaxy Black' by 2 other variant(s), e.g. b".to_string(),
+                    Some("c".to_string()),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_spelling_wins_a_tie() {
+        assert_eq!(
+            issues(&[("a", "Red"), ("b", "RED"), ("c", "RED"), ("d", "Red")]),
+            [
+                ("color_name 'RED' is spelled 'Red' by 2 other variant(s), e.g. a".to_string(), Some("b".to_string())),
+                ("color_name 'RED' is spelled 'Red' by 2 other variant(s), e.g. a".to_string(), Some("c".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_are_reported_separately() {
+        let messages: Vec<String> =
+            issues(&[("a", "Red"), ("b", "Blue"), ("c", "red"), ("d", "BLUE")]).into_iter().map(|(m, _)| m).collect();
+        assert_eq!(
+            messages,
+            [
+                "color_name 'red' is spelled 'Red' by 1 other variant(s), e.g. a",
+                "color_name 'BLUE' is spelled 'Blue' by 1 other variant(s), e.g. b",
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// What the `if` field of a `ConditionalRule` must hold for the rule to apply.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// The field is present and not null
+    Present,
+    /// The field equals this JSON value
+    Equals(Value),
+}
+
+/// What the `then` field must hold once a `ConditionalRule` applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    /// Present and not null
+    Present,
+    /// Present and not null, an empty string, an empty array or an empty object
+    NonEmpty,
+}
+
+/// An if/then rule between two top-level fields of one object, for constraints that are
+/// awkward to express in JSON Schema. Written as
+///
+/// `<schema>: if <field>[ == <JSON value>] then <field>[ nonempty]`
+///
+/// e.g. `sizes: if available == true then purchase_links nonempty` or
+/// `sizes: if spool_weight then filament_weight`. Without `== <value>` the rule applies when the field is set
+/// (present and not null); without `nonempty` the other field only has to be set.
+/// "sizes" rules are checked on every size of a sizes.json (including sizes inlined in
+/// variant.json), rules for other schema names on each file validated against it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionalRule {
+    pub schema: String,
+    pub if_field: String,
+    pub condition: Condition,
+    pub then_field: String,
+    pub requirement: Requirement,
+}
+
+impl FromStr for ConditionalRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, String> {
+        let invalid = |why: &str| format!("Invalid conditional rule '{}': {}", rule, why);
+        let (schema, body) = rule.split_once(':').ok_or_else(|| invalid("expected '<schema>: if ...'"))?;
+        let body = body.trim().strip_prefix("if ").ok_or_else(|| invalid("expected 'if' after the schema name"))?;
+        let (condition, requirement) = body.split_once(" then ").ok_or_else(|| invalid("expected 'then'"))?;
+
+        let (if_field, condition) = match condition.split_once("==") {
+            Some((field, value)) => {
+                let value = serde_json::from_str(value.trim())
+                    .map_err(|e| invalid(&format!("'{}' is not a JSON value: {}", value.trim(), e)))?;
+                (field.trim(), Condition::Equals(value))
+            }
+            None => (condition.trim(), Condition::Present),
+        };
+        let requirement = requirement.trim();
+        let (then_field, requirement) = match requirement.strip_suffix(" nonempty") {
+            Some(field) => (field.trim(), Requirement::NonEmpty),
+            None => (requirement, Requirement::Present),
+        };
+
+        let schema = schema.trim();
+        let names_ok = [schema, if_field, then_field]
+            .iter()
+            .all(|name| !name.is_empty() && !name.contains(char::is_whitespace));
+        if !names_ok {
+            return Err(invalid("the schema and field names must be single words"));
+        }
+        Ok(Self {
+            schema: schema.to_string(),
+            if_field: if_field.to_string(),
+            condition,
+            then_field: then_field.to_string(),
+            requirement,
+        })
+    }
+}
+
+impl ConditionalRule {
+    /// How `object` breaks the rule, or `None` if the rule holds or doesn't apply.
+    fn violation(&self, object: &Value) -> Option<String> {
+        let when = match &self.condition {
+            Condition::Present => {
+                object.get(&self.if_field).filter(|v| !v.is_null())?;
+                "is set".to_string()
+            }
+            Condition::Equals(expected) => {
+                object.get(&self.if_field).filter(|v| *v == expected)?;
+                format!("is {}", expected)
+            }
+        };
+        let satisfied = match (object.get(&self.then_field), self.requirement) {
+            (None | Some(Value::Null), _) => false,
+            (Some(Value::String(s)), Requirement::NonEmpty) => !s.is_empty(),
+            (Some(Value::Array(a)), Requirement::NonEmpty) => !a.is_empty(),
+            (Some(Value::Object(o)), Requirement::NonEmpty) => !o.is_empty(),
+            (Some(_), _) => true,
+        };
+        if satisfied {
+            return None;
+        }
+        let must = match self.requirement {
+            Requirement::Present => "must be set",
+            Requirement::NonEmpty => "must be non-empty",
+        };
+        Some(format!("{} {} when {} {}", self.then_field, must, self.if_field, when))
+    }
+}
+
+/// Report every object breaking one of `rules` as a "Content" error.
+/// `json_entries` is a list of (path_label, schema name, parsed JSON) and
+/// `sizes_entries` a list of (path_label, parsed sizes.json Value).
+pub fn validate_conditional_rules(
+    json_entries: &[(&str, &str, &Value)],
+    sizes_entries: &[(&str, &Value)],
+    rules: &[ConditionalRule],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for rule in rules {
+        if rule.schema == "sizes" {
+            for (path_str, sizes_data) in sizes_entries {
+                let Some(sizes_arr) = sizes_data.as_array() else {
+                    continue;
+                };
+                for (idx, size) in sizes_arr.iter().enumerate() {
+                    if let Some(violation) = rule.violation(size) {
+                        result.add(ValidationError::error(
+                            codes::CONTENT,
+                            format!("Size at $[{}]: {}", idx, violation),
+                            Some(path_str.to_string()),
+                        ));
+                    }
+                }
+            }
+            continue;
+        }
+
+        for (path_str, _, data) in json_entries.iter().filter(|(_, schema, _)| *schema == rule.schema) {
+            if let Some(violation) = rule.violation(data) {
+                result.add(ValidationError::error(codes::CONTENT, violation, Some(path_str.to_string())));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::util::DEFAULT_CONDITIONAL_RULES;
+
+    fn rule(text: &str) -> ConditionalRule {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(
+            rule("sizes: if available == true then purchase_links nonempty"),
+            ConditionalRule {
+                schema: "sizes".to_string(),
+                if_field: "available".to_string(),
+                condition: Condition::Equals(json!(true)),
+                then_field: "purchase_links".to_string(),
+                requirement: Requirement::NonEmpty,
+            }
+        );
+        assert_eq!(
+            rule("  sizes :if spool_weight   then filament_weight "),
+            ConditionalRule {
+                schema: "sizes".to_string(),
+                if_field: "spool_weight".to_string(),
+                condition: Condition::Present,
+                then_field: "filament_weight".to_string(),
+                requirement: Requirement::Present,
+            }
+        );
+        assert_eq!(rule(r#"filament: if kind == "dual color" then colors"#).condition, Condition::Equals(json!("dual color")));
+        assert!(DEFAULT_CONDITIONAL_RULES.iter().all(|r| r.parse::<ConditionalRule>().is_ok()));
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for text in [
+            "if gtin then ean",
+            "sizes: gtin then ean",
+            "sizes: if gtin ean",
+            "sizes: if available == yes then purchase_links",
+            "sizes: if spool weight then filament_weight",
+            ": if gtin then ean",
+            "sizes: if gtin then ",
+        ] {
+            let err = text.parse::<ConditionalRule>().unwrap_err();
+            assert!(err.starts_with("Invalid conditional rule"), "{text}: {err}");
+        }
+    }
+
+    #[test]
+    fn present_condition_and_requirement() {
+        let rule = rule("sizes: if spool_weight then filament_weight");
+        assert_eq!(rule.violation(&json!({})), None);
+        assert_eq!(rule.violation(&json!({"spool_weight": null})), None);
+        assert_eq!(rule.violation(&json!({"spool_weight": 200, "filament_weight": 0})), None);
+        assert_eq!(
+            rule.violation(&json!({"spool_weight": 200, "filament_weight": null})).as_deref(),
+            Some("filament_weight must be set when spool_weight is set")
+        );
+    }
+
+    #[test]
+    fn equals_condition_and_nonempty_requirement() {
+        let rule = rule("sizes: if available == true then purchase_links nonempty");
+        assert_eq!(rule.violation(&json!({"available": false})), None);
+        assert_eq!(rule.violation(&json!({"available": "true"})), None);
+        assert_eq!(rule.violation(&json!({"available": true, "purchase_links": [{}]})), None);
+        for links in [json!(null), json!([]), json!({}), json!("")] {
+            assert_eq!(
+                rule.violation(&json!({"available": true, "purchase_links": links})).as_deref(),
+                Some("purchase_links must be non-empty when available is true")
+            );
+        }
+        assert!(rule.violation(&json!({"available": true})).is_some());
+    }
+
+    #[test]
+    fn reports_sizes_by_index_and_other_schemas_by_file() {
+        let sizes = json!([
+            {"available": true, "purchase_links": [{"store_id": "shop"}]},
+            {"available": true, "purchase_links": []},
+        ]);
+        let filament = json!({"kind": "dual color"});
+        let material = json!({"kind": "dual color"});
+        let rules = [
+            rule("sizes: if available == true then purchase_links nonempty"),
+            rule(r#"filament: if kind == "dual color" then colors"#),
+        ];
+
+        let result = validate_conditional_rules(
+            &[("filament.json", "filament", &filament), ("material.json", "material", &material)],
+            &[("sizes.json", &sizes)],
+            &rules,
+        );
+        let errors: Vec<_> = result.errors.iter().map(|e| (e.path.as_deref().unwrap(), e.message.as_str())).collect();
+        assert_eq!(
+            errors,
+            [
+                ("sizes.json", "Size at $[1]: purchase_links must be non-empty when available is true"),
+                ("filament.json", "colors must be set when kind is \"dual color\""),
+            ]
+        );
+        assert!(result.errors.iter().all(|e| e.category == codes::CONTENT));
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about sizes.json files whose sizes list more than one `diameter`: the sizes of a
+/// variant normally share one, so 1.75 next to 2.85 usually means sizes were copied from
+/// another variant. Numeric and numeric-string diameters are compared by value; sizes
+/// without one are ignored. Each entry is (path_label, parsed sizes.json Value).
+pub fn validate_variant_diameter_consistency(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        // Diameter in thousandths of a millimetre -> indexes of the sizes using it
+        let mut by_diameter: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+        for (idx, size) in sizes_arr.iter().enumerate() {
+            let diameter = match size.get("diameter") {
+                Some(Value::Number(n)) => n.as_f64(),
+                Some(Value::String(s)) => s.trim().parse::<f64>().ok(),
+                _ => None,
+            };
+            if let Some(d) = diameter.filter(|d| d.is_finite()) {
+                by_diameter.entry((d * 1000.0).round() as i64).or_default().push(idx);
+            }
+        }
+
+        if by_diameter.len() > 1 {
+            let listed: Vec<String> = by_diameter
+                .iter()
+                .map(|(micrometres, indexes)| {
+                    let at: Vec<String> = indexes.iter().map(|i| format!("$[{}]", i)).collect();
+                    format!("{} at {}", *micrometres as f64 / 1000.0, at.join(", "))
+                })
+                .collect();
+            result.add(ValidationError::warning(
+                codes::SIZES,
+                format!("Sizes mix diameters: {}", listed.join("; ")),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(sizes: Value) -> Vec<String> {
+        validate_variant_diameter_consistency(&[("sizes.json", &sizes)])
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn one_diameter_passes() {
+        assert!(messages(json!([{"diameter": 1.75}, {"diameter": "1.75"}, {"weight": 1000}])).is_empty());
+    }
+
+    #[test]
+    fn diameters_are_compared_to_the_micrometre() {
+        // Float noise below half a micrometre is the same diameter
+        assert!(messages(json!([{"diameter": 1.75}, {"diameter": 1.7500004}, {"diameter": " 1.7499996 "}])).is_empty());
+        assert_eq!(
+            messages(json!([{"diameter": 1.75}, {"diameter": 1.751}])),
+            ["Sizes mix diameters: 1.75 at $[0]; 1.751 at $[1]"]
+        );
+    }
+
+    #[test]
+    fn mixed_diameters_are_listed_by_value() {
+        let sizes = json!([
+            {"diameter": 2.85},
+            {"diameter": 1.75},
+            {"diameter": "2.85"},
+            {"diameter": "thick"},
+            {"diameter": 1.75},
+        ]);
+        assert_eq!(messages(sizes), ["Sizes mix diameters: 1.75 at $[1], $[4]; 2.85 at $[0], $[2]"]);
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about string values longer than the limit set for their key in `limits`
+/// (key -> maximum length in characters). Schemas often leave lengths open, so e.g. a
+/// 10,000-character brand name would otherwise validate.
+/// Each entry is (path_label, parsed JSON Value); objects are walked recursively, so
+/// nested keys are checked too.
+pub fn validate_field_lengths(entries: &[(&str, &Value)], limits: &BTreeMap<String, usize>) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    if limits.is_empty() {
+        return result;
+    }
+
+    for (path_str, data) in entries {
+        walk(data, "$", limits, path_str, &mut result);
+    }
+
+    result
+}
+
+fn walk(
+    value: &Value,
+    json_path: &str,
+    limits: &BTreeMap<String, usize>,
+    path_str: &str,
+    result: &mut ValidationResult,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = format!("{}.{}", json_path, key);
+                if let Value::String(s) = child {
+                    if let Some(&max) = limits.get(key) {
+                        let len = s.chars().count();
+                        if len > max {
+                            result.add(ValidationError::warning(
+                                codes::CONTENT,
+                                format!(
+                                    "Value at {} is {} characters long (at most {} expected)",
+                                    child_path, len, max
+                                ),
+                                Some(path_str.to_string()),
+                            ));
+                        }
+                    }
+                } else {
+                    walk(child, &child_path, limits, path_str, result);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                walk(item, &format!("{}[{}]", json_path, idx), limits, path_str, result);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(data: Value, limits: &[(&str, usize)]) -> Vec<String> {
+        let limits = limits.iter().map(|(key, max)| (key.to_string(), *max)).collect();
+        validate_field_lengths(&[("brand.json", &data)], &limits)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn values_within_the_limit_pass() {
+        let data = json!({"name": "Acme", "website": "https://acme.example"});
+        assert!(messages(data, &[("name", 4)]).is_empty());
+    }
+
+    #[test]
+    fn length_is_counted_in_characters() {
+        // 5 characters, 7 bytes
+        assert!(messages(json!({"name": "Crème"}), &[("name", 5)]).is_empty());
+        assert_eq!(
+            messages(json!({"name": "Crèmes"}), &[("name", 5)]),
+            ["Value at $.name is 6 characters long (at most 5 expected)"]
+        );
+    }
+
+    #[test]
+    fn nested_keys_are_checked_with_their_path() {
+        let data = json!({
+            "a_name": "ok",
+            "sizes": [{"store": {"name": "Long store name"}}, {"name": "Shop"}],
+        });
+        assert_eq!(
+            messages(data, &[("name", 10), ("a_name", 10)]),
+            ["Value at $.sizes[0].store.name is 15 characters long (at most 10 expected)"]
+        );
+    }
+
+    #[test]
+    fn keys_without_a_limit_and_non_strings_are_ignored() {
+        let data = json!({"description": "x".repeat(1000), "name": 12345678901_u64});
+        assert!(messages(data.clone(), &[("name", 3)]).is_empty());
+        assert!(messages(data, &[]).is_empty());
+    }
+}
@@ -1,9 +1,12 @@
 use serde_json::Value;
 
+use crate::codes;
 use crate::types::{ValidationError, ValidationResult};
 use crate::util::{cleanse_folder_name, ILLEGAL_CHARACTERS};
 
 /// Validate that a folder name matches the expected value from its JSON data.
+/// A `json_key` that is missing, null or not a string is an error, since the folder
+/// name can't be checked without it.
 pub fn validate_folder_name(
     actual_folder_name: &str,
     json_data: &Value,
@@ -15,7 +18,22 @@ pub fn validate_folder_name(
 
     let expected_name = match json_data.get(json_key).and_then(|v| v.as_str()) {
         Some(name) => cleanse_folder_name(name),
-        None => return result,
+        None => {
+            let problem = match json_data.get(json_key) {
+                None => "is missing from the JSON".to_string(),
+                Some(Value::Null) => "is null in the JSON".to_string(),
+                Some(other) => format!("is {} in the JSON, not a string", other),
+            };
+            result.add(ValidationError::error(
+                codes::FOLDER,
+                format!(
+                    "Cannot check folder name '{}': '{}' {}",
+                    actual_folder_name, json_key, problem
+                ),
+                path_str,
+            ));
+            return result;
+        }
     };
 
     if actual_folder_name != expected_name {
@@ -23,7 +41,7 @@ pub fn validate_folder_name(
 
         if !has_illegal_chars {
             result.add(ValidationError::error(
-                "Folder",
+                codes::FOLDER,
                 format!(
                     "Folder name '{}' does not match '{}' value '{}' in JSON",
                     actual_folder_name, json_key, expected_name
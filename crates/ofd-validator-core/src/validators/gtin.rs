@@ -1,15 +1,118 @@
 use regex::Regex;
 use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::LazyLock;
 
+use crate::codes;
+use crate::options::GtinOptions;
 use crate::types::{ValidationError, ValidationResult};
+use crate::util::{brand_dirs, owning_brand};
 
-static GTIN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9]{12,13}$").unwrap());
-static EAN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9]{13}$").unwrap());
+static DIGITS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9]+$").unwrap());
+
+/// GS1 barcode formats accepted in the `gtin`/`ean` fields of sizes.json.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GtinFormat {
+    /// 12 digits, the UPC-A barcodes used in North America
+    Gtin12,
+    /// 13 digits, the EAN-13 barcodes used everywhere else
+    Gtin13,
+}
+
+impl GtinFormat {
+    pub fn digits(self) -> usize {
+        match self {
+            GtinFormat::Gtin12 => 12,
+            GtinFormat::Gtin13 => 13,
+        }
+    }
+
+    /// The format with the same number of digits as `code`, if any.
+    pub fn detect(code: &str) -> Option<Self> {
+        match code.len() {
+            12 => Some(GtinFormat::Gtin12),
+            13 => Some(GtinFormat::Gtin13),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for GtinFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GtinFormat::Gtin12 => write!(f, "GTIN-12 (UPC-A)"),
+            GtinFormat::Gtin13 => write!(f, "GTIN-13 (EAN-13)"),
+        }
+    }
+}
+
+/// The GS1 check digit for `payload` (all digits but the last). Weights alternate 3, 1, ...
+/// starting from the rightmost payload digit, so the same rule covers every GTIN length.
+pub fn gs1_check_digit(payload: &str) -> u32 {
+    let sum: u32 = payload
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { d })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// `code` as a GTIN-13: a GTIN-12 (UPC-A) gains a leading zero, which is how the same
+/// product is encoded as an EAN-13. `None` for anything that isn't 12 or 13 digits.
+fn as_gtin13(code: &str) -> Option<String> {
+    if !DIGITS_RE.is_match(code) {
+        return None;
+    }
+    match GtinFormat::detect(code)? {
+        GtinFormat::Gtin12 => Some(format!("0{}", code)),
+        GtinFormat::Gtin13 => Some(code.to_string()),
+    }
+}
+
+/// Check one barcode field against the `allowed` formats, returning the problem if any.
+fn check_code(code: &str, allowed: &[GtinFormat]) -> Option<String> {
+    let format = GtinFormat::detect(code).filter(|f| allowed.contains(f));
+    let format = match format {
+        Some(f) if DIGITS_RE.is_match(code) => f,
+        _ => {
+            let lengths: Vec<String> = allowed.iter().map(|f| f.digits().to_string()).collect();
+            let names: Vec<String> = allowed.iter().map(|f| f.to_string()).collect();
+            let lengths = match lengths.as_slice() {
+                [one] => format!("exactly {}", one),
+                _ => lengths.join(" or "),
+            };
+            return Some(format!("must be {} digits ({})", lengths, names.join(", ")));
+        }
+    };
+
+    let (payload, check) = code.split_at(code.len() - 1);
+    let expected = gs1_check_digit(payload);
+    if check.chars().next().and_then(|c| c.to_digit(10)) != Some(expected) {
+        return Some(format!(
+            "detected {}, but the check digit should be {}",
+            format, expected
+        ));
+    }
+    None
+}
 
 /// Validate GTIN/EAN fields in pre-loaded sizes.json entries.
 /// Each entry is (path_label, parsed sizes.json Value).
 pub fn validate_gtin_ean(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
+    validate_gtin_ean_with_options(sizes_entries, &GtinOptions::default())
+}
+
+/// Like `validate_gtin_ean`, with the formats accepted by each field taken from `options`.
+///
+/// The length picks the format (12 digits is GTIN-12, 13 is GTIN-13) and the value must
+/// then carry the correct GS1 check digit. A size with both fields must use the same code
+/// in each, where a GTIN-12 matches the EAN-13 formed by prefixing it with "0".
+pub fn validate_gtin_ean_with_options(
+    sizes_entries: &[(&str, &Value)],
+    options: &GtinOptions,
+) -> ValidationResult {
     let mut result = ValidationResult::default();
 
     for (path_str, sizes_data) in sizes_entries {
@@ -22,37 +125,74 @@ pub fn validate_gtin_ean(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
             let gtin = size.get("gtin").and_then(|v| v.as_str());
             let ean = size.get("ean").and_then(|v| v.as_str());
 
+            // The string checks below never see an unquoted code. One written with a
+            // fraction or exponent (e.g. 4.006381333931e12) is parsed as a float, so its
+            // digits can't be trusted; an integer loses any leading zero
+            for (field, code) in [("gtin", codes::GTIN), ("ean", codes::EAN)] {
+                let Some(n) = size.get(field).and_then(|v| v.as_number()) else {
+                    continue;
+                };
+                let error = if n.is_f64() {
+                    ValidationError::error(
+                        code,
+                        format!(
+                            "Numeric {} at $[{}] was read as the float {}, which may have lost digits; quote the code as a string",
+                            field, idx, n
+                        ),
+                        Some(path_str.to_string()),
+                    )
+                } else {
+                    ValidationError::warning(
+                        code,
+                        format!(
+                            "Numeric {} at $[{}] is the number {}, which drops leading zeros and isn't checked; quote the code as a string",
+                            field, idx, n
+                        ),
+                        Some(path_str.to_string()),
+                    )
+                };
+                result.add(error);
+            }
+
             if let Some(gtin_val) = gtin {
-                if !GTIN_RE.is_match(gtin_val) {
+                if let Some(problem) = check_code(gtin_val, &options.gtin_formats) {
                     result.add(ValidationError::error(
-                        "GTIN",
-                        format!("Invalid gtin at $[{}]: must be 12 or 13 digits", idx),
+                        codes::GTIN,
+                        format!("Invalid gtin at $[{}]: {}", idx, problem),
                         Some(path_str.to_string()),
                     ));
                 }
             }
 
             if let Some(ean_val) = ean {
-                if !EAN_RE.is_match(ean_val) {
+                if let Some(problem) = check_code(ean_val, &options.ean_formats) {
                     result.add(ValidationError::error(
-                        "EAN",
-                        format!("Invalid ean at $[{}]: must be exactly 13 digits", idx),
+                        codes::EAN,
+                        format!("Invalid ean at $[{}]: {}", idx, problem),
                         Some(path_str.to_string()),
                     ));
                 }
             }
 
-            // When both present and both 13 digits, they must match
+            // When both are present they must encode the same product, comparing a
+            // 12-digit code as its zero-prefixed 13-digit form
             if let (Some(gtin_val), Some(ean_val)) = (gtin, ean) {
-                if gtin_val.len() == 13 && ean_val.len() == 13 && gtin_val != ean_val {
-                    result.add(ValidationError::error(
-                        "GTIN/EAN",
-                        format!(
-                            "Mismatch at $[{}]: gtin and ean are both 13 digits but not equal",
-                            idx
-                        ),
-                        Some(path_str.to_string()),
-                    ));
+                if let (Some(gtin13), Some(ean13)) = (as_gtin13(gtin_val), as_gtin13(ean_val)) {
+                    if gtin13 != ean13 {
+                        let message = if gtin_val.len() == ean_val.len() {
+                            format!(
+                                "Mismatch at $[{}]: gtin and ean are both {} digits but not equal",
+                                idx,
+                                gtin_val.len()
+                            )
+                        } else {
+                            format!(
+                                "Mismatch at $[{}]: gtin '{}' and ean '{}' should differ only by a leading zero",
+                                idx, gtin_val, ean_val
+                            )
+                        };
+                        result.add(ValidationError::error(codes::GTIN_EAN, message, Some(path_str.to_string())));
+                    }
                 }
             }
         }
@@ -60,3 +200,174 @@ pub fn validate_gtin_ean(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
 
     result
 }
+
+/// Report barcodes used under more than one brand. A GTIN identifies one manufacturer's
+/// product, so the same code in two brands' sizes.json files is always a data error.
+///
+/// `brand_entries` are (path_label, parsed brand.json); a sizes.json belongs to the brand
+/// whose folder is its closest ancestor. `gtin` and `ean` are compared in their 13-digit
+/// form, and malformed codes (already reported by `validate_gtin_ean`) are ignored.
+pub fn validate_gtin_brand_conflict(
+    brand_entries: &[(&str, &Value)],
+    sizes_entries: &[(&str, &Value)],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let brand_dirs = brand_dirs(brand_entries);
+
+    // (path, size index, field, code as given, code as GTIN-13, brand)
+    let mut occurrences = Vec::new();
+    for (path_str, sizes_data) in sizes_entries {
+        let (Some(sizes_arr), Some(brand)) = (sizes_data.as_array(), owning_brand(&brand_dirs, path_str)) else {
+            continue;
+        };
+        for (idx, size) in sizes_arr.iter().enumerate() {
+            for field in ["gtin", "ean"] {
+                if let Some(code) = size.get(field).and_then(|v| v.as_str()) {
+                    if let Some(gtin13) = as_gtin13(code) {
+                        occurrences.push((*path_str, idx, field, code, gtin13, brand));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut brands_by_code: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for (_, _, _, _, gtin13, brand) in &occurrences {
+        brands_by_code.entry(gtin13.as_str()).or_default().insert(brand);
+    }
+
+    for (path_str, idx, field, code, gtin13, brand) in &occurrences {
+        let brands = &brands_by_code[gtin13.as_str()];
+        if brands.len() > 1 {
+            let others: Vec<&str> = brands.iter().copied().filter(|b| b != brand).collect();
+            result.add(ValidationError::error(
+                codes::GTIN,
+                format!(
+                    "{} '{}' at $[{}] of brand '{}' is also used by brand '{}'",
+                    field,
+                    code,
+                    idx,
+                    brand,
+                    others.join("', '")
+                ),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+/// Warn about sizes carrying neither a `gtin` nor an `ean`, for datasets whose policy is
+/// that every size is identified by a barcode. Empty strings and `null` count as missing;
+/// malformed codes count as present, since `validate_gtin_ean` already reports them.
+/// Each entry is (path_label, parsed sizes.json Value).
+pub fn validate_barcode_presence(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (idx, size) in sizes_arr.iter().enumerate() {
+            let has_code = |field: &str| match size.get(field) {
+                Some(Value::String(s)) => !s.trim().is_empty(),
+                Some(Value::Number(_)) => true,
+                _ => false,
+            };
+            if !has_code("gtin") && !has_code("ean") {
+                result.add(ValidationError::warning(
+                    codes::GTIN,
+                    format!("Size at $[{}] has neither a gtin nor an ean", idx),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::ValidationLevel;
+
+    fn issues(sizes: Value) -> Vec<(ValidationLevel, String, String)> {
+        validate_gtin_ean(&[("sizes.json", &sizes)])
+            .errors
+            .into_iter()
+            .map(|e| (e.level, e.category, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn float_gtin_is_an_error() {
+        let sizes: Value = serde_json::from_str(r#"[{"gtin": 4.006381333931e12}]"#).unwrap();
+        assert_eq!(
+            issues(sizes),
+            [(
+                ValidationLevel::Error,
+                codes::GTIN.to_string(),
+                "Numeric gtin at $[0] was read as the float 4006381333931.0, which may have lost digits; quote the code as a string".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn integer_gtin_and_ean_are_warnings() {
+        assert_eq!(
+            issues(json!([{"gtin": 4012345678901_u64}, {"ean": 12345678905_u64}])),
+            [
+                (
+                    ValidationLevel::Warning,
+                    codes::GTIN.to_string(),
+                    "Numeric gtin at $[0] is the number 4012345678901, which drops leading zeros and isn't checked; quote the code as a string".to_string(),
+                ),
+                (
+                    ValidationLevel::Warning,
+                    codes::EAN.to_string(),
+                    "Numeric ean at $[1] is the number 12345678905, which drops leading zeros and isn't checked; quote the code as a string".to_string(),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_codes_pass() {
+        assert!(issues(json!([{"gtin": "4012345678901", "ean": "4012345678901"}])).is_empty());
+    }
+
+    #[test]
+    fn gtin12_matches_its_zero_prefixed_ean13() {
+        assert!(issues(json!([{"gtin": "012345678905", "ean": "0012345678905"}])).is_empty());
+    }
+
+    #[test]
+    fn gtin12_and_unrelated_ean13_mismatch() {
+        assert_eq!(
+            issues(json!([{"gtin": "012345678905", "ean": "4012345678901"}])),
+            [(
+                ValidationLevel::Error,
+                codes::GTIN_EAN.to_string(),
+                "Mismatch at $[0]: gtin '012345678905' and ean '4012345678901' should differ only by a leading zero".to_string(),
+            )]
+        );
+    }
+
+    #[test]
+    fn equal_length_codes_must_be_equal() {
+        assert_eq!(
+            issues(json!([{"gtin": "4012345678901", "ean": "0012345678905"}])),
+            [(
+                ValidationLevel::Error,
+                codes::GTIN_EAN.to_string(),
+                "Mismatch at $[0]: gtin and ean are both 13 digits but not equal".to_string(),
+            )]
+        );
+    }
+}
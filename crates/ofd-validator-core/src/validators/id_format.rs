@@ -0,0 +1,143 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+use crate::util::ILLEGAL_CHARACTERS;
+
+/// Validate the `id` field of pre-loaded entity JSON against `pattern`.
+/// Each entry is (path_label, parsed JSON Value); entries without a string `id` are skipped.
+pub fn validate_id_format(entries: &[(&str, &Value)], pattern: &Regex) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, data) in entries {
+        if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+            if !pattern.is_match(id) {
+                result.add(ValidationError::error(
+                    codes::ID,
+                    format!("Invalid id '{}': must match {}", id, pattern.as_str()),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+/// Check that the `id` field of pre-loaded entity JSON maps to a clean folder name.
+/// Each entry is (path_label, parsed JSON Value); entries without a string `id` are skipped.
+///
+/// `validate_folder_name` skips ids containing illegal characters, so this reports
+/// them (along with uppercase letters and whitespace) instead of letting them go unnoticed.
+pub fn validate_id_slug(entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, data) in entries {
+        let id = match data.get("id").and_then(|v| v.as_str()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let mut problems = Vec::new();
+        if id.chars().any(|c| c.is_uppercase()) {
+            problems.push("uppercase letters".to_string());
+        }
+        if id.chars().any(|c| c.is_whitespace()) {
+            problems.push("whitespace".to_string());
+        }
+        let mut illegal: Vec<char> = id.chars().filter(|c| ILLEGAL_CHARACTERS.contains(c)).collect();
+        illegal.sort_unstable();
+        illegal.dedup();
+        if !illegal.is_empty() {
+            let chars: Vec<String> = illegal.iter().map(|c| format!("'{}'", c)).collect();
+            problems.push(format!("illegal characters {}", chars.join(", ")));
+        }
+
+        if !problems.is_empty() {
+            result.add(ValidationError::error(
+                codes::ID,
+                format!(
+                    "id '{}' does not produce a clean folder name: contains {}",
+                    id,
+                    problems.join(", ")
+                ),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::util::DEFAULT_ID_PATTERN;
+
+    fn format_messages(ids: &[Value]) -> Vec<String> {
+        let pattern = Regex::new(DEFAULT_ID_PATTERN).unwrap();
+        let data: Vec<Value> = ids.iter().map(|id| json!({"id": id})).collect();
+        let entries: Vec<(&str, &Value)> = data.iter().map(|d| ("brand.json", d)).collect();
+        validate_id_format(&entries, &pattern).errors.into_iter().map(|e| e.message).collect()
+    }
+
+    fn slug_messages(id: &str) -> Vec<String> {
+        let data = json!({"id": id});
+        validate_id_slug(&[("brand.json", &data)]).errors.into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn valid_ids_pass() {
+        assert!(format_messages(&[json!("acme"), json!("acme-3d"), json!("3dfil")]).is_empty());
+        for id in ["acme", "acme-3d", "acme_pro", "acme.pro"] {
+            assert!(slug_messages(id).is_empty(), "{id}");
+        }
+    }
+
+    #[test]
+    fn ids_not_matching_the_pattern_are_errors() {
+        assert_eq!(
+            format_messages(&[json!("-acme"), json!("acme_pro"), json!(""), json!(42)]),
+            [
+                "Invalid id '-acme': must match ^[a-z0-9][a-z0-9-]*$",
+                "Invalid id 'acme_pro': must match ^[a-z0-9][a-z0-9-]*$",
+                "Invalid id '': must match ^[a-z0-9][a-z0-9-]*$",
+            ]
+        );
+    }
+
+    #[test]
+    fn uppercase_ids_are_not_clean() {
+        assert_eq!(
+            slug_messages("Acme"),
+            ["id 'Acme' does not produce a clean folder name: contains uppercase letters"]
+        );
+    }
+
+    #[test]
+    fn whitespace_in_ids_is_not_clean() {
+        assert_eq!(
+            slug_messages("acme pro\t"),
+            ["id 'acme pro\t' does not produce a clean folder name: contains whitespace"]
+        );
+    }
+
+    #[test]
+    fn illegal_characters_are_listed_once_in_order() {
+        assert_eq!(
+            slug_messages("a/b?c/d#"),
+            ["id 'a/b?c/d#' does not produce a clean folder name: contains illegal characters '#', '/', '?'"]
+        );
+    }
+
+    #[test]
+    fn every_problem_is_listed() {
+        assert_eq!(
+            slug_messages("Acme Pro!"),
+            ["id 'Acme Pro!' does not produce a clean folder name: contains uppercase letters, whitespace, illegal characters '!'"]
+        );
+    }
+}
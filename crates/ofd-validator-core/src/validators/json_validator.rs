@@ -1,10 +1,17 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use jsonschema::Retrieve;
+use rayon::prelude::*;
 use serde_json::Value;
 
+use crate::codes;
+use crate::options::JsonOptions;
 use crate::schema_cache::SchemaCache;
 use crate::types::{ValidationError, ValidationResult};
+use crate::util::json_pointer_location;
 
-/// Custom retriever for resolving $ref URIs against our schema cache.
+/// Custom retriever for resolving $ref URIs against our schema cache, falling back to
+/// the cache's `RefResolver` (if any) for URIs it doesn't hold.
 struct SchemaRetriever {
     cache: SchemaCache,
 }
@@ -27,9 +34,25 @@ impl Retrieve for SchemaRetriever {
             .or_else(|| base_uri.strip_prefix("json-schema://"))
             .unwrap_or(base_uri);
 
-        self.cache
-            .resolve_ref(lookup_key)
-            .ok_or_else(|| format!("Schema not found: {}", uri_str).into())
+        if let Some(schema) = self.cache.resolve_ref(lookup_key) {
+            return Ok(schema);
+        }
+        match self.cache.ref_resolver() {
+            Some(resolver) => resolver(base_uri),
+            None => Err(format!("Schema not found: {}", uri_str).into()),
+        }
+    }
+}
+
+/// Cut `message` down to `max_len` characters, marking that it was shortened.
+fn truncate_message(message: String, max_len: Option<usize>) -> String {
+    match max_len {
+        Some(max_len) if message.chars().count() > max_len => {
+            let mut truncated: String = message.chars().take(max_len).collect();
+            truncated.push_str("…[truncated]");
+            truncated
+        }
+        _ => message,
     }
 }
 
@@ -39,6 +62,41 @@ pub fn validate_json(
     schema_name: &str,
     schema_cache: &SchemaCache,
     path_label: Option<&str>,
+) -> ValidationResult {
+    validate_json_with_options(data, schema_name, schema_cache, path_label, &JsonOptions::default())
+}
+
+/// Like `validate_json`, with control over how long error messages may get.
+pub fn validate_json_with_options(
+    data: &Value,
+    schema_name: &str,
+    schema_cache: &SchemaCache,
+    path_label: Option<&str>,
+    options: &JsonOptions,
+) -> ValidationResult {
+    validate_named(data, None, schema_name, schema_cache, path_label, options)
+}
+
+/// Like `validate_json_with_options`, where `source` is the text `data` was parsed from:
+/// each schema failure also gets the `line` and `column` of the offending value.
+pub fn validate_json_source(
+    data: &Value,
+    source: &str,
+    schema_name: &str,
+    schema_cache: &SchemaCache,
+    path_label: Option<&str>,
+    options: &JsonOptions,
+) -> ValidationResult {
+    validate_named(data, Some(source), schema_name, schema_cache, path_label, options)
+}
+
+fn validate_named(
+    data: &Value,
+    source: Option<&str>,
+    schema_name: &str,
+    schema_cache: &SchemaCache,
+    path_label: Option<&str>,
+    options: &JsonOptions,
 ) -> ValidationResult {
     let mut result = ValidationResult::default();
     let path_str = path_label.map(|s| s.to_string());
@@ -47,7 +105,7 @@ pub fn validate_json(
         Some(s) => s,
         None => {
             result.add(ValidationError::error(
-                "JSON",
+                codes::JSON,
                 format!("Schema '{}' not found", schema_name),
                 path_str,
             ));
@@ -55,18 +113,102 @@ pub fn validate_json(
         }
     };
 
-    let retriever = SchemaRetriever {
+    validate_against(data, source, schema, &cache_options(schema_cache), path_label, options)
+}
+
+/// Compiler options resolving `$ref`s through `schema_cache`.
+fn cache_options(schema_cache: &SchemaCache) -> jsonschema::ValidationOptions {
+    let mut builder = jsonschema::options();
+    builder.with_retriever(SchemaRetriever {
         cache: schema_cache.clone(),
-    };
+    });
+    builder
+}
+
+/// Why the named schema doesn't compile (including unresolvable `$ref`s), or `None` if
+/// it compiles or isn't in the cache. Lets a batch compile each schema once up front
+/// instead of reporting the same failure for every file.
+pub fn schema_compile_error(schema_name: &str, schema_cache: &SchemaCache) -> Option<String> {
+    let schema = schema_cache.get(schema_name)?;
+    cache_options(schema_cache).build(schema).err().map(|e| e.to_string())
+}
+
+/// Check each schema a batch of files uses once, up front. `file_schemas` holds the
+/// schema name of every file. Returns the names that are missing from `schema_cache` or
+/// fail to compile, whose files should be skipped, with one `codes::SCHEMA` error per
+/// such schema saying how many files it leaves unchecked, instead of one per file.
+pub fn unusable_schemas<'a, I>(file_schemas: I, schema_cache: &SchemaCache) -> (BTreeSet<&'a str>, ValidationResult)
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut file_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for name in file_schemas {
+        *file_counts.entry(name).or_default() += 1;
+    }
+    let problems: BTreeMap<&str, (String, usize)> = file_counts
+        .into_par_iter()
+        .filter_map(|(name, files)| {
+            let problem = match schema_cache.get(name) {
+                None => "not found".to_string(),
+                Some(_) => format!("failed to compile: {}", schema_compile_error(name, schema_cache)?),
+            };
+            Some((name, (problem, files)))
+        })
+        .collect();
+
+    let mut result = ValidationResult::default();
+    for (name, (problem, files)) in &problems {
+        result.add(ValidationError::error(
+            codes::SCHEMA,
+            format!(
+                "Schema '{}' {}; {} file{} skipped",
+                name,
+                problem,
+                files,
+                if *files == 1 { "" } else { "s" }
+            ),
+            None,
+        ));
+    }
+    (problems.into_keys().collect(), result)
+}
+
+/// Validate `data` against an inline `schema`, without a `SchemaCache`, e.g. to try a
+/// schema change or for callers managing their own schemas. `$ref`s can only point
+/// inside `schema` itself; use `validate_json` for schemas referencing each other.
+pub fn validate_value_against_schema(data: &Value, schema: &Value) -> ValidationResult {
+    validate_value_against_schema_with_options(data, schema, None, &JsonOptions::default())
+}
+
+/// Like `validate_value_against_schema`, with a path label for the reported errors and
+/// control over message length.
+pub fn validate_value_against_schema_with_options(
+    data: &Value,
+    schema: &Value,
+    path_label: Option<&str>,
+    options: &JsonOptions,
+) -> ValidationResult {
+    validate_against(data, None, schema, &jsonschema::options(), path_label, options)
+}
+
+/// Compile `schema` with `builder` and report every way `data` fails it, locating the
+/// failures in `source` when given.
+fn validate_against(
+    data: &Value,
+    source: Option<&str>,
+    schema: &Value,
+    builder: &jsonschema::ValidationOptions,
+    path_label: Option<&str>,
+    options: &JsonOptions,
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let path_str = path_label.map(|s| s.to_string());
 
-    let validator = match jsonschema::options()
-        .with_retriever(retriever)
-        .build(schema)
-    {
+    let validator = match builder.build(schema) {
         Ok(v) => v,
         Err(e) => {
             result.add(ValidationError::error(
-                "JSON",
+                codes::JSON,
                 format!("Schema compilation error: {}", e),
                 path_str,
             ));
@@ -76,15 +218,98 @@ pub fn validate_json(
 
     for error in validator.iter_errors(data) {
         let json_path_str = format!("{}", error.instance_path);
-        result.add(ValidationError::error(
-            "JSON",
-            format!(
-                "Schema validation failed: {} at {}",
-                error, json_path_str
-            ),
-            path_str.clone(),
-        ));
+        let location = source.and_then(|s| json_pointer_location(s, &json_path_str));
+        result.add(
+            ValidationError::error(
+                codes::JSON,
+                format!(
+                    "Schema validation failed: {} at {}",
+                    truncate_message(error.to_string(), options.max_message_len),
+                    json_path_str
+                ),
+                path_str.clone(),
+            )
+            .with_location(location),
+        );
     }
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn cache_with_external_ref() -> SchemaCache {
+        let brand = json!({
+            "type": "object",
+            "properties": {"name": {"$ref": "https://schemas.example/common.json#/definitions/name"}},
+        });
+        SchemaCache::from_map(HashMap::from([("brand".to_string(), brand)]))
+    }
+
+    fn common() -> Value {
+        json!({"definitions": {"name": {"type": "string", "minLength": 2}}})
+    }
+
+    #[test]
+    fn external_ref_without_a_resolver_fails_to_compile() {
+        let cache = cache_with_external_ref();
+        let error = schema_compile_error("brand", &cache).unwrap();
+        assert!(error.contains("Schema not found: https://schemas.example/common.json"), "{error}");
+
+        let messages: Vec<String> = validate_json(&json!({"name": "Acme"}), "brand", &cache, None)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("Schema not found"), "{}", messages[0]);
+    }
+
+    #[test]
+    fn external_ref_compiles_through_the_resolver() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requested);
+        let cache = cache_with_external_ref().with_ref_resolver(move |uri| {
+            seen.lock().unwrap().push(uri.to_string());
+            match uri {
+                "https://schemas.example/common.json" => Ok(common()),
+                _ => Err(format!("unexpected {}", uri).into()),
+            }
+        });
+
+        assert_eq!(schema_compile_error("brand", &cache), None);
+        assert!(validate_json(&json!({"name": "Acme"}), "brand", &cache, None).errors.is_empty());
+        let errors = validate_json(&json!({"name": "A"}), "brand", &cache, None).errors;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("shorter than 2 characters"), "{}", errors[0].message);
+
+        // The resolver gets the URI without its fragment
+        let requested = requested.lock().unwrap();
+        assert!(!requested.is_empty());
+        assert!(requested.iter().all(|uri| uri == "https://schemas.example/common.json"));
+    }
+
+    #[test]
+    fn resolver_errors_are_reported() {
+        let cache = cache_with_external_ref().with_ref_resolver(|_| Err("offline".into()));
+        let error = schema_compile_error("brand", &cache).unwrap();
+        assert!(error.contains("offline"), "{error}");
+    }
+
+    #[test]
+    fn cached_schemas_take_precedence_over_the_resolver() {
+        let brand = json!({"properties": {"name": {"$ref": "common.json#/definitions/name"}}});
+        let common = json!({"$id": "common.json", "definitions": {"name": {"type": "string"}}});
+        let schemas = HashMap::from([("brand".to_string(), brand), ("material".to_string(), common)]);
+        let cache =
+            SchemaCache::from_map(schemas).with_ref_resolver(|uri| Err(format!("resolver called for {}", uri).into()));
+        assert_eq!(schema_compile_error("brand", &cache), None);
+    }
+}
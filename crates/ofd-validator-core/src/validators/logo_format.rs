@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+use crate::util::{brand_dirs, owning_brand};
+
+/// Warn about brands whose entities reference logos in more than one format, e.g.
+/// `logo.svg` in brand.json but `logo.png` in a material.json, for datasets whose policy
+/// is one format per brand. `jpeg` counts as `jpg`; references without an extension are
+/// ignored.
+///
+/// `brand_entries` are (path_label, parsed brand.json) and `entries` every
+/// (path_label, parsed JSON) that may carry a `logo` field, including the brand.json
+/// files; each belongs to the brand whose folder is its closest ancestor. A brand gets
+/// one warning, reported against its brand.json.
+pub fn validate_logo_format_consistency(
+    brand_entries: &[(&str, &Value)],
+    entries: &[(&str, &Value)],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let brands = brand_dirs(brand_entries);
+
+    // brand id -> format -> files referencing a logo in that format
+    let mut formats: BTreeMap<&str, BTreeMap<String, BTreeSet<&str>>> = BTreeMap::new();
+    for (path_str, data) in entries {
+        let Some(logo) = data.get("logo").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let Some(ext) = Path::new(logo).extension() else {
+            continue;
+        };
+        let Some(brand) = owning_brand(&brands, path_str) else {
+            continue;
+        };
+        let format = match ext.to_string_lossy().to_lowercase().as_str() {
+            "jpeg" => "jpg".to_string(),
+            other => other.to_string(),
+        };
+        formats.entry(brand).or_default().entry(format).or_default().insert(path_str);
+    }
+
+    for (brand, by_format) in formats.into_iter().filter(|(_, f)| f.len() > 1) {
+        let Some((brand_path, _)) = brand_entries
+            .iter()
+            .find(|(_, data)| data.get("id").and_then(|v| v.as_str()) == Some(brand))
+        else {
+            continue;
+        };
+        let brand_dir = Path::new(brand_path).parent().unwrap_or(Path::new(""));
+        let listed: Vec<String> = by_format
+            .iter()
+            .map(|(format, files)| {
+                let files: Vec<String> = files
+                    .iter()
+                    .map(|f| {
+                        let relative = Path::new(f).strip_prefix(brand_dir).unwrap_or(Path::new(f));
+                        relative.to_string_lossy().replace('\\', "/")
+                    })
+                    .collect();
+                format!("{} in {}", format, files.join(", "))
+            })
+            .collect();
+        result.add(ValidationError::warning(
+            codes::LOGO,
+            format!("Logos of brand '{}' mix formats: {}", brand, listed.join("; ")),
+            Some(brand_path.to_string()),
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn issues(entries: &[(&str, Value)]) -> Vec<(String, Option<String>)> {
+        let all: Vec<(&str, &Value)> = entries.iter().map(|(path, data)| (*path, data)).collect();
+        let brands: Vec<(&str, &Value)> =
+            all.iter().copied().filter(|(path, _)| path.ends_with("brand.json")).collect();
+        validate_logo_format_consistency(&brands, &all)
+            .errors
+            .into_iter()
+            .map(|e| (e.message, e.path))
+            .collect()
+    }
+
+    #[test]
+    fn one_format_per_brand_passes() {
+        let entries = [
+            ("data/acme/brand.json", json!({"id": "acme", "logo": "logo.svg"})),
+            ("data/acme/PLA/material.json", json!({"material": "PLA", "logo": "assets/logo.SVG"})),
+            ("data/other/brand.json", json!({"id": "other", "logo": "logo.png"})),
+        ];
+        assert!(issues(&entries).is_empty());
+    }
+
+    #[test]
+    fn mixed_formats_are_reported_once_against_the_brand() {
+        let entries = [
+            ("data/acme/brand.json", json!({"id": "acme", "logo": "logo.svg"})),
+            ("data/acme/PLA/material.json", json!({"material": "PLA", "logo": "logo.png"})),
+            ("data/acme/PETG/material.json", json!({"material": "PETG", "logo": "logo.png"})),
+            ("data/other/brand.json", json!({"id": "other", "logo": "logo.png"})),
+        ];
+        assert_eq!(
+            issues(&entries),
+            [(
+                "Logos of brand 'acme' mix formats: png in PETG/material.json, PLA/material.json; svg in brand.json"
+                    .to_string(),
+                Some("data/acme/brand.json".to_string()),
+            )]
+        );
+    }
+
+    #[test]
+    fn jpeg_counts_as_jpg() {
+        let entries = [
+            ("data/acme/brand.json", json!({"id": "acme", "logo": "logo.jpg"})),
+            ("data/acme/PLA/material.json", json!({"material": "PLA", "logo": "logo.JPEG"})),
+        ];
+        assert!(issues(&entries).is_empty());
+
+        let entries = [
+            ("data/acme/brand.json", json!({"id": "acme", "logo": "logo.jpeg"})),
+            ("data/acme/PLA/material.json", json!({"material": "PLA", "logo": "logo.png"})),
+        ];
+        assert_eq!(
+            issues(&entries),
+            [(
+                "Logos of brand 'acme' mix formats: jpg in brand.json; png in PLA/material.json".to_string(),
+                Some("data/acme/brand.json".to_string()),
+            )]
+        );
+    }
+
+    #[test]
+    fn references_without_an_extension_are_ignored() {
+        let entries = [
+            ("data/acme/brand.json", json!({"id": "acme", "logo": "logo.svg"})),
+            ("data/acme/PLA/material.json", json!({"material": "PLA", "logo": "logo"})),
+            ("data/acme/PETG/material.json", json!({"material": "PETG", "logo": "assets/logo"})),
+        ];
+        assert!(issues(&entries).is_empty());
+    }
+}
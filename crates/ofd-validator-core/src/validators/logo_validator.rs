@@ -1,13 +1,304 @@
-use image::GenericImageView;
-use regex::Regex;
-use std::sync::LazyLock;
+use image::{DynamicImage, GenericImageView, ImageFormat, ImageReader, Limits};
+use serde::Serialize;
+use std::io::Cursor;
 
+use crate::codes;
+use crate::options::{LogoConstraints, LogoOptions};
 use crate::types::{ValidationError, ValidationResult};
-use crate::util::{LOGO_MAX_SIZE, LOGO_MIN_SIZE};
+use crate::util::{logo_escape_error, logo_ref_escapes, normalize_logo_ref};
 
-static LOGO_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^logo\.(png|jpg|svg)$").unwrap()
-});
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// `ftyp` brands of HEIF-based images: AVIF and HEIC stills and sequences.
+const HEIF_BRANDS: &[&[u8]] = &[
+    b"avif", b"avis", b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"mif1", b"msf1",
+];
+
+/// The kind of entity a logo belongs to, selecting which `LogoConstraints` from
+/// `LogoOptions` apply.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogoContext {
+    #[default]
+    Brand,
+    Store,
+}
+
+/// Fields from a PNG's IHDR chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PngHeader {
+    pub width: u32,
+    pub height: u32,
+    /// Bits per sample (or per palette index): 1, 2, 4, 8 or 16
+    pub bit_depth: u8,
+    /// 0 = grayscale, 2 = RGB, 3 = palette, 4 = grayscale + alpha, 6 = RGBA
+    pub color_type: u8,
+}
+
+/// Read the IHDR chunk of a PNG without decoding the image.
+/// Returns `None` if `content` doesn't start with a PNG signature followed by IHDR.
+pub fn parse_png_header(content: &[u8]) -> Option<PngHeader> {
+    // Signature (8) + chunk length (4) + "IHDR" (4) + width (4) + height (4) + depth (1) + color type (1)
+    if content.len() < 26 || !content.starts_with(PNG_SIGNATURE) || &content[12..16] != b"IHDR" {
+        return None;
+    }
+    let be_u32 = |at: usize| u32::from_be_bytes([content[at], content[at + 1], content[at + 2], content[at + 3]]);
+    Some(PngHeader {
+        width: be_u32(16),
+        height: be_u32(20),
+        bit_depth: content[24],
+        color_type: content[25],
+    })
+}
+
+/// The chunks of a PNG before `IEND`, as (type, data length). Empty if `content` isn't
+/// a PNG; stops at the first chunk that runs past the end of `content`.
+fn png_chunks(content: &[u8]) -> Vec<(&[u8], u64)> {
+    let mut chunks = Vec::new();
+    if !content.starts_with(PNG_SIGNATURE) {
+        return chunks;
+    }
+    let mut at = PNG_SIGNATURE.len();
+    // Length (4) + type (4) + data + CRC (4)
+    while at + 8 <= content.len() {
+        let len = u32::from_be_bytes([content[at], content[at + 1], content[at + 2], content[at + 3]]) as u64;
+        let kind = &content[at + 4..at + 8];
+        if kind == b"IEND" {
+            break;
+        }
+        let next = match usize::try_from(len + 12).ok().and_then(|n| at.checked_add(n)) {
+            Some(next) if next <= content.len() => next,
+            _ => break,
+        };
+        chunks.push((kind, len));
+        at = next;
+    }
+    chunks
+}
+
+/// "APNG" or "animated WebP" if `content` declares animation through an `acTL` chunk or
+/// the animation flag of a WebP `VP8X` header, without decoding it. Decoders show such
+/// files as their first frame, so the animation would otherwise go unnoticed.
+pub fn detect_animation(content: &[u8]) -> Option<&'static str> {
+    if content.starts_with(PNG_SIGNATURE) {
+        return png_chunks(content).iter().any(|(kind, _)| *kind == b"acTL").then_some("APNG");
+    }
+    // "RIFF" + size (4) + "WEBP", then the VP8X chunk: "VP8X" + size (4) + flags (1)
+    let animated_webp = content.len() > 20
+        && content.starts_with(b"RIFF")
+        && &content[8..12] == b"WEBP"
+        && &content[12..16] == b"VP8X"
+        && content[20] & 0x02 != 0;
+    animated_webp.then_some("animated WebP")
+}
+
+/// Bytes taken up by metadata in a PNG or JPEG, per chunk or segment type and largest
+/// first: ancillary PNG chunks except `tRNS` (which carries transparency), and JPEG
+/// `APPn` and `COM` segments before the image data. Empty for other formats.
+pub fn raster_metadata(content: &[u8]) -> Vec<(String, u64)> {
+    let mut sizes: Vec<(String, u64)> = Vec::new();
+    let mut add = |kind: String, size: u64| match sizes.iter_mut().find(|(k, _)| *k == kind) {
+        Some((_, total)) => *total += size,
+        None => sizes.push((kind, size)),
+    };
+
+    if content.starts_with(PNG_SIGNATURE) {
+        for (kind, len) in png_chunks(content) {
+            // A lowercase first letter marks an ancillary chunk
+            if kind[0].is_ascii_lowercase() && kind != b"tRNS" {
+                add(String::from_utf8_lossy(kind).to_string(), len + 12);
+            }
+        }
+    } else if content.starts_with(&[0xFF, 0xD8]) {
+        let mut at = 2;
+        while at + 4 <= content.len() && content[at] == 0xFF {
+            let marker = content[at + 1];
+            // Fill bytes and markers without a length
+            if marker == 0xFF || marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+                at += if marker == 0xFF { 1 } else { 2 };
+                continue;
+            }
+            // Start of scan or end of image: no metadata after this
+            if marker == 0xDA || marker == 0xD9 {
+                break;
+            }
+            // The length counts itself but not the marker; stop at a corrupt or truncated segment
+            let len = u16::from_be_bytes([content[at + 2], content[at + 3]]) as u64;
+            if len < 2 || at + 2 + len as usize > content.len() {
+                break;
+            }
+            match marker {
+                0xE0..=0xEF => add(format!("APP{}", marker - 0xE0), len + 2),
+                0xFE => add("COM".to_string(), len + 2),
+                _ => {}
+            }
+            at += len as usize + 2;
+        }
+    }
+
+    sizes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    sizes
+}
+
+/// Decode a raster image, refusing files whose header declares more than `max_pixels`
+/// pixels so a crafted or corrupt logo can't stall or exhaust memory in a parallel batch.
+fn decode_limited(content: &[u8], max_pixels: Option<u64>) -> Result<DynamicImage, String> {
+    let reader = || ImageReader::new(Cursor::new(content)).with_guessed_format().map_err(|e| e.to_string());
+
+    let mut decoder = reader()?;
+    if let Some(max_pixels) = max_pixels {
+        // Reads only the header, so the size is known before any pixel buffer is allocated
+        let (width, height) = reader()?.into_dimensions().map_err(|e| e.to_string())?;
+        if u64::from(width) * u64::from(height) > max_pixels {
+            return Err(format!(
+                "image declares {}x{} pixels, over the decode limit of {} pixels",
+                width, height, max_pixels
+            ));
+        }
+        let mut limits = Limits::default();
+        // 8 bytes per pixel covers 16-bit RGBA, the widest format the decoders produce
+        limits.max_alloc = Some(max_pixels.saturating_mul(8));
+        decoder.limits(limits);
+    }
+    decoder.decode().map_err(|e| e.to_string())
+}
+
+/// The ISO-BMFF boxes directly inside `data`, as (type, body). Stops at the first box
+/// whose size doesn't fit.
+fn bmff_boxes(mut data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut boxes = Vec::new();
+    while data.len() >= 8 {
+        let (header, size) = match u32::from_be_bytes([data[0], data[1], data[2], data[3]]) {
+            // Box extends to the end of its container
+            0 => (8, data.len() as u64),
+            // 64-bit size follows the type
+            1 if data.len() >= 16 => (16, u64::from_be_bytes(data[8..16].try_into().unwrap())),
+            1 => break,
+            n => (8, u64::from(n)),
+        };
+        if size < header as u64 || size > data.len() as u64 {
+            break;
+        }
+        boxes.push((&data[4..8], &data[header..size as usize]));
+        data = &data[size as usize..];
+    }
+    boxes
+}
+
+fn find_box<'a>(data: &'a [u8], kind: &[u8]) -> Option<&'a [u8]> {
+    bmff_boxes(data).into_iter().find(|(k, _)| *k == kind).map(|(_, body)| body)
+}
+
+/// Whether `content` is a HEIF container (AVIF or HEIC), judged by its `ftyp` brands.
+pub fn is_heif(content: &[u8]) -> bool {
+    let Some((kind, ftyp)) = bmff_boxes(content).into_iter().next() else {
+        return false;
+    };
+    if kind != b"ftyp" || ftyp.len() < 8 {
+        return false;
+    }
+    // Major brand, minor version, then compatible brands
+    std::iter::once(&ftyp[..4])
+        .chain(ftyp[8..].chunks_exact(4))
+        .any(|brand| HEIF_BRANDS.contains(&brand))
+}
+
+/// Read the image size of an AVIF/HEIC file from its `ispe` (image spatial extents)
+/// properties, without decoding it. When several images are present (thumbnails, grid
+/// tiles, alpha planes) the largest is taken as the primary image.
+/// Returns `None` if no `meta/iprp/ipco/ispe` box can be found.
+pub fn parse_heif_dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    let meta = find_box(content, b"meta")?;
+    // `meta` is a full box: version and flags precede its children
+    let iprp = find_box(meta.get(4..)?, b"iprp")?;
+    let ipco = find_box(iprp, b"ipco")?;
+    bmff_boxes(ipco)
+        .into_iter()
+        .filter(|(kind, body)| *kind == b"ispe" && body.len() >= 12)
+        .map(|(_, body)| {
+            let be_u32 = |at: usize| u32::from_be_bytes([body[at], body[at + 1], body[at + 2], body[at + 3]]);
+            (be_u32(4), be_u32(8))
+        })
+        .max_by_key(|(width, height)| u64::from(*width) * u64::from(*height))
+}
+
+/// `'logo.png', 'logo.jpg' or 'logo.svg'` for the given extensions.
+fn allowed_names(extensions: &[String]) -> String {
+    let names: Vec<String> = extensions.iter().map(|ext| format!("'logo.{}'", ext)).collect();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => "(no extensions allowed)".to_string(),
+    }
+}
+
+/// Report a raster logo's size against `constraints` and the opt-in `allowed_sizes`.
+fn check_dimensions(
+    width: u32,
+    height: u32,
+    constraints: &LogoConstraints,
+    options: &LogoOptions,
+    path_str: &Option<String>,
+    result: &mut ValidationResult,
+) {
+    if constraints.require_square && width != height {
+        if width.abs_diff(height) <= constraints.square_tolerance {
+            result.add(ValidationError::warning(
+                codes::LOGO,
+                format!(
+                    "Logo is not quite square (width={}, height={}); within the {} px tolerance",
+                    width, height, constraints.square_tolerance
+                ),
+                path_str.clone(),
+            ));
+        } else {
+            result.add(ValidationError::error(
+                codes::LOGO,
+                format!(
+                    "Logo must be square (width={}, height={})",
+                    width, height
+                ),
+                path_str.clone(),
+            ));
+        }
+    }
+
+    if width < constraints.min_size || height < constraints.min_size {
+        result.add(ValidationError::error(
+            codes::LOGO,
+            format!(
+                "Logo dimensions too small (minimum {}x{})",
+                constraints.min_size, constraints.min_size
+            ),
+            path_str.clone(),
+        ));
+    }
+
+    if let Some(allowed) = &options.allowed_sizes {
+        if width == height && !allowed.contains(&width) {
+            let sizes: Vec<String> = allowed.iter().map(|s| s.to_string()).collect();
+            result.add(ValidationError::warning(
+                codes::LOGO,
+                format!(
+                    "Logo size {}x{} is not a standard size (allowed: {})",
+                    width, height, sizes.join(", ")
+                ),
+                path_str.clone(),
+            ));
+        }
+    }
+
+    if width > constraints.max_size || height > constraints.max_size {
+        result.add(ValidationError::error(
+            codes::LOGO,
+            format!(
+                "Logo dimensions too large (maximum {}x{})",
+                constraints.max_size, constraints.max_size
+            ),
+            path_str.clone(),
+        ));
+    }
+}
 
 fn after_doctype_end(s: &str) -> &str {
     s.find('>').map(|i| s[i + 1..].trim_start()).unwrap_or(s)
@@ -16,7 +307,8 @@ fn after_doctype_end(s: &str) -> &str {
 /// Validate a logo file from its raw bytes.
 ///
 /// `filename` is the file's basename (e.g. "logo.png").
-/// `logo_name` is the value from brand.json/store.json "logo" field, if available.
+/// `logo_name` is the value from brand.json/store.json "logo" field, if available. It may
+/// name a file in a subfolder of the entity directory (e.g. "assets/logo.png").
 /// `path_label` is used for error reporting only.
 pub fn validate_logo(
     content: &[u8],
@@ -24,31 +316,86 @@ pub fn validate_logo(
     logo_name: Option<&str>,
     path_label: Option<&str>,
 ) -> ValidationResult {
+    validate_logo_with_options(content, filename, logo_name, path_label, &LogoOptions::default())
+}
+
+/// Like `validate_logo`, additionally applying the opt-in checks enabled in `options`.
+/// The logo is checked as a brand logo.
+pub fn validate_logo_with_options(
+    content: &[u8],
+    filename: &str,
+    logo_name: Option<&str>,
+    path_label: Option<&str>,
+    options: &LogoOptions,
+) -> ValidationResult {
+    validate_logo_in_context(content, filename, logo_name, path_label, LogoContext::Brand, options)
+}
+
+/// Like `validate_logo_with_options`, applying the size constraints `options` sets for
+/// `context` (`options.brand` or `options.store`).
+pub fn validate_logo_in_context(
+    content: &[u8],
+    filename: &str,
+    logo_name: Option<&str>,
+    path_label: Option<&str>,
+    context: LogoContext,
+    options: &LogoOptions,
+) -> ValidationResult {
+    let constraints = options.constraints(context);
     let mut result = ValidationResult::default();
     let path_str = path_label.map(|s| s.to_string());
+    // Loaders resolve the reference with backslashes turned into '/'
+    let normalized_name = logo_name.map(normalize_logo_ref);
     let parent_path = path_label.map(|p| {
-        // Strip the filename to get the parent directory for logo_name errors
-        p.rsplit_once('/').map(|(parent, _)| parent.to_string()).unwrap_or_else(|| p.to_string())
+        // Strip the logo reference (which may include subfolders) to get the entity
+        // directory for logo_name errors
+        normalized_name
+            .as_deref()
+            .and_then(|name| p.strip_suffix(name))
+            .and_then(|parent| parent.strip_suffix('/'))
+            .or_else(|| p.rsplit_once('/').map(|(parent, _)| parent))
+            .unwrap_or(p)
+            .to_string()
     });
 
-    // Check if logo name contains "/"
-    if let Some(name) = logo_name {
-        if name.contains('/') {
+    // Nested paths like "assets/logo.png" are resolved relative to the entity folder,
+    // but must not point outside it
+    if let (Some(name), Some(normalized)) = (logo_name, normalized_name.as_deref()) {
+        if name.contains('\\') {
             result.add(ValidationError::error(
-                "Logo",
-                format!("Logo path '{}' contains '/' - only use filename", name),
-                parent_path,
+                codes::LOGO,
+                format!(
+                    "Logo path '{}' uses backslashes; use forward slashes (e.g. '{}') so it resolves on every OS",
+                    name, normalized
+                ),
+                parent_path.clone(),
+            ));
+        }
+        if logo_ref_escapes(normalized) {
+            result.add(logo_escape_error(name, parent_path.as_deref()));
+        }
+        // The file checked must be the one the "logo" field names, spelled the same way
+        let referenced = normalized.rsplit('/').next().unwrap_or(normalized);
+        if referenced != filename {
+            result.add(ValidationError::error(
+                codes::LOGO,
+                format!("Logo file is named '{}' but the \"logo\" field references '{}'", filename, referenced),
+                path_str.clone(),
             ));
         }
     }
 
     // Validate naming convention
-    if !LOGO_NAME_RE.is_match(filename) {
+    let named_ok = filename
+        .strip_prefix("logo.")
+        .is_some_and(|ext| options.allowed_extensions.iter().any(|allowed| allowed == ext));
+    if !named_ok {
         result.add(ValidationError::error(
-            "Logo",
+            codes::LOGO,
             format!(
-                "Logo name '{}' must be 'logo.png', 'logo.jpg' or 'logo.svg'",
-                filename
+                "Logo name '{}' must be {}",
+                filename,
+                allowed_names(&options.allowed_extensions)
             ),
             path_str.clone(),
         ));
@@ -78,53 +425,126 @@ pub fn validate_logo(
         }
         if !after_comments.to_lowercase().starts_with("<svg") {
             result.add(ValidationError::error(
-                "Logo",
+                codes::LOGO,
                 "File has .svg extension but is not a valid SVG (root element is not <svg>)",
                 path_str,
             ));
         }
+    } else if !is_heif(content) && (filename.ends_with(".avif") || filename.ends_with(".heic")) {
+        let extension = filename.rsplit('.').next().unwrap_or_default();
+        result.add(ValidationError::error(
+            codes::LOGO,
+            format!(
+                "File has .{} extension but is not a valid AVIF/HEIC image (no HEIF brand in its 'ftyp' box)",
+                extension
+            ),
+            path_str,
+        ));
+    } else if is_heif(content) {
+        // The image crate is built without AVIF/HEIC decoders, so only the container's
+        // declared size is checked
+        match parse_heif_dimensions(content) {
+            Some((width, height)) => {
+                check_dimensions(width, height, constraints, options, &path_str, &mut result);
+                if options.require_transparency {
+                    result.add(ValidationError::warning(
+                        codes::LOGO,
+                        "Transparency of AVIF/HEIC logos can't be checked: this build has no AVIF/HEIC decoder",
+                        path_str,
+                    ));
+                }
+            }
+            None => {
+                result.add(ValidationError::error(
+                    codes::LOGO,
+                    "Failed to read image: no image size found in the AVIF/HEIC container (missing 'ispe' property)",
+                    path_str,
+                ));
+            }
+        }
     } else {
+        if let Some(kind) = detect_animation(content) {
+            result.add(ValidationError::warning(
+                codes::LOGO,
+                format!("Logo is an {}; logos should be a static image", kind),
+                path_str.clone(),
+            ));
+        }
+
+        if let Some(max_bytes) = options.max_metadata_bytes {
+            let metadata = raster_metadata(content);
+            let total: u64 = metadata.iter().map(|(_, size)| size).sum();
+            if total > max_bytes {
+                let parts: Vec<String> = metadata.iter().map(|(kind, size)| format!("{} {} B", kind, size)).collect();
+                result.add(ValidationError::warning(
+                    codes::LOGO,
+                    format!(
+                        "Logo carries {} bytes of metadata ({}), over the {}-byte limit; strip it to shrink the file",
+                        total,
+                        parts.join(", "),
+                        max_bytes
+                    ),
+                    path_str.clone(),
+                ));
+            }
+        }
+
         // Validate dimensions for raster images
-        match image::load_from_memory(content) {
+        match decode_limited(content, options.max_decode_pixels) {
             Ok(img) => {
                 let (width, height) = img.dimensions();
+                check_dimensions(width, height, constraints, options, &path_str, &mut result);
 
-                if width != height {
-                    result.add(ValidationError::error(
-                        "Logo",
-                        format!(
-                            "Logo must be square (width={}, height={})",
-                            width, height
-                        ),
-                        path_str.clone(),
-                    ));
+                if options.verify_header_dimensions {
+                    if let Some(header) = parse_png_header(content) {
+                        if (header.width, header.height) != (width, height) {
+                            result.add(ValidationError::warning(
+                                codes::LOGO,
+                                format!(
+                                    "PNG header reader found {}x{} but the decoder produced {}x{}",
+                                    header.width, header.height, width, height
+                                ),
+                                path_str.clone(),
+                            ));
+                        }
+                    }
                 }
 
-                if width < LOGO_MIN_SIZE || height < LOGO_MIN_SIZE {
-                    result.add(ValidationError::error(
-                        "Logo",
-                        format!(
-                            "Logo dimensions too small (minimum {}x{})",
-                            LOGO_MIN_SIZE, LOGO_MIN_SIZE
-                        ),
-                        path_str.clone(),
-                    ));
+                if options.warn_high_bit_depth {
+                    if let Some(header) = parse_png_header(content) {
+                        if header.bit_depth > 8 {
+                            result.add(ValidationError::warning(
+                                codes::LOGO,
+                                format!(
+                                    "PNG logo uses {}-bit samples; 8-bit is enough for logos and keeps files small",
+                                    header.bit_depth
+                                ),
+                                path_str.clone(),
+                            ));
+                        }
+                    }
                 }
 
-                if width > LOGO_MAX_SIZE || height > LOGO_MAX_SIZE {
-                    result.add(ValidationError::error(
-                        "Logo",
-                        format!(
-                            "Logo dimensions too large (maximum {}x{})",
-                            LOGO_MAX_SIZE, LOGO_MAX_SIZE
-                        ),
-                        path_str,
-                    ));
+                if options.require_transparency
+                    && image::guess_format(content).ok() == Some(ImageFormat::Png)
+                {
+                    // The decoder expands tRNS chunks into an alpha channel, so this
+                    // covers both RGBA/gray-alpha color types and palette transparency.
+                    let message = if !img.color().has_alpha() {
+                        Some("PNG logo has no alpha channel or tRNS chunk; use a transparent background")
+                    } else if img.to_rgba8().pixels().all(|p| p[3] == u8::MAX) {
+                        Some("PNG logo is fully opaque; use a transparent background")
+                    } else {
+                        None
+                    };
+                    if let Some(message) = message {
+                        result.add(ValidationError::warning(codes::LOGO, message, path_str));
+                    }
                 }
             }
             Err(e) => {
                 result.add(ValidationError::error(
-                    "Logo",
+                    codes::LOGO,
                     format!("Failed to read image: {}", e),
                     path_str,
                 ));
@@ -134,3 +554,304 @@ pub fn validate_logo(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200"/>"#;
+
+    /// A PNG made of `chunks` (type, data) between IHDR and IEND, with zeroed CRCs.
+    fn png(chunks: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let ihdr: &[u8] = &[0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0];
+        let mut content = PNG_SIGNATURE.to_vec();
+        for (kind, data) in std::iter::once(&(&b"IHDR"[..], ihdr)).chain(chunks).chain([&(&b"IEND"[..], &[][..])]) {
+            content.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            content.extend_from_slice(kind);
+            content.extend_from_slice(data);
+            content.extend_from_slice(&[0; 4]);
+        }
+        content
+    }
+
+    /// A JPEG made of `segments` (marker, body) after SOI, followed by a scan and EOI.
+    fn jpeg(segments: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut content = vec![0xFF, 0xD8];
+        for (marker, body) in segments {
+            content.extend_from_slice(&[0xFF, *marker]);
+            content.extend_from_slice(&(body.len() as u16 + 2).to_be_bytes());
+            content.extend_from_slice(body);
+        }
+        content.extend_from_slice(&[0xFF, 0xDA, 0, 2, 0xFF, 0xD9]);
+        content
+    }
+
+    /// An ISO-BMFF box with a 32-bit size.
+    fn bmff_box(kind: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(body);
+        data
+    }
+
+    /// An AVIF file declaring one `ispe` property per (width, height) in `sizes`.
+    fn avif(sizes: &[(u32, u32)]) -> Vec<u8> {
+        let ispe: Vec<u8> = sizes
+            .iter()
+            .flat_map(|(width, height)| {
+                let body = [[0; 4], width.to_be_bytes(), height.to_be_bytes()].concat();
+                bmff_box(b"ispe", &body)
+            })
+            .collect();
+        let meta_body = [&[0; 4][..], &bmff_box(b"iprp", &bmff_box(b"ipco", &ispe))].concat();
+        [bmff_box(b"ftyp", b"avif\0\0\0\0mif1"), bmff_box(b"meta", &meta_body)].concat()
+    }
+
+    fn avif_options() -> LogoOptions {
+        let mut options = LogoOptions::default();
+        options.allowed_extensions.push("avif".to_string());
+        options
+    }
+
+    fn avif_messages(content: &[u8]) -> Vec<String> {
+        let path = Some("data/acme/logo.avif");
+        validate_logo_with_options(content, "logo.avif", Some("logo.avif"), path, &avif_options())
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    /// A WebP with a VP8X header carrying `flags`.
+    fn webp_vp8x(flags: u8) -> Vec<u8> {
+        let mut content = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+        content.extend_from_slice(&10u32.to_le_bytes());
+        content.extend_from_slice(&[flags, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        content
+    }
+
+    #[test]
+    fn png_chunks_lists_chunks_before_iend() {
+        let content = png(&[(b"tEXt", b"Title\0Logo"), (b"IDAT", &[0; 5])]);
+        assert_eq!(png_chunks(&content), [(&b"IHDR"[..], 13), (&b"tEXt"[..], 10), (&b"IDAT"[..], 5)]);
+        assert!(png_chunks(SVG).is_empty());
+    }
+
+    #[test]
+    fn png_chunks_stops_at_a_truncated_chunk() {
+        let mut content = png(&[(b"tEXt", b"Title\0Logo"), (b"acTL", &[0; 8])]);
+        // Cut inside the acTL chunk's data
+        content.truncate(PNG_SIGNATURE.len() + 25 + 22 + 12);
+        assert_eq!(png_chunks(&content), [(&b"IHDR"[..], 13), (&b"tEXt"[..], 10)]);
+        assert_eq!(detect_animation(&content), None);
+        // Cut inside its header: only the length is left
+        content.truncate(PNG_SIGNATURE.len() + 25 + 22 + 4);
+        assert_eq!(png_chunks(&content), [(&b"IHDR"[..], 13), (&b"tEXt"[..], 10)]);
+    }
+
+    #[test]
+    fn png_chunks_stops_at_an_overflowing_chunk_length() {
+        let mut content = png(&[(b"tEXt", b"Title\0Logo"), (b"acTL", &[0; 8])]);
+        // The tEXt chunk claims to run far past the end of the file, hiding the acTL after it
+        let text_at = PNG_SIGNATURE.len() + 25;
+        content[text_at..text_at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(png_chunks(&content), [(&b"IHDR"[..], 13)]);
+        assert_eq!(detect_animation(&content), None);
+    }
+
+    #[test]
+    fn apng_is_detected_by_its_actl_chunk() {
+        assert_eq!(detect_animation(&png(&[(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 0]), (b"IDAT", &[0; 5])])), Some("APNG"));
+        assert_eq!(detect_animation(&png(&[(b"IDAT", &[0; 5])])), None);
+        // An acTL after IEND is not part of the image
+        let mut trailing = png(&[]);
+        trailing.extend_from_slice(&png(&[(b"acTL", &[0; 8])])[PNG_SIGNATURE.len()..]);
+        assert_eq!(detect_animation(&trailing), None);
+    }
+
+    #[test]
+    fn png_metadata_sums_ancillary_chunks_by_type() {
+        let content = png(&[
+            (b"iCCP", &[0; 100]),
+            (b"tEXt", &[0; 20]),
+            (b"IDAT", &[0; 500]),
+            (b"tEXt", &[0; 30]),
+        ]);
+        // Each chunk counts its length, type and CRC
+        assert_eq!(raster_metadata(&content), [("iCCP".to_string(), 112), ("tEXt".to_string(), 74)]);
+    }
+
+    #[test]
+    fn png_metadata_excludes_transparency() {
+        let content = png(&[(b"PLTE", &[0; 30]), (b"tRNS", &[0; 300]), (b"gAMA", &[0; 4])]);
+        assert_eq!(raster_metadata(&content), [("gAMA".to_string(), 16)]);
+    }
+
+    #[test]
+    fn jpeg_metadata_sums_app_and_comment_segments() {
+        let content = jpeg(&[
+            (0xE0, &[0; 14]),
+            (0xE1, &[0; 1000]),
+            (0xDB, &[0; 65]),
+            (0xFE, &[0; 10]),
+            (0xE1, &[0; 500]),
+        ]);
+        // Each segment counts its marker and length
+        assert_eq!(
+            raster_metadata(&content),
+            [("APP1".to_string(), 1508), ("APP0".to_string(), 18), ("COM".to_string(), 14)]
+        );
+
+        // Nothing after the start of scan is metadata
+        let mut after_scan = jpeg(&[(0xE0, &[0; 14])]);
+        after_scan.splice(after_scan.len() - 2.., [0xFF, 0xE1, 0x10, 0x00]);
+        assert_eq!(raster_metadata(&after_scan), [("APP0".to_string(), 18)]);
+    }
+
+    #[test]
+    fn jpeg_metadata_stops_at_a_zero_length_segment() {
+        let mut content = jpeg(&[(0xE0, &[0; 14]), (0xE2, &[]), (0xE1, &[0; 100])]);
+        // The APP2 length can't be below 2, since it counts itself
+        let app2_at = 2 + 18;
+        content[app2_at + 2..app2_at + 4].copy_from_slice(&[0, 0]);
+        assert_eq!(raster_metadata(&content), [("APP0".to_string(), 18)]);
+    }
+
+    #[test]
+    fn metadata_of_a_truncated_file_counts_only_complete_parts() {
+        let mut content = jpeg(&[(0xE0, &[0; 14]), (0xE1, &[0; 1000])]);
+        content.truncate(2 + 18 + 100);
+        assert_eq!(raster_metadata(&content), [("APP0".to_string(), 18)]);
+
+        let mut content = png(&[(b"gAMA", &[0; 4]), (b"iCCP", &[0; 1000])]);
+        content.truncate(PNG_SIGNATURE.len() + 25 + 16 + 100);
+        assert_eq!(raster_metadata(&content), [("gAMA".to_string(), 16)]);
+
+        assert!(raster_metadata(&[0xFF, 0xD8, 0xFF]).is_empty());
+        assert!(raster_metadata(SVG).is_empty());
+    }
+
+    #[test]
+    fn animated_webp_is_detected_by_its_vp8x_flag() {
+        assert_eq!(detect_animation(&webp_vp8x(0x02)), Some("animated WebP"));
+        // Alpha and metadata flags alone are a still image
+        assert_eq!(detect_animation(&webp_vp8x(0x10 | 0x08 | 0x04)), None);
+        // Cut before the flags byte
+        assert_eq!(detect_animation(&webp_vp8x(0x02)[..20]), None);
+        assert_eq!(detect_animation(SVG), None);
+    }
+
+    fn messages(filename: &str, logo_name: &str) -> Vec<String> {
+        let path = format!("data/acme/{}", filename);
+        validate_logo(SVG, filename, Some(logo_name), Some(&path))
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn logo_field_matching_the_file_passes() {
+        assert!(messages("logo.svg", "logo.svg").is_empty());
+        assert!(messages("logo.svg", "assets/logo.svg").is_empty());
+    }
+
+    #[test]
+    fn logo_field_naming_another_file_is_an_error() {
+        assert_eq!(
+            messages("logo.svg", "logo.png"),
+            ["Logo file is named 'logo.svg' but the \"logo\" field references 'logo.png'"]
+        );
+    }
+
+    #[test]
+    fn logo_field_differing_in_case_is_an_error() {
+        assert_eq!(
+            messages("Logo.svg", "logo.svg"),
+            [
+                "Logo file is named 'Logo.svg' but the \"logo\" field references 'logo.svg'",
+                "Logo name 'Logo.svg' must be 'logo.png', 'logo.jpg' or 'logo.svg'",
+            ]
+        );
+    }
+
+    #[test]
+    fn backslash_separated_logo_field_is_compared_by_its_last_segment() {
+        assert_eq!(
+            messages("logo.svg", r"assets\logo.svg"),
+            [r"Logo path 'assets\logo.svg' uses backslashes; use forward slashes (e.g. 'assets/logo.svg') so it resolves on every OS"]
+        );
+        assert_eq!(
+            messages("logo.svg", r"assets\logo.png"),
+            [
+                r"Logo path 'assets\logo.png' uses backslashes; use forward slashes (e.g. 'assets/logo.png') so it resolves on every OS",
+                "Logo file is named 'logo.svg' but the \"logo\" field references 'logo.png'",
+            ]
+        );
+    }
+
+    #[test]
+    fn bmff_boxes_reads_32_bit_sizes() {
+        let data = [bmff_box(b"ftyp", b"avif"), bmff_box(b"free", &[])].concat();
+        assert_eq!(bmff_boxes(&data), [(&b"ftyp"[..], &b"avif"[..]), (&b"free"[..], &[][..])]);
+    }
+
+    #[test]
+    fn bmff_box_of_size_zero_extends_to_the_end() {
+        let mut data = bmff_box(b"ftyp", b"avif");
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(b"mdat");
+        data.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(bmff_boxes(&data), [(&b"ftyp"[..], &b"avif"[..]), (&b"mdat"[..], &[1, 2, 3][..])]);
+    }
+
+    #[test]
+    fn bmff_box_of_size_one_has_a_64_bit_size() {
+        let mut data = vec![0, 0, 0, 1];
+        data.extend_from_slice(b"mdat");
+        data.extend_from_slice(&19u64.to_be_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(&bmff_box(b"free", &[]));
+        assert_eq!(bmff_boxes(&data), [(&b"mdat"[..], &[1, 2, 3][..]), (&b"free"[..], &[][..])]);
+
+        // The 64-bit size is cut off
+        assert!(bmff_boxes(&data[..12]).is_empty());
+        // A size smaller than the header
+        let mut short = data.clone();
+        short[8..16].copy_from_slice(&8u64.to_be_bytes());
+        assert!(bmff_boxes(&short).is_empty());
+    }
+
+    #[test]
+    fn bmff_boxes_stops_at_a_box_that_does_not_fit() {
+        let mut data = [bmff_box(b"ftyp", b"avif"), bmff_box(b"meta", &[0; 8])].concat();
+        data.truncate(data.len() - 1);
+        assert_eq!(bmff_boxes(&data), [(&b"ftyp"[..], &b"avif"[..])]);
+    }
+
+    #[test]
+    fn heif_dimensions_come_from_the_largest_ispe() {
+        assert!(is_heif(&avif(&[(1, 1)])));
+        assert_eq!(parse_heif_dimensions(&avif(&[(64, 64), (512, 256), (128, 128)])), Some((512, 256)));
+        assert_eq!(parse_heif_dimensions(&avif(&[])), None);
+        assert!(!is_heif(&png(&[])));
+    }
+
+    #[test]
+    fn avif_logo_is_checked_by_its_declared_size() {
+        assert!(avif_messages(&avif(&[(400, 400)])).is_empty());
+        assert_eq!(avif_messages(&avif(&[(400, 300)])), ["Logo must be square (width=400, height=300)"]);
+        assert_eq!(
+            avif_messages(&avif(&[])),
+            ["Failed to read image: no image size found in the AVIF/HEIC container (missing 'ispe' property)"]
+        );
+    }
+
+    #[test]
+    fn avif_extension_on_other_content_is_an_error() {
+        assert_eq!(
+            avif_messages(&png(&[(b"IDAT", &[0; 5])])),
+            ["File has .avif extension but is not a valid AVIF/HEIC image (no HEIF brand in its 'ftyp' box)"]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Collect the canonical material names from the material_types schema,
+/// i.e. every string listed in an `enum` anywhere in the schema.
+pub fn material_type_names(schema: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_enum_strings(schema, &mut names);
+    names
+}
+
+fn collect_enum_strings(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                if key == "enum" {
+                    if let Value::Array(items) = child {
+                        names.extend(items.iter().filter_map(|v| v.as_str()).map(String::from));
+                    }
+                } else {
+                    collect_enum_strings(child, names);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_enum_strings(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Lowercase alphanumerics only, so "petg", "PET-G" and "PETG" compare equal.
+fn loose_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Check that the `material` value in pre-loaded material.json entries uses the
+/// canonical spelling from `canonical_names` (see `material_type_names`).
+/// Each entry is (path_label, parsed material.json Value).
+///
+/// Values that only differ from a canonical name in casing or punctuation get a
+/// warning suggesting the canonical form; unknown materials are left to schema validation.
+pub fn validate_material_names(
+    material_entries: &[(&str, &Value)],
+    canonical_names: &[String],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, material_data) in material_entries {
+        let material = match material_data.get("material").and_then(|v| v.as_str()) {
+            Some(m) => m,
+            None => continue,
+        };
+        if canonical_names.iter().any(|c| c == material) {
+            continue;
+        }
+
+        let key = loose_key(material);
+        if let Some(canonical) = canonical_names.iter().find(|c| loose_key(c) == key) {
+            result.add(ValidationError::warning(
+                codes::MATERIAL,
+                format!(
+                    "Material '{}' should use the canonical spelling '{}'",
+                    material, canonical
+                ),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn messages(material: &str, canonical: &[&str]) -> Vec<String> {
+        let data = json!({"material": material});
+        validate_material_names(&[("material.json", &data)], &names(canonical))
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn enum_strings_are_collected_from_anywhere_in_the_schema() {
+        let schema = json!({
+            "definitions": {"fibre": {"enum": ["PA-CF", 5, null]}},
+            "oneOf": [{"enum": ["PLA", "PETG"]}, {"const": "TPU"}],
+            "type": "string",
+        });
+        assert_eq!(material_type_names(&schema), names(&["PA-CF", "PLA", "PETG"]));
+        assert!(material_type_names(&json!({"type": "string"})).is_empty());
+    }
+
+    #[test]
+    fn loose_key_ignores_case_and_punctuation() {
+        assert_eq!(loose_key("PET-G"), "petg");
+        assert_eq!(loose_key("pa_cf "), "pacf");
+        assert_eq!(loose_key("Ästhetik 2"), "ästhetik2");
+    }
+
+    #[test]
+    fn canonical_and_unknown_materials_pass() {
+        assert!(messages("PETG", &["PLA", "PETG"]).is_empty());
+        assert!(messages("Wood", &["PLA", "PETG"]).is_empty());
+    }
+
+    #[test]
+    fn variant_spellings_suggest_the_canonical_name() {
+        assert_eq!(
+            messages("pet-g", &["PLA", "PETG"]),
+            ["Material 'pet-g' should use the canonical spelling 'PETG'"]
+        );
+        assert_eq!(
+            messages("PA CF", &["PA-CF"]),
+            ["Material 'PA CF' should use the canonical spelling 'PA-CF'"]
+        );
+    }
+}
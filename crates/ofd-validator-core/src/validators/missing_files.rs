@@ -1,10 +1,13 @@
-use crate::types::{ValidationError, ValidationResult};
+use crate::codes;
+use crate::types::{ValidationError, ValidationLevel, ValidationResult};
 
 /// Describes the file manifest for a variant directory.
 pub struct VariantEntry {
     pub path: String,
     pub has_variant_json: bool,
     pub has_sizes_json: bool,
+    /// Subdirectories of the variant directory, which the hierarchy walk never looks into
+    pub nested_dirs: Vec<String>,
 }
 
 /// Describes the file manifest for a filament directory.
@@ -38,11 +41,15 @@ pub struct StoreEntry {
 pub struct FileManifest {
     pub brands: Vec<BrandEntry>,
     pub stores: Vec<StoreEntry>,
+    /// Directories outside the variant level that contain a sizes.json but no variant.json
+    pub orphan_sizes_dirs: Vec<String>,
 }
 
 /// Build a FileManifest by walking the filesystem.
 #[cfg(feature = "filesystem")]
 pub fn build_file_manifest(data_dir: &std::path::Path, stores_dir: &std::path::Path) -> FileManifest {
+    let data_dir = &crate::util::normalize_dir(data_dir);
+    let stores_dir = &crate::util::normalize_dir(stores_dir);
     let mut brands = Vec::new();
 
     if let Ok(brand_entries) = std::fs::read_dir(data_dir) {
@@ -53,6 +60,8 @@ pub fn build_file_manifest(data_dir: &std::path::Path, stores_dir: &std::path::P
             }
 
             let mut materials = Vec::new();
+            let brand_data = crate::util::load_json(&brand_dir.join("brand.json"));
+            let asset_dir = brand_data.as_ref().and_then(crate::util::logo_asset_dir);
 
             if let Ok(material_entries) = std::fs::read_dir(&brand_dir) {
                 for material_entry in material_entries.filter_map(|e| e.ok()) {
@@ -60,6 +69,10 @@ pub fn build_file_manifest(data_dir: &std::path::Path, stores_dir: &std::path::P
                     if !material_dir.is_dir() {
                         continue;
                     }
+                    // A subfolder holding the brand logo (e.g. "assets/logo.png") is not a material
+                    if asset_dir.is_some_and(|d| material_entry.file_name() == d) {
+                        continue;
+                    }
 
                     let mut filaments = Vec::new();
 
@@ -79,10 +92,21 @@ pub fn build_file_manifest(data_dir: &std::path::Path, stores_dir: &std::path::P
                                         continue;
                                     }
 
+                                    let nested_dirs = match std::fs::read_dir(&variant_dir) {
+                                        Ok(entries) => entries
+                                            .filter_map(|e| e.ok())
+                                            .map(|e| e.path())
+                                            .filter(|p| p.is_dir())
+                                            .map(|p| p.to_string_lossy().to_string())
+                                            .collect(),
+                                        Err(_) => Vec::new(),
+                                    };
+
                                     variants.push(VariantEntry {
                                         path: variant_dir.to_string_lossy().to_string(),
                                         has_variant_json: variant_dir.join("variant.json").exists(),
                                         has_sizes_json: variant_dir.join("sizes.json").exists(),
+                                        nested_dirs,
                                     });
                                 }
                             }
@@ -111,22 +135,43 @@ pub fn build_file_manifest(data_dir: &std::path::Path, stores_dir: &std::path::P
         }
     }
 
-    let mut stores = Vec::new();
-    if let Ok(store_entries) = std::fs::read_dir(stores_dir) {
-        for store_entry in store_entries.filter_map(|e| e.ok()) {
-            let store_dir = store_entry.path();
-            if !store_dir.is_dir() {
-                continue;
-            }
+    let stores = build_store_entries(stores_dir);
 
-            stores.push(StoreEntry {
-                path: store_dir.to_string_lossy().to_string(),
-                has_store_json: store_dir.join("store.json").exists(),
-            });
+    // Variant-level directories are covered above; catch sizes.json at any other depth
+    let mut orphan_sizes_dirs = Vec::new();
+    for entry in walkdir::WalkDir::new(data_dir).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_name() != "sizes.json" || entry.depth() == 5 {
+            continue;
+        }
+        if let Some(dir) = entry.path().parent() {
+            if !dir.join("variant.json").exists() {
+                orphan_sizes_dirs.push(dir.to_string_lossy().to_string());
+            }
         }
     }
 
-    FileManifest { brands, stores }
+    FileManifest { brands, stores, orphan_sizes_dirs }
+}
+
+/// Build a FileManifest covering only the stores directory.
+#[cfg(feature = "filesystem")]
+pub fn build_store_manifest(stores_dir: &std::path::Path) -> FileManifest {
+    FileManifest {
+        brands: Vec::new(),
+        stores: build_store_entries(&crate::util::normalize_dir(stores_dir)),
+        orphan_sizes_dirs: Vec::new(),
+    }
+}
+
+#[cfg(feature = "filesystem")]
+fn build_store_entries(stores_dir: &std::path::Path) -> Vec<StoreEntry> {
+    crate::util::store_dirs(stores_dir)
+        .into_iter()
+        .map(|store_dir| StoreEntry {
+            path: store_dir.to_string_lossy().to_string(),
+            has_store_json: store_dir.join("store.json").exists(),
+        })
+        .collect()
 }
 
 /// Validate required files exist based on the file manifest.
@@ -136,7 +181,7 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
     for brand in &manifest.brands {
         if !brand.has_brand_json {
             result.add(ValidationError::error(
-                "Missing File",
+                codes::MISSING_FILE,
                 "Missing brand.json",
                 Some(brand.path.clone()),
             ));
@@ -145,7 +190,7 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
         for material in &brand.materials {
             if !material.has_material_json {
                 result.add(ValidationError::error(
-                    "Missing File",
+                    codes::MISSING_FILE,
                     "Missing material.json",
                     Some(material.path.clone()),
                 ));
@@ -154,7 +199,7 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
             for filament in &material.filaments {
                 if !filament.has_filament_json {
                     result.add(ValidationError::error(
-                        "Missing File",
+                        codes::MISSING_FILE,
                         "Missing filament.json",
                         Some(filament.path.clone()),
                     ));
@@ -163,7 +208,7 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
                 for variant in &filament.variants {
                     if !variant.has_variant_json {
                         result.add(ValidationError::error(
-                            "Missing File",
+                            codes::MISSING_FILE,
                             "Missing variant.json",
                             Some(variant.path.clone()),
                         ));
@@ -171,20 +216,36 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
 
                     if !variant.has_sizes_json {
                         result.add(ValidationError::error(
-                            "Missing File",
+                            codes::MISSING_FILE,
                             "Missing sizes.json",
                             Some(variant.path.clone()),
                         ));
                     }
+
+                    for dir in &variant.nested_dirs {
+                        result.add(ValidationError::warning(
+                            codes::STRUCTURE,
+                            "Unexpected directory below variant level; its contents are not validated",
+                            Some(dir.clone()),
+                        ));
+                    }
                 }
             }
         }
     }
 
+    for dir in &manifest.orphan_sizes_dirs {
+        result.add(ValidationError::error(
+            codes::MISSING_FILE,
+            "Missing variant.json (directory contains sizes.json)",
+            Some(dir.clone()),
+        ));
+    }
+
     for store in &manifest.stores {
         if !store.has_store_json {
             result.add(ValidationError::error(
-                "Missing File",
+                codes::MISSING_FILE,
                 "Missing store.json",
                 Some(store.path.clone()),
             ));
@@ -193,3 +254,36 @@ pub fn validate_required_files(manifest: &FileManifest) -> ValidationResult {
 
     result
 }
+
+/// Report material directories without any filament subdirectory and filament
+/// directories without any variant subdirectory, as "Structure" issues at `level`.
+/// Such folders pass the required-file checks but describe nothing a user can buy.
+pub fn validate_empty_directories(manifest: &FileManifest, level: ValidationLevel) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for brand in &manifest.brands {
+        for material in &brand.materials {
+            if material.filaments.is_empty() {
+                result.add(ValidationError::new(
+                    level,
+                    codes::STRUCTURE,
+                    "Material directory has no filament directories",
+                    Some(material.path.clone()),
+                ));
+            }
+
+            for filament in &material.filaments {
+                if filament.variants.is_empty() {
+                    result.add(ValidationError::new(
+                        level,
+                        codes::STRUCTURE,
+                        "Filament directory has no variant directories",
+                        Some(filament.path.clone()),
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
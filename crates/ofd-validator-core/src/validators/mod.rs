@@ -1,13 +1,59 @@
+mod availability;
+mod brand_name;
+mod color_name;
+mod conditional;
+mod diameter;
+mod field_length;
 mod folder_name;
 mod gtin;
+mod id_format;
 mod json_validator;
+mod logo_format;
 mod logo_validator;
+mod material_name;
 pub mod missing_files;
+mod numeric_format;
+mod path_length;
+mod purchase_link;
 mod store_id;
+mod store_metadata;
+mod version;
+mod weight;
+mod whitespace;
 
+pub use availability::validate_availability;
+pub use brand_name::validate_brand_name_uniqueness;
+pub use color_name::validate_color_name_consistency;
+pub use conditional::{validate_conditional_rules, Condition, ConditionalRule, Requirement};
+pub use diameter::validate_variant_diameter_consistency;
+pub use field_length::validate_field_lengths;
 pub use folder_name::validate_folder_name;
-pub use gtin::validate_gtin_ean;
-pub use json_validator::validate_json;
-pub use logo_validator::validate_logo;
-pub use missing_files::validate_required_files;
-pub use store_id::validate_store_ids;
+pub use gtin::{
+    gs1_check_digit, validate_barcode_presence, validate_gtin_brand_conflict, validate_gtin_ean, validate_gtin_ean_with_options,
+    GtinFormat,
+};
+pub use id_format::{validate_id_format, validate_id_slug};
+pub use json_validator::{
+    schema_compile_error, unusable_schemas, validate_json, validate_json_source, validate_json_with_options,
+    validate_value_against_schema, validate_value_against_schema_with_options,
+};
+pub use logo_format::validate_logo_format_consistency;
+pub use logo_validator::{
+    detect_animation, is_heif, parse_heif_dimensions, parse_png_header, raster_metadata, validate_logo, validate_logo_in_context,
+    validate_logo_with_options, LogoContext, PngHeader,
+};
+pub use material_name::{material_type_names, validate_material_names};
+pub use missing_files::{validate_empty_directories, validate_required_files};
+pub use numeric_format::validate_numeric_format;
+pub use path_length::validate_path_lengths;
+pub use purchase_link::{
+    validate_duplicate_purchase_links, validate_purchase_link_completeness, validate_purchase_link_count,
+};
+pub use store_id::{
+    validate_brand_has_store, validate_missing_store_ids, validate_store_brand_coverage, validate_store_ids,
+    validate_store_refs,
+};
+pub use store_metadata::validate_store_metadata;
+pub use version::{validate_required_version, VERSION_FIELDS};
+pub use weight::{parse_weight, validate_weight_range};
+pub use whitespace::validate_whitespace_in_values;
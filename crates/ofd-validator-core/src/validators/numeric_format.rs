@@ -0,0 +1,142 @@
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about values stored with the wrong JSON type for their key: strings under any of
+/// `number_fields` (e.g. `"diameter": "1.75"`) and numbers under any of `text_fields`.
+/// Mixed representations pass loose schemas but break comparisons and display.
+/// Each entry is (path_label, parsed JSON Value); objects are walked recursively, so
+/// nested keys (e.g. inside sizes.json arrays) are checked too.
+pub fn validate_numeric_format(
+    entries: &[(&str, &Value)],
+    number_fields: &[String],
+    text_fields: &[String],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    if number_fields.is_empty() && text_fields.is_empty() {
+        return result;
+    }
+
+    for (path_str, data) in entries {
+        walk(data, "$", number_fields, text_fields, path_str, &mut result);
+    }
+
+    result
+}
+
+fn walk(
+    value: &Value,
+    json_path: &str,
+    number_fields: &[String],
+    text_fields: &[String],
+    path_str: &str,
+    result: &mut ValidationResult,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = format!("{}.{}", json_path, key);
+                match child {
+                    Value::String(s) if number_fields.iter().any(|f| f == key) => {
+                        let hint = match s.trim().parse::<f64>() {
+                            Ok(n) if n.is_finite() => format!("store it as the number {}", s.trim()),
+                            _ => "expected a number".to_string(),
+                        };
+                        result.add(ValidationError::warning(
+                            codes::FORMAT,
+                            format!("Value at {} is the string \"{}\"; {}", child_path, s, hint),
+                            Some(path_str.to_string()),
+                        ));
+                    }
+                    Value::Number(n) if text_fields.iter().any(|f| f == key) => {
+                        result.add(ValidationError::warning(
+                            codes::FORMAT,
+                            format!(
+                                "Value at {} is the number {}; store it as the string \"{}\"",
+                                child_path, n, n
+                            ),
+                            Some(path_str.to_string()),
+                        ));
+                    }
+                    _ => walk(child, &child_path, number_fields, text_fields, path_str, result),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                walk(
+                    item,
+                    &format!("{}[{}]", json_path, idx),
+                    number_fields,
+                    text_fields,
+                    path_str,
+                    result,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(data: Value) -> Vec<String> {
+        let number_fields = vec!["diameter".to_string(), "weight_grams".to_string()];
+        let text_fields = vec!["gtin".to_string()];
+        validate_numeric_format(&[("sizes.json", &data)], &number_fields, &text_fields)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn matching_types_pass() {
+        assert!(messages(json!({"diameter": 1.75, "gtin": "4012345678901", "name": "1.75"})).is_empty());
+    }
+
+    #[test]
+    fn string_under_a_number_field_is_a_warning() {
+        assert_eq!(
+            messages(json!({"diameter": " 1.75 "})),
+            ["Value at $.diameter is the string \" 1.75 \"; store it as the number 1.75"]
+        );
+        assert_eq!(
+            messages(json!({"diameter": "thick"})),
+            ["Value at $.diameter is the string \"thick\"; expected a number"]
+        );
+        assert_eq!(
+            messages(json!({"diameter": "inf"})),
+            ["Value at $.diameter is the string \"inf\"; expected a number"]
+        );
+    }
+
+    #[test]
+    fn number_under_a_text_field_is_a_warning() {
+        assert_eq!(
+            messages(json!({"gtin": 4012345678901_u64})),
+            ["Value at $.gtin is the number 4012345678901; store it as the string \"4012345678901\""]
+        );
+    }
+
+    #[test]
+    fn fields_inside_sizes_arrays_are_checked() {
+        let sizes = json!([
+            {"diameter": 1.75, "weight_grams": "1000"},
+            {"diameter": "2.85", "purchase_links": [{"gtin": 12345}]},
+        ]);
+        assert_eq!(
+            messages(sizes),
+            [
+                "Value at $[0].weight_grams is the string \"1000\"; store it as the number 1000",
+                "Value at $[1].diameter is the string \"2.85\"; store it as the number 2.85",
+                "Value at $[1].purchase_links[0].gtin is the number 12345; store it as the string \"12345\"",
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about folder paths that some filesystems or tools can't handle: any component
+/// longer than `max_component` bytes, or a whole path longer than `max_total` bytes.
+/// Long `id`s nested several levels deep can otherwise break clones on other platforms.
+/// Paths are measured as given, so labels relative to the repository root give results
+/// that don't depend on where it is checked out.
+pub fn validate_path_lengths(folder_paths: &[&str], max_component: usize, max_total: usize) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for path_str in folder_paths {
+        let longest = path_str
+            .split(['/', '\\'])
+            .max_by_key(|component| component.len())
+            .unwrap_or_default();
+        if longest.len() > max_component {
+            result.add(ValidationError::warning(
+                codes::PATH,
+                format!(
+                    "Folder name '{}' is {} bytes long, over the {}-byte limit for a path component",
+                    longest,
+                    longest.len(),
+                    max_component
+                ),
+                Some(path_str.to_string()),
+            ));
+        }
+        if path_str.len() > max_total {
+            result.add(ValidationError::warning(
+                codes::PATH,
+                format!("Path is {} bytes long, over the {}-byte limit", path_str.len(), max_total),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(path: &str, max_component: usize, max_total: usize) -> Vec<String> {
+        validate_path_lengths(&[path], max_component, max_total)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn paths_at_the_limits_pass() {
+        assert!(messages("data/acme/PLA", 4, 13).is_empty());
+    }
+
+    #[test]
+    fn longest_component_over_the_limit_is_reported() {
+        assert_eq!(
+            messages("data/acme/basic-matte", 8, 100),
+            ["Folder name 'basic-matte' is 11 bytes long, over the 8-byte limit for a path component"]
+        );
+        // Backslash-separated labels are split too
+        assert_eq!(
+            messages(r"data\acme\basic-matte", 8, 100),
+            ["Folder name 'basic-matte' is 11 bytes long, over the 8-byte limit for a path component"]
+        );
+    }
+
+    #[test]
+    fn lengths_are_measured_in_bytes() {
+        // 5 characters, 6 and 7 bytes
+        assert!(messages("data/crème", 6, 12).is_empty());
+        assert_eq!(
+            messages("data/brûlé", 6, 12),
+            ["Folder name 'brûlé' is 7 bytes long, over the 6-byte limit for a path component"]
+        );
+    }
+
+    #[test]
+    fn total_length_over_the_limit_is_reported() {
+        assert_eq!(messages("data/acme/PLA", 10, 12), ["Path is 13 bytes long, over the 12-byte limit"]);
+        assert_eq!(
+            messages("data/acme/PLA-CF", 5, 12),
+            [
+                "Folder name 'PLA-CF' is 6 bytes long, over the 5-byte limit for a path component",
+                "Path is 16 bytes long, over the 12-byte limit",
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Check that every sizes.json purchase link has the `required` keys.
+/// `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+///
+/// A key counts as missing when it is absent, null or an empty string.
+pub fn validate_purchase_link_completeness(
+    sizes_entries: &[(&str, &Value)],
+    required: &[String],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let purchase_links = match size.get("purchase_links").and_then(|v| v.as_array()) {
+                Some(links) => links,
+                None => continue,
+            };
+
+            for (link_idx, link) in purchase_links.iter().enumerate() {
+                for field in required {
+                    let present = match link.get(field) {
+                        None | Some(Value::Null) => false,
+                        Some(Value::String(s)) => !s.is_empty(),
+                        Some(_) => true,
+                    };
+
+                    if !present {
+                        result.add(ValidationError::error(
+                            codes::PURCHASE_LINK,
+                            format!(
+                                "Missing '{}' at $[{}].purchase_links[{}]",
+                                field, size_idx, link_idx
+                            ),
+                            Some(path_str.to_string()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Warn about sizes with more than `max` purchase links, which usually points at spam
+/// or a data error. `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+pub fn validate_purchase_link_count(sizes_entries: &[(&str, &Value)], max: usize) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let count = size
+                .get("purchase_links")
+                .and_then(|v| v.as_array())
+                .map_or(0, |links| links.len());
+
+            if count > max {
+                result.add(ValidationError::warning(
+                    codes::PURCHASE_LINK,
+                    format!(
+                        "Too many purchase links at $[{}]: {} (at most {} expected)",
+                        size_idx, count, max
+                    ),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+/// Warn about purchase links listed more than once in the same size, i.e. with the same
+/// `store_id` and `url`. Links without a `url` are left to the completeness check.
+/// `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+pub fn validate_duplicate_purchase_links(sizes_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let purchase_links = match size.get("purchase_links").and_then(|v| v.as_array()) {
+                Some(links) => links,
+                None => continue,
+            };
+
+            // (store_id, url) -> link indices, in order of first appearance
+            let mut order: Vec<(Option<&str>, &str)> = Vec::new();
+            let mut indices: HashMap<(Option<&str>, &str), Vec<usize>> = HashMap::new();
+            for (link_idx, link) in purchase_links.iter().enumerate() {
+                let Some(url) = link.get("url").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let key = (link.get("store_id").and_then(|v| v.as_str()), url);
+                let seen = indices.entry(key).or_default();
+                if seen.is_empty() {
+                    order.push(key);
+                }
+                seen.push(link_idx);
+            }
+
+            for key in order {
+                let repeated = &indices[&key];
+                if repeated.len() > 1 {
+                    let at: Vec<String> = repeated.iter().map(|i| i.to_string()).collect();
+                    let store = key.0.map(|id| format!("store '{}', ", id)).unwrap_or_default();
+                    result.add(ValidationError::warning(
+                        codes::PURCHASE_LINK,
+                        format!(
+                            "Duplicate purchase link at $[{}].purchase_links[{}]: {}url '{}'",
+                            size_idx,
+                            at.join(", "),
+                            store,
+                            key.1
+                        ),
+                        Some(path_str.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(result: ValidationResult) -> Vec<String> {
+        result.errors.into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn complete_links_pass() {
+        let sizes = json!([{"purchase_links": [{"store_id": "shop", "url": "https://shop.example/pla", "price": 0}]}]);
+        let required = vec!["store_id".to_string(), "url".to_string()];
+        assert!(messages(validate_purchase_link_completeness(&[("sizes.json", &sizes)], &required)).is_empty());
+    }
+
+    #[test]
+    fn absent_null_and_empty_keys_are_missing() {
+        let sizes = json!([
+            {"weight": 1000},
+            {"purchase_links": [
+                {"store_id": "shop", "url": "https://shop.example/pla"},
+                {"store_id": null, "url": ""},
+                {"url": "https://shop.example/petg"},
+            ]},
+        ]);
+        let required = vec!["store_id".to_string(), "url".to_string()];
+        assert_eq!(
+            messages(validate_purchase_link_completeness(&[("sizes.json", &sizes)], &required)),
+            [
+                "Missing 'store_id' at $[1].purchase_links[1]",
+                "Missing 'url' at $[1].purchase_links[1]",
+                "Missing 'store_id' at $[1].purchase_links[2]",
+            ]
+        );
+    }
+
+    #[test]
+    fn sizes_over_the_link_count_are_reported() {
+        let link = json!({"store_id": "shop", "url": "https://shop.example/pla"});
+        let sizes = json!([
+            {"purchase_links": [link, link]},
+            {"purchase_links": [link, link, link]},
+            {"weight": 1000},
+        ]);
+        assert_eq!(
+            messages(validate_purchase_link_count(&[("sizes.json", &sizes)], 2)),
+            ["Too many purchase links at $[1]: 3 (at most 2 expected)"]
+        );
+    }
+
+    #[test]
+    fn duplicate_links_are_grouped_by_store_and_url() {
+        let sizes = json!([
+            {"purchase_links": [
+                {"store_id": "shop", "url": "https://shop.example/pla"},
+                {"store_id": "other", "url": "https://shop.example/pla"},
+                {"url": "https://mall.example/pla"},
+                {"store_id": "shop", "url": "https://shop.example/pla"},
+                {"url": "https://mall.example/pla"},
+                {"store_id": "shop"},
+                {"store_id": "shop"},
+            ]},
+            {"purchase_links": [{"store_id": "shop", "url": "https://shop.example/pla"}]},
+        ]);
+        assert_eq!(
+            messages(validate_duplicate_purchase_links(&[("sizes.json", &sizes)])),
+            [
+                "Duplicate purchase link at $[0].purchase_links[0, 3]: store 'shop', url 'https://shop.example/pla'",
+                "Duplicate purchase link at $[0].purchase_links[2, 4]: url 'https://mall.example/pla'",
+            ]
+        );
+    }
+}
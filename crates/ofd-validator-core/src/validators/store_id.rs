@@ -1,7 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use serde_json::Value;
 
-use crate::types::{ValidationError, ValidationResult};
+use crate::codes;
+use crate::types::{ValidationError, ValidationLevel, ValidationResult};
+use crate::util::{brand_dirs, owning_brand};
 
 /// Validate store IDs referenced in sizes.json purchase_links.
 /// `valid_store_ids` is the set of known store IDs from store.json files.
@@ -28,7 +30,7 @@ pub fn validate_store_ids(
                 if let Some(store_id) = link.get("store_id").and_then(|v| v.as_str()) {
                     if !valid_store_ids.contains(store_id) {
                         result.add(ValidationError::error(
-                            "StoreID",
+                            codes::STORE_ID,
                             format!(
                                 "Invalid store_id '{}' at $[{}].purchase_links[{}]",
                                 store_id, size_idx, link_idx
@@ -43,3 +45,285 @@ pub fn validate_store_ids(
 
     result
 }
+
+/// Report purchase links that lack a `store_id` (absent, null or empty).
+/// `level` controls the severity of the reported issues. Links with a non-empty
+/// `store_ref_field` value name their store by path instead and are not reported.
+pub fn validate_missing_store_ids(
+    sizes_entries: &[(&str, &Value)],
+    level: ValidationLevel,
+    store_ref_field: Option<&str>,
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let purchase_links = match size.get("purchase_links").and_then(|v| v.as_array()) {
+                Some(links) => links,
+                None => continue,
+            };
+
+            for (link_idx, link) in purchase_links.iter().enumerate() {
+                let has_store_id = link
+                    .get("store_id")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|s| !s.is_empty());
+                let has_store_ref = store_ref_field
+                    .and_then(|field| link.get(field))
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|s| !s.is_empty());
+
+                if !has_store_id && !has_store_ref {
+                    result.add(ValidationError::new(
+                        level,
+                        codes::STORE_ID,
+                        format!(
+                            "Missing store_id at $[{}].purchase_links[{}]",
+                            size_idx, link_idx
+                        ),
+                        Some(path_str.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Validate purchase links that name their store by a relative path under `field`
+/// (e.g. `"store_ref": "../../../../../stores/acme"`) rather than by `store_id`.
+/// The path is resolved against the sizes.json's folder and must land on one of
+/// `store_dirs` (the store directory path labels).
+pub fn validate_store_refs(
+    store_dirs: &[&str],
+    sizes_entries: &[(&str, &Value)],
+    field: &str,
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let known: HashSet<String> = store_dirs.iter().map(|d| normalize_label(d)).collect();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+        let sizes_dir = normalize_label(path_str);
+        let sizes_dir = sizes_dir.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let purchase_links = match size.get("purchase_links").and_then(|v| v.as_array()) {
+                Some(links) => links,
+                None => continue,
+            };
+
+            for (link_idx, link) in purchase_links.iter().enumerate() {
+                let store_ref = match link.get(field).and_then(|v| v.as_str()) {
+                    Some(r) if !r.is_empty() => r,
+                    _ => continue,
+                };
+                let problem = if store_ref.starts_with('/') || store_ref.starts_with('\\') {
+                    Some("must be a path relative to the sizes.json folder")
+                } else if !known.contains(&resolve_relative(sizes_dir, store_ref)) {
+                    Some("does not resolve to a store directory")
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    result.add(ValidationError::error(
+                        codes::STORE_ID,
+                        format!(
+                            "{} '{}' at $[{}].purchase_links[{}] {}",
+                            field, store_ref, size_idx, link_idx, problem
+                        ),
+                        Some(path_str.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// A path label with '/' separators and no trailing separator.
+fn normalize_label(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// Lexically apply relative path `rel` to directory `base`.
+fn resolve_relative(base: &str, rel: &str) -> String {
+    let mut segments: Vec<&str> = base.split('/').collect();
+    for segment in rel.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    segments.join("/")
+}
+
+/// Warn about purchase links pointing at a store whose store.json declares a `brands`
+/// list that doesn't include the brand the sizes.json belongs to.
+/// `store_entries` and `brand_entries` are (path_label, parsed store.json / brand.json);
+/// a sizes.json belongs to the brand whose brand.json sits in one of its ancestor folders.
+///
+/// Stores without a `brands` list are assumed to carry every brand.
+pub fn validate_store_brand_coverage(
+    store_entries: &[(&str, &Value)],
+    brand_entries: &[(&str, &Value)],
+    sizes_entries: &[(&str, &Value)],
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    let carried: HashMap<&str, HashSet<&str>> = store_entries
+        .iter()
+        .filter_map(|(_, store)| {
+            let id = store.get("id")?.as_str()?;
+            let brands = store.get("brands")?.as_array()?;
+            Some((id, brands.iter().filter_map(|b| b.as_str()).collect()))
+        })
+        .collect();
+    if carried.is_empty() {
+        return result;
+    }
+
+    let brand_dirs = brand_dirs(brand_entries);
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        // The closest enclosing brand folder owns this file
+        let brand_id = match owning_brand(&brand_dirs, path_str) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        for (size_idx, size) in sizes_arr.iter().enumerate() {
+            let purchase_links = match size.get("purchase_links").and_then(|v| v.as_array()) {
+                Some(links) => links,
+                None => continue,
+            };
+
+            for (link_idx, link) in purchase_links.iter().enumerate() {
+                let store_id = match link.get("store_id").and_then(|v| v.as_str()) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                if let Some(brands) = carried.get(store_id) {
+                    if !brands.contains(brand_id) {
+                        result.add(ValidationError::warning(
+                            codes::STORE_ID,
+                            format!(
+                                "Store '{}' does not list brand '{}' at $[{}].purchase_links[{}]",
+                                store_id, brand_id, size_idx, link_idx
+                            ),
+                            Some(path_str.to_string()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Report brands that no purchase link points at a store for: none of the sizes.json
+/// files under the brand's folder has a link with a `store_id` (or a `store_ref_field`
+/// path). A brand nobody sells is usually missing its purchase links.
+/// `brand_entries` are (path_label, parsed brand.json); issues are reported against the
+/// brand.json at `level`.
+pub fn validate_brand_has_store(
+    brand_entries: &[(&str, &Value)],
+    sizes_entries: &[(&str, &Value)],
+    store_ref_field: Option<&str>,
+    level: ValidationLevel,
+) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let brand_dirs = brand_dirs(brand_entries);
+    let names_store = |link: &Value, field: &str| {
+        link.get(field).and_then(|v| v.as_str()).is_some_and(|s| !s.is_empty())
+    };
+
+    let mut sold: HashSet<&str> = HashSet::new();
+    for (path_str, sizes_data) in sizes_entries {
+        let brand_id = match owning_brand(&brand_dirs, path_str) {
+            Some(id) => id,
+            None => continue,
+        };
+        let has_store_link = sizes_data
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|size| size.get("purchase_links")?.as_array())
+            .flatten()
+            .any(|link| names_store(link, "store_id") || store_ref_field.is_some_and(|f| names_store(link, f)));
+        if has_store_link {
+            sold.insert(brand_id);
+        }
+    }
+
+    for (path_str, brand) in brand_entries {
+        let Some(brand_id) = brand.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if !sold.contains(brand_id) {
+            result.add(ValidationError::new(
+                level,
+                codes::STORE_ID,
+                format!("Brand '{}' is not sold by any store: no purchase link names a store", brand_id),
+                Some(path_str.to_string()),
+            ));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn coverage_warnings(store: Value) -> Vec<String> {
+        let brand = json!({"id": "acme"});
+        let sizes = json!([{"purchase_links": [{"store_id": "shop", "url": "https://shop.example/acme"}]}]);
+        let result = validate_store_brand_coverage(
+            &[("stores/shop/store.json", &store)],
+            &[("data/acme/brand.json", &brand)],
+            &[("data/acme/PLA/basic/red/sizes.json", &sizes)],
+        );
+        result.errors.into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn store_listing_the_brand_passes() {
+        assert!(coverage_warnings(json!({"id": "shop", "brands": ["other", "acme"]})).is_empty());
+    }
+
+    #[test]
+    fn store_without_a_brands_list_carries_every_brand() {
+        assert!(coverage_warnings(json!({"id": "shop"})).is_empty());
+    }
+
+    #[test]
+    fn store_not_listing_the_brand_is_a_warning() {
+        assert_eq!(
+            coverage_warnings(json!({"id": "shop", "brands": ["other"]})),
+            ["Store 'shop' does not list brand 'acme' at $[0].purchase_links[0]"]
+        );
+    }
+}
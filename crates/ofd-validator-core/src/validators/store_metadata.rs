@@ -0,0 +1,67 @@
+use isocountry::CountryCode;
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Validate metadata fields in pre-loaded store.json entries.
+/// Each entry is (path_label, parsed store.json Value).
+///
+/// A `country` field, when present, must be an ISO 3166-1 alpha-2 code (e.g. "DE").
+pub fn validate_store_metadata(store_entries: &[(&str, &Value)]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, store_data) in store_entries {
+        if let Some(country) = store_data.get("country").and_then(|v| v.as_str()) {
+            if CountryCode::for_alpha2(country).is_err() {
+                result.add(ValidationError::warning(
+                    codes::STORE,
+                    format!(
+                        "Unrecognized country '{}': must be an ISO 3166-1 alpha-2 code (e.g. 'DE')",
+                        country
+                    ),
+                    Some(path_str.to_string()),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::ValidationLevel;
+
+    fn issues(store: Value) -> Vec<(ValidationLevel, String, String)> {
+        validate_store_metadata(&[("stores/shop/store.json", &store)])
+            .errors
+            .into_iter()
+            .map(|e| (e.level, e.category, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn alpha2_country_passes() {
+        assert!(issues(json!({"id": "shop", "country": "DE"})).is_empty());
+        assert!(issues(json!({"id": "shop", "country": "US"})).is_empty());
+        assert!(issues(json!({"id": "shop"})).is_empty());
+    }
+
+    #[test]
+    fn other_country_values_are_warnings() {
+        for country in ["XX", "DEU", "Germany", ""] {
+            assert_eq!(
+                issues(json!({"id": "shop", "country": country})),
+                [(
+                    ValidationLevel::Warning,
+                    codes::STORE.to_string(),
+                    format!("Unrecognized country '{}': must be an ISO 3166-1 alpha-2 code (e.g. 'DE')", country),
+                )]
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use regex::Regex;
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Keys accepted as the version marker of a sizes.json file, in lookup order.
+pub const VERSION_FIELDS: &[&str] = &["version", "schema_version"];
+
+/// Check that every sizes.json carries a version marker matching `pattern`, so files
+/// can be migrated reliably when the format changes.
+/// `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+///
+/// The first of `VERSION_FIELDS` present on the top-level object is checked. A bare
+/// array has nowhere to put the marker and is reported as missing it.
+pub fn validate_required_version(sizes_entries: &[(&str, &Value)], pattern: &Regex) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    for (path_str, sizes_data) in sizes_entries {
+        let found = VERSION_FIELDS
+            .iter()
+            .find_map(|field| sizes_data.get(field).map(|v| (*field, v)));
+
+        let message = match found {
+            None => format!("Missing version marker (expected one of: {})", VERSION_FIELDS.join(", ")),
+            Some((field, Value::String(version))) => {
+                if pattern.is_match(version) {
+                    continue;
+                }
+                format!("Invalid {} '{}': must match {}", field, version, pattern.as_str())
+            }
+            Some((field, other)) => format!("Invalid {} {}: must be a string", field, other),
+        };
+
+        result.add(ValidationError::warning(codes::VERSION, message, Some(path_str.to_string())));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(sizes: Value) -> Vec<String> {
+        let pattern = Regex::new(r"^\d+\.\d+$").unwrap();
+        validate_required_version(&[("sizes.json", &sizes)], &pattern)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn matching_version_passes() {
+        assert!(messages(json!({"version": "1.0", "sizes": []})).is_empty());
+    }
+
+    #[test]
+    fn missing_marker_is_reported() {
+        let expected = ["Missing version marker (expected one of: version, schema_version)"];
+        assert_eq!(messages(json!({"sizes": []})), expected);
+        assert_eq!(messages(json!([{"weight": 1000}])), expected);
+    }
+
+    #[test]
+    fn schema_version_is_the_fallback() {
+        assert!(messages(json!({"schema_version": "2.1"})).is_empty());
+        assert_eq!(messages(json!({"schema_version": "v2"})), ["Invalid schema_version 'v2': must match ^\\d+\\.\\d+$"]);
+        // `version` is looked up first
+        assert_eq!(
+            messages(json!({"version": "latest", "schema_version": "2.1"})),
+            ["Invalid version 'latest': must match ^\\d+\\.\\d+$"]
+        );
+    }
+
+    #[test]
+    fn non_string_version_is_reported() {
+        assert_eq!(messages(json!({"version": 1.0})), ["Invalid version 1.0: must be a string"]);
+        assert_eq!(messages(json!({"version": null})), ["Invalid version null: must be a string"]);
+    }
+
+    #[test]
+    fn pattern_mismatch_is_reported() {
+        assert_eq!(messages(json!({"version": "1"})), ["Invalid version '1': must match ^\\d+\\.\\d+$"]);
+        assert_eq!(messages(json!({"version": "1.0-beta"})), ["Invalid version '1.0-beta': must match ^\\d+\\.\\d+$"]);
+    }
+}
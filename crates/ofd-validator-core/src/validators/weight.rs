@@ -0,0 +1,120 @@
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Grams per unit for the textual `weight` suffixes understood by `parse_weight`.
+const WEIGHT_UNITS: &[(&str, f64)] = &[
+    ("kg", 1000.0),
+    ("g", 1.0),
+    ("lbs", 453.592),
+    ("lb", 453.592),
+    ("oz", 28.3495),
+];
+
+/// Parse a textual weight such as "1kg", "750 g" or "2.2 lb" into grams.
+/// Returns `None` for text without a number followed by a known unit.
+pub fn parse_weight(text: &str) -> Option<f64> {
+    let text = text.trim().to_ascii_lowercase();
+    let (unit, grams) = WEIGHT_UNITS.iter().find(|(unit, _)| text.ends_with(unit))?;
+    let number = text[..text.len() - unit.len()].trim().replace(',', ".");
+    number.parse::<f64>().ok().filter(|n| n.is_finite()).map(|n| n * grams)
+}
+
+/// Warn about sizes whose weight is outside `range` (min, max), in grams: a spool of
+/// 0 g or 50 kg is almost certainly a typo. Reads numeric `weight_grams` and textual
+/// `weight` (see `parse_weight`); a size with neither is not checked.
+/// `sizes_entries` is a list of (path_label, parsed sizes.json Value).
+pub fn validate_weight_range(sizes_entries: &[(&str, &Value)], range: (f64, f64)) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    let (min, max) = range;
+
+    for (path_str, sizes_data) in sizes_entries {
+        let sizes_arr = match sizes_data.as_array() {
+            Some(a) => a,
+            None => continue,
+        };
+
+        for (idx, size) in sizes_arr.iter().enumerate() {
+            let weights = [
+                size.get("weight_grams").and_then(|v| v.as_f64()).map(|g| ("weight_grams", g)),
+                size.get("weight").and_then(|v| v.as_str()).and_then(parse_weight).map(|g| ("weight", g)),
+            ];
+            for (field, grams) in weights.into_iter().flatten() {
+                if grams < min || grams > max {
+                    result.add(ValidationError::warning(
+                        codes::SIZES,
+                        format!(
+                            "Implausible {} at $[{}]: {} g is outside {}-{} g",
+                            field,
+                            idx,
+                            // Unit conversions leave long fractions; a tenth of a gram is plenty
+                            (grams * 10.0).round() / 10.0,
+                            min,
+                            max
+                        ),
+                        Some(path_str.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn kilograms_are_not_read_as_grams() {
+        assert_eq!(parse_weight("1kg"), Some(1000.0));
+        assert_eq!(parse_weight("750g"), Some(750.0));
+        assert_eq!(parse_weight("1 KG"), Some(1000.0));
+    }
+
+    #[test]
+    fn pounds_accept_both_suffixes() {
+        assert_eq!(parse_weight("2 lbs"), Some(907.184));
+        assert_eq!(parse_weight("2lb"), Some(907.184));
+        assert_eq!(parse_weight("10 oz"), Some(283.495));
+    }
+
+    #[test]
+    fn decimal_comma_and_whitespace_are_accepted() {
+        assert_eq!(parse_weight("1,5 kg"), Some(1500.0));
+        assert_eq!(parse_weight("  0.75   kg \n"), Some(750.0));
+    }
+
+    #[test]
+    fn garbage_is_not_a_weight() {
+        for text in ["", "kg", "heavy", "1", "1 stone", "one kg", "1.2.3 g", "10 mg", "NaN g", "inf kg"] {
+            assert_eq!(parse_weight(text), None, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn implausible_weights_are_reported_per_field() {
+        let sizes = json!([
+            {"weight_grams": 1000, "weight": "1 kg"},
+            {"weight_grams": 0},
+            {"weight": "50 kg"},
+            {"weight": "about a kilo"},
+        ]);
+        let messages: Vec<String> = validate_weight_range(&[("sizes.json", &sizes)], (50.0, 10000.0))
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Implausible weight_grams at $[1]: 0 g is outside 50-10000 g",
+                "Implausible weight at $[2]: 50000 g is outside 50-10000 g",
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use serde_json::Value;
+
+use crate::codes;
+use crate::types::{ValidationError, ValidationResult};
+
+/// Warn about string values under any of `fields` that have leading/trailing whitespace
+/// or embedded tabs. Such values pass schema validation but cause folder mismatches
+/// and display bugs (e.g. `"PLA "`).
+/// Each entry is (path_label, parsed JSON Value); objects are walked recursively, so
+/// nested keys (e.g. inside sizes.json arrays) are checked too.
+pub fn validate_whitespace_in_values(entries: &[(&str, &Value)], fields: &[String]) -> ValidationResult {
+    let mut result = ValidationResult::default();
+    if fields.is_empty() {
+        return result;
+    }
+
+    for (path_str, data) in entries {
+        walk(data, "$", fields, path_str, &mut result);
+    }
+
+    result
+}
+
+fn walk(value: &Value, json_path: &str, fields: &[String], path_str: &str, result: &mut ValidationResult) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = format!("{}.{}", json_path, key);
+                if let Value::String(s) = child {
+                    if fields.iter().any(|f| f == key) {
+                        check_string(s, &child_path, path_str, result);
+                    }
+                } else {
+                    walk(child, &child_path, fields, path_str, result);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                walk(item, &format!("{}[{}]", json_path, idx), fields, path_str, result);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_string(s: &str, json_path: &str, path_str: &str, result: &mut ValidationResult) {
+    let mut problems = Vec::new();
+    if s.starts_with(char::is_whitespace) {
+        problems.push("leading whitespace");
+    }
+    if s.ends_with(char::is_whitespace) {
+        problems.push("trailing whitespace");
+    }
+    if s.trim().contains('\t') {
+        problems.push("a tab");
+    }
+
+    if !problems.is_empty() {
+        result.add(ValidationError::warning(
+            codes::WHITESPACE,
+            format!("Value {:?} at {} has {}", s, json_path, problems.join(" and ")),
+            Some(path_str.to_string()),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn messages(data: Value) -> Vec<String> {
+        let fields = vec!["name".to_string(), "material".to_string()];
+        validate_whitespace_in_values(&[("material.json", &data)], &fields)
+            .errors
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    }
+
+    #[test]
+    fn clean_values_pass() {
+        assert!(messages(json!({"material": "PLA", "name": "Basic Matte", "notes": " free text "})).is_empty());
+    }
+
+    #[test]
+    fn leading_and_trailing_whitespace_are_reported() {
+        assert_eq!(messages(json!({"material": " PLA"})), [r#"Value " PLA" at $.material has leading whitespace"#]);
+        assert_eq!(messages(json!({"material": "PLA "})), [r#"Value "PLA " at $.material has trailing whitespace"#]);
+        assert_eq!(
+            messages(json!({"material": "\nPLA\u{a0}"})),
+            [r#"Value "\nPLA\u{a0}" at $.material has leading whitespace and trailing whitespace"#]
+        );
+    }
+
+    #[test]
+    fn embedded_tab_is_reported() {
+        assert_eq!(messages(json!({"name": "Basic\tMatte"})), [r#"Value "Basic\tMatte" at $.name has a tab"#]);
+        // A trailing tab is only trailing whitespace
+        assert_eq!(messages(json!({"name": "Basic\t"})), [r#"Value "Basic\t" at $.name has trailing whitespace"#]);
+        assert_eq!(
+            messages(json!({"name": " Basic\tMatte"})),
+            [r#"Value " Basic\tMatte" at $.name has leading whitespace and a tab"#]
+        );
+    }
+
+    #[test]
+    fn nested_keys_are_checked_with_their_path() {
+        let data = json!({"sizes": [{"name": "1 kg"}, {"store": {"name": "Shop "}}]});
+        assert_eq!(messages(data), [r#"Value "Shop " at $.sizes[1].store.name has trailing whitespace"#]);
+    }
+}
@@ -0,0 +1,66 @@
+//! `DataSet::from_directories_async` must load the same DataSet as the synchronous walk.
+#![cfg(feature = "async")]
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use ofd_validator_core::{validate_dataset, DataSet};
+
+fn fixture(dir: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(dir)
+}
+
+/// Everything the validators look at, order-independent (the async loader finishes
+/// files in any order).
+fn contents(dataset: &DataSet) -> Vec<BTreeSet<String>> {
+    vec![
+        dataset.json_entries.iter().map(|(path, schema, data)| format!("{path} {schema} {data}")).collect(),
+        dataset.json_sources.iter().map(|(path, source)| format!("{path} {source}")).collect(),
+        dataset
+            .logo_entries
+            .iter()
+            .map(|(path, filename, bytes, logo_name, context)| {
+                format!("{path} {filename} {} {logo_name:?} {context:?}", bytes.len())
+            })
+            .collect(),
+        dataset
+            .folder_entries
+            .iter()
+            .map(|(path, folder_name, data, json_key)| format!("{path} {folder_name} {data} {json_key}"))
+            .collect(),
+        dataset.sizes_entries.iter().map(|(path, data)| format!("{path} {data}")).collect(),
+        dataset.valid_store_ids.iter().cloned().collect(),
+    ]
+}
+
+#[test]
+fn async_loader_matches_sync_loader() {
+    let (data_dir, stores_dir, schemas_dir) = (fixture("dataset/data"), fixture("dataset/stores"), fixture("dataset/schemas"));
+
+    let sync = DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let loaded = runtime.block_on(DataSet::from_directories_async(&data_dir, &stores_dir, &schemas_dir));
+
+    assert!(!sync.json_entries.is_empty());
+    assert_eq!(contents(&sync), contents(&loaded));
+
+    let errors = |dataset: &DataSet| -> BTreeSet<String> {
+        validate_dataset(dataset).errors.iter().map(|e| format!("{e:?}")).collect()
+    };
+    let sync_errors = errors(&sync);
+    assert!(!sync_errors.is_empty());
+    assert_eq!(sync_errors, errors(&loaded));
+}
+
+#[test]
+fn async_loader_skips_the_brand_logo_folder() {
+    let (data_dir, stores_dir, schemas_dir) =
+        (fixture("asset_dir/data"), fixture("asset_dir/stores"), fixture("dataset/schemas"));
+
+    let sync = DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let loaded = runtime.block_on(DataSet::from_directories_async(&data_dir, &stores_dir, &schemas_dir));
+
+    assert!(!loaded.json_entries.iter().any(|(path, _, _)| path.contains("assets")));
+    assert_eq!(contents(&sync), contents(&loaded));
+}
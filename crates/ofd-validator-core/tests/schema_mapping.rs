@@ -0,0 +1,32 @@
+//! `DataSet::from_directories_with_cache_and_layout` loads with the given cache, so a
+//! remapped schema is used without loading the defaults first.
+#![cfg(feature = "filesystem")]
+
+use std::path::{Path, PathBuf};
+
+use ofd_validator_core::{DataSet, LayoutStrategy, SchemaCache};
+
+fn fixture(dir: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dataset").join(dir)
+}
+
+#[test]
+fn mapped_cache_is_used_for_the_walk() {
+    let (data_dir, stores_dir, schemas_dir) = (fixture("data"), fixture("stores"), fixture("schemas"));
+    let default = DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+
+    let cache = SchemaCache::with_mapping(&schemas_dir, &[("brand", "store_schema.json")]);
+    let mapped = DataSet::from_directories_with_cache_and_layout(&data_dir, &stores_dir, cache, LayoutStrategy::Nested);
+
+    assert_eq!(mapped.schema_cache.get("brand"), default.schema_cache.get("store"));
+    assert_ne!(mapped.schema_cache.get("brand"), default.schema_cache.get("brand"));
+    assert_eq!(mapped.schema_cache.get("material"), default.schema_cache.get("material"));
+
+    let paths = |dataset: &DataSet| -> Vec<String> {
+        let mut paths: Vec<String> = dataset.json_entries.iter().map(|(path, _, _)| path.clone()).collect();
+        paths.sort();
+        paths
+    };
+    assert!(!paths(&mapped).is_empty());
+    assert_eq!(paths(&mapped), paths(&default));
+}
@@ -0,0 +1,82 @@
+//! The task producer, `build_file_manifest` and `DataSet::from_memory` must agree on which
+//! folders are entities. The fixture brand keeps its logo in an `assets/` folder that also
+//! holds a stray material.json; none of the walkers may treat it as a material.
+#![cfg(feature = "filesystem")]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use ofd_validator_core::validators::missing_files::{build_file_manifest, FileManifest};
+use ofd_validator_core::{DataSet, Task, TaskProducer};
+use walkdir::WalkDir;
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/asset_dir")
+}
+
+/// `path` relative to the fixture root, with '/' separators.
+fn relative(path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(fixture())
+        .unwrap_or(Path::new(path))
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn material_paths(manifest: &FileManifest) -> BTreeSet<String> {
+    manifest.brands.iter().flat_map(|b| &b.materials).map(|m| relative(&m.path)).collect()
+}
+
+#[test]
+fn walkers_skip_the_brand_logo_folder() {
+    let root = fixture();
+
+    let mut tasks = Vec::new();
+    TaskProducer::new(&root.join("data"), &root.join("stores")).for_each(|task| tasks.push(task));
+    let produced_json: BTreeSet<String> = tasks
+        .iter()
+        .filter_map(|task| match task {
+            Task::Json { path, .. } => Some(relative(path)),
+            _ => None,
+        })
+        .collect();
+    let produced_folders: BTreeSet<String> = tasks
+        .iter()
+        .filter_map(|task| match task {
+            Task::Folder { path, .. } => Some(relative(path)),
+            _ => None,
+        })
+        .collect();
+
+    let files: BTreeMap<String, Vec<u8>> = WalkDir::new(&root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|e| (relative(&e.path().to_string_lossy()), std::fs::read(e.path()).unwrap()))
+        .collect();
+    let memory = DataSet::from_memory(files);
+    let memory_json: BTreeSet<String> = memory.json_entries.iter().map(|(path, _, _)| path.clone()).collect();
+    let memory_folders: BTreeSet<String> = memory.folder_entries.iter().map(|(path, _, _, _)| path.clone()).collect();
+
+    let expected_json = BTreeSet::from(["data/acme/brand.json".to_string(), "data/acme/PLA/material.json".to_string()]);
+    let expected_folders = BTreeSet::from(["data/acme".to_string(), "data/acme/PLA".to_string()]);
+    assert_eq!(produced_json, expected_json);
+    assert_eq!(memory_json, expected_json);
+    assert_eq!(produced_folders, expected_folders);
+    assert_eq!(memory_folders, expected_folders);
+
+    let expected_materials = BTreeSet::from(["data/acme/PLA".to_string()]);
+    let manifest = build_file_manifest(&root.join("data"), &root.join("stores"));
+    assert_eq!(material_paths(&manifest), expected_materials);
+    assert_eq!(material_paths(&memory.file_manifest), expected_materials);
+
+    // The logo itself is still loaded from the asset folder
+    assert!(tasks.iter().any(|task| matches!(task, Task::Logo { path, bytes, .. }
+        if relative(path) == "data/acme/assets/logo.svg" && !bytes.is_empty())));
+    assert!(memory
+        .logo_entries
+        .iter()
+        .any(|(path, _, bytes, _, _)| path == "data/acme/assets/logo.svg" && !bytes.is_empty()));
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
@@ -14,6 +14,9 @@ pub struct ValidationError {
     pub category: String,
     pub message: String,
     pub path: Option<String>,
+    /// 1-based line of the offending value in `path`, for JSON schema failures
+    pub line: Option<u32>,
+    pub column: Option<u32>,
 }
 
 #[napi(object)]
@@ -22,68 +25,459 @@ pub struct ValidationResult {
     pub is_valid: bool,
     pub error_count: u32,
     pub warning_count: u32,
+    /// Paths that passed each validator (only set when `includePassing` is requested)
+    pub passing: Option<HashMap<String, Vec<String>>>,
 }
 
 impl From<core::ValidationResult> for ValidationResult {
     fn from(r: core::ValidationResult) -> Self {
+        let summary = r.summary();
         ValidationResult {
-            is_valid: r.is_valid(),
-            error_count: r.error_count() as u32,
-            warning_count: r.warning_count() as u32,
-            errors: r.errors.into_iter().map(|e| ValidationError {
-                level: e.level.to_string(),
-                category: e.category,
-                message: e.message,
-                path: e.path,
-            }).collect(),
+            is_valid: summary.errors == 0,
+            error_count: summary.errors as u32,
+            warning_count: summary.warnings as u32,
+            passing: (!r.passing.is_empty()).then(|| r.passing.into_iter().collect()),
+            errors: r.errors.into_iter().map(|e| e.into()).collect(),
         }
     }
 }
 
+impl From<core::ValidationError> for ValidationError {
+    fn from(e: core::ValidationError) -> Self {
+        ValidationError {
+            level: e.level.to_string(),
+            category: e.category,
+            message: e.message,
+            path: e.path,
+            line: e.line.map(|l| l as u32),
+            column: e.column.map(|c| c as u32),
+        }
+    }
+}
+
+/// Rebuild a core result from a JS one, for reusing core helpers (`passing` is dropped).
+fn to_core(result: ValidationResult) -> core::ValidationResult {
+    let errors = result.errors.into_iter().map(|e| {
+        let level = if e.level == "ERROR" {
+            core::ValidationLevel::Error
+        } else {
+            core::ValidationLevel::Warning
+        };
+        core::ValidationError::new(level, e.category, e.message, e.path)
+            .with_location(e.line.zip(e.column).map(|(l, c)| (l as usize, c as usize)))
+    });
+    core::ValidationResult {
+        errors: errors.collect(),
+        ..Default::default()
+    }
+}
+
+/// One entry of `listCodes()`.
+#[napi(object)]
+pub struct ErrorCode {
+    pub code: String,
+    pub description: String,
+}
+
+/// Every category the validator can emit, with a one-line description.
+#[napi]
+pub fn list_codes() -> Vec<ErrorCode> {
+    core::codes::ALL
+        .iter()
+        .map(|(code, description)| ErrorCode {
+            code: code.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// Render a result as CSV with columns `level,category,code,path,message`, for
+/// filtering in a spreadsheet.
+#[napi]
+pub fn result_to_csv(result: ValidationResult) -> String {
+    core::report::to_csv(&to_core(result))
+}
+
+/// Render a result as JSON with issues grouped by category:
+/// `{"is_valid", "error_count", "warning_count", "by_category": {<code>: [...], ...}}`.
+#[napi]
+pub fn result_to_json_grouped(result: ValidationResult) -> String {
+    core::report::to_json_grouped(&to_core(result))
+}
+
+/// True when `result` has no error (warnings are allowed) in `category`, one of the codes
+/// listed by `listCodes()`.
+#[napi]
+pub fn category_is_valid(result: ValidationResult, category: String) -> bool {
+    to_core(result).category_is_valid(&category)
+}
+
+/// True when `result` has an error (not a warning) in the category `code`, e.g. "GTIN".
+#[napi]
+pub fn has_error_with_code(result: ValidationResult, code: String) -> bool {
+    to_core(result).has_error_with_code(&code)
+}
+
+/// Every issue (errors and warnings) in `result` reported against exactly `path`.
+#[napi]
+pub fn errors_for_path(result: ValidationResult, path: String) -> Vec<ValidationError> {
+    to_core(result)
+        .errors_for_path(&path)
+        .into_iter()
+        .map(|e| e.clone().into())
+        .collect()
+}
+
+/// At most one issue per distinct path: its first error, or its first warning if it
+/// has no errors.
+#[napi]
+pub fn first_error_per_file(result: ValidationResult) -> Vec<ValidationError> {
+    to_core(result)
+        .first_error_per_file()
+        .into_iter()
+        .map(|e| e.clone().into())
+        .collect()
+}
+
 // ---- Path mode (filesystem-based, mirrors Python API) ----
 
+/// Load a dataset, applying any custom schema-name-to-filename mappings and
+/// collecting extra JSON files matched by glob.
+fn load_dataset(
+    data_dir: &str,
+    stores_dir: &str,
+    schemas_dir: Option<&str>,
+    schema_files: Option<HashMap<String, String>>,
+    extra_json: Option<HashMap<String, String>>,
+    layout: core::LayoutStrategy,
+) -> Result<core::DataSet> {
+    let schemas = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    let schema_cache = match schema_files {
+        Some(mapping) => {
+            let extra: Vec<(&str, &str)> = mapping.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            core::SchemaCache::with_mapping(&schemas, &extra)
+        }
+        None => core::SchemaCache::from_directory(&schemas),
+    };
+    let mut dataset = core::DataSet::from_directories_with_cache_and_layout(
+        &PathBuf::from(data_dir),
+        &PathBuf::from(stores_dir),
+        schema_cache,
+        layout,
+    );
+    if let Some(globs) = extra_json {
+        let extra: Vec<(&str, &str)> = globs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        dataset
+            .add_extra_json(&PathBuf::from(data_dir), &extra)
+            .map_err(|e| Error::from_reason(format!("Invalid extraJson pattern: {}", e)))?;
+    }
+    Ok(dataset)
+}
+
+/// JSON validation settings; `fullMessages: true` disables message truncation.
+fn json_options(full_messages: Option<bool>) -> core::JsonOptions {
+    if full_messages.unwrap_or(false) {
+        core::JsonOptions { max_message_len: None }
+    } else {
+        core::JsonOptions::default()
+    }
+}
+
+/// The core options for the validation settings in `options`; throws on an invalid
+/// conditional rule.
+fn core_options(options: &ValidateAllOptions) -> Result<core::ValidationOptions> {
+    let mut core_options = core::ValidationOptions {
+        include_passing: options.include_passing.unwrap_or(false),
+        logo: core::LogoOptions {
+            require_transparency: options.require_transparent_logos.unwrap_or(false),
+            allowed_sizes: options.allowed_logo_sizes.clone(),
+            warn_high_bit_depth: options.warn_high_bit_depth_logos.unwrap_or(false),
+            max_metadata_bytes: options.max_logo_metadata_bytes.map(u64::from),
+            consistent_brand_format: options.consistent_logo_formats.unwrap_or(false),
+            ..Default::default()
+        },
+        json: json_options(options.full_messages),
+        ..Default::default()
+    };
+    if let Some(fields) = options.purchase_link_fields.clone() {
+        core_options.purchase_link_fields = fields;
+    }
+    if let Some(fields) = options.whitespace_fields.clone() {
+        core_options.whitespace_fields = fields;
+    }
+    core_options.max_purchase_links = options.max_purchase_links.map(|max| max as usize);
+    core_options.schema_preflight = options.schema_preflight.unwrap_or(false);
+    if let Some(limits) = &options.field_length_limits {
+        core_options.field_length_limits = limits.iter().map(|(k, v)| (k.clone(), *v as usize)).collect();
+    }
+    if let Some(fields) = options.number_fields.clone() {
+        core_options.number_fields = fields;
+    }
+    if let Some(fields) = options.text_fields.clone() {
+        core_options.text_fields = fields;
+    }
+    if let Some(extensions) = options.allowed_logo_extensions.clone() {
+        core_options.logo.allowed_extensions = extensions;
+    }
+    if let Some(tolerance) = options.logo_square_tolerance {
+        core_options.logo.brand.square_tolerance = tolerance;
+        core_options.logo.store.square_tolerance = tolerance;
+    }
+    core_options.gtin.require_barcode = options.require_barcode.unwrap_or(false);
+    core_options.fail_fast = options.fail_fast.unwrap_or(false);
+    core_options.diameter_consistency = options.diameter_consistency.unwrap_or(false);
+    if options.brand_without_store.unwrap_or(false) {
+        core_options.brand_without_store_level = Some(core::ValidationLevel::Warning);
+    }
+    core_options.store_brand_coverage = options.store_brand_coverage.unwrap_or(false);
+    core_options.conditional_rules = options.conditional_rules
+        .iter()
+        .flatten()
+        .map(|rule| rule.parse().map_err(Error::from_reason))
+        .collect::<Result<_>>()?;
+    Ok(core_options)
+}
+
+/// Optional settings for `validateAll`.
+#[napi(object)]
+#[derive(Default)]
+pub struct ValidateAllOptions {
+    /// List the paths that passed each validator in `result.passing`
+    pub include_passing: Option<bool>,
+    /// Schema name -> filename overrides or additions inside `schemasDir`
+    pub schema_files: Option<HashMap<String, String>>,
+    /// Warn about PNG logos without a transparent background
+    pub require_transparent_logos: Option<bool>,
+    /// Edge lengths square logos must have (e.g. `[128, 256, 512]`)
+    pub allowed_logo_sizes: Option<Vec<u32>>,
+    /// Warn about PNG logos with more than 8 bits per sample
+    pub warn_high_bit_depth_logos: Option<bool>,
+    /// Keep schema validation messages untruncated
+    pub full_messages: Option<bool>,
+    /// Keys every purchase link must have (default `["url"]`)
+    pub purchase_link_fields: Option<Vec<String>>,
+    /// Keys whose string values are checked for stray whitespace (default `["id", "name"]`)
+    pub whitespace_fields: Option<Vec<String>>,
+    /// Warn about sizes with more purchase links than this (off by default)
+    pub max_purchase_links: Option<u32>,
+    /// Key -> maximum string length; replaces the defaults (`id` 64, `name` 128, `description` 2000)
+    pub field_length_limits: Option<HashMap<String, u32>>,
+    /// Report missing or unreadable schema files once, up front, as "Schema" errors
+    pub schema_preflight: Option<bool>,
+    /// Glob pattern (relative to `dataDir`) -> schema name, for auxiliary JSON files
+    pub extra_json: Option<HashMap<String, String>>,
+    /// Also write `{ error_count, warning_count, is_valid, files_validated, duration_ms }` to this file
+    pub summary_out: Option<String>,
+    /// Keys whose values must be numbers (default `["diameter", "weight_grams", "density"]`)
+    pub number_fields: Option<Vec<String>>,
+    /// Keys whose values must be strings (none by default)
+    pub text_fields: Option<Vec<String>>,
+    /// Extensions logo files may have (default `["png", "jpg", "svg"]`; add `"avif"`/`"heic"` to accept those)
+    pub allowed_logo_extensions: Option<Vec<String>>,
+    /// Also write the discovered brands/materials/filaments/variants/sizes and stores to this file
+    pub manifest_out: Option<String>,
+    /// Only validate files changed since this git ref (e.g. `"origin/main"`); deleted files are skipped
+    pub since: Option<String>,
+    /// Warn about PNG/JPEG logos whose metadata (color profiles, EXIF, text chunks) exceeds this many bytes
+    pub max_logo_metadata_bytes: Option<u32>,
+    /// Drop issues listed in this baseline file, so only new ones are reported
+    pub baseline: Option<String>,
+    /// Write every issue found (before `baseline` is applied) to this file as a baseline
+    pub write_baseline: Option<String>,
+    /// Pixels a logo's width and height may differ by and still pass the square check with a warning (default 0)
+    pub logo_square_tolerance: Option<u32>,
+    /// Warn about sizes with neither a `gtin` nor an `ean`
+    pub require_barcode: Option<bool>,
+    /// Stop after the first validation stage that reports an error (best effort per stage)
+    pub fail_fast: Option<bool>,
+    /// Warn when one brand's entities reference logos in different formats (e.g. svg and png)
+    pub consistent_logo_formats: Option<bool>,
+    /// Warn about sizes.json files mixing diameters
+    pub diameter_consistency: Option<bool>,
+    /// Warn about brands no purchase link sells through a store
+    pub brand_without_store: Option<bool>,
+    /// Warn about purchase links to a store whose `brands` list leaves out the brand
+    pub store_brand_coverage: Option<bool>,
+    /// If/then field rules such as `"sizes: if available == true then purchase_links nonempty"`, reported as "Content" errors
+    pub conditional_rules: Option<Vec<String>>,
+    /// `"nested"` (default) or `"flat"`: every data file directly in `dataDir`, named `<name>.<schema>.json`
+    pub layout: Option<String>,
+}
+
 #[napi]
 pub fn validate_all(
     data_dir: String,
     stores_dir: String,
     schemas_dir: Option<String>,
-) -> ValidationResult {
+    options: Option<ValidateAllOptions>,
+) -> Result<ValidationResult> {
+    let started = std::time::Instant::now();
+    let options = options.unwrap_or_default();
+    let layout = options.layout.as_deref().unwrap_or("nested").parse().map_err(Error::from_reason)?;
+    let core_options = core_options(&options)?;
+    let mut dataset = load_dataset(
+        &data_dir,
+        &stores_dir,
+        schemas_dir.as_deref(),
+        options.schema_files,
+        options.extra_json,
+        layout,
+    )?;
+    if let Some(git_ref) = &options.since {
+        let changed = core::git::changed_files(Path::new(&data_dir), git_ref).map_err(|e| {
+            Error::from_reason(format!("Could not list files changed since '{}': {}", git_ref, e))
+        })?;
+        dataset.retain_changed(&changed);
+    }
+    let mut result = core::validate_dataset_with_options(&dataset, &core_options);
+    if let Some(path) = options.write_baseline {
+        std::fs::write(&path, core::baseline::Baseline::from_result(&result).to_json())
+            .map_err(|e| Error::from_reason(format!("Failed to write '{}': {}", path, e)))?;
+    }
+    if let Some(path) = options.baseline {
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| Error::from_reason(format!("Failed to read '{}': {}", path, e)))?;
+        let baseline = core::baseline::Baseline::from_json(&json)
+            .map_err(|e| Error::from_reason(format!("Invalid baseline '{}': {}", path, e)))?;
+        baseline.suppress(&mut result);
+    }
+    if let Some(path) = options.summary_out {
+        let summary = core::report::StatusSummary::new(&result, dataset.file_count(), started.elapsed());
+        std::fs::write(&path, summary.to_json())
+            .map_err(|e| Error::from_reason(format!("Failed to write '{}': {}", path, e)))?;
+    }
+    if let Some(path) = options.manifest_out {
+        std::fs::write(&path, dataset.catalog().to_json())
+            .map_err(|e| Error::from_reason(format!("Failed to write '{}': {}", path, e)))?;
+    }
+    Ok(result.into())
+}
+
+/// A JSON file loaded by `validate_all_with_dataset`, with its parsed content.
+#[napi(object)]
+pub struct JsonEntry {
+    pub path: String,
+    pub schema_name: String,
+    pub data: serde_json::Value,
+}
+
+#[napi(object)]
+pub struct ValidationWithDataset {
+    pub result: ValidationResult,
+    pub json_entries: Vec<JsonEntry>,
+}
+
+/// Like `validate_all`, also returning the JSON files it loaded with their parsed
+/// content, so the data can be processed without reading the files again.
+#[napi]
+pub fn validate_all_with_dataset(
+    data_dir: String,
+    stores_dir: String,
+    schemas_dir: Option<String>,
+    include_passing: Option<bool>,
+) -> ValidationWithDataset {
     let schemas = PathBuf::from(schemas_dir.as_deref().unwrap_or("schemas"));
-    let dataset = core::DataSet::from_directories(
-        &PathBuf::from(&data_dir),
-        &PathBuf::from(&stores_dir),
+    let options = core::ValidationOptions {
+        include_passing: include_passing.unwrap_or(false),
+        ..Default::default()
+    };
+    let (result, dataset) = core::validate_all_with_dataset(
+        Path::new(&data_dir),
+        Path::new(&stores_dir),
         &schemas,
+        &options,
     );
-    core::validate_dataset(&dataset).into()
+    ValidationWithDataset {
+        result: result.into(),
+        json_entries: dataset
+            .json_entries
+            .into_iter()
+            .map(|(path, schema_name, data)| JsonEntry { path, schema_name, data })
+            .collect(),
+    }
 }
 
 #[napi]
-pub fn validate_json_files(
-    data_dir: String,
+pub fn validate_stores(
     stores_dir: String,
     schemas_dir: Option<String>,
+    include_passing: Option<bool>,
 ) -> ValidationResult {
-    use rayon::prelude::*;
+    let schemas = PathBuf::from(schemas_dir.as_deref().unwrap_or("schemas"));
+    let dataset = core::DataSet::from_stores_directory(&PathBuf::from(&stores_dir), &schemas);
+    let options = core::ValidationOptions {
+        include_passing: include_passing.unwrap_or(false),
+        ..Default::default()
+    };
+    core::validate_dataset_with_options(&dataset, &options).into()
+}
 
+/// Validate one brand's subtree, matched by folder name or by the `id` or `name` in its
+/// brand.json. Stores are only read for their ids. Errors if no brand matches.
+#[napi]
+pub fn validate_brand(
+    data_dir: String,
+    stores_dir: String,
+    brand: String,
+    schemas_dir: Option<String>,
+    include_passing: Option<bool>,
+) -> Result<ValidationResult> {
     let schemas = PathBuf::from(schemas_dir.as_deref().unwrap_or("schemas"));
-    let dataset = core::DataSet::from_directories(
+    let dataset = core::DataSet::from_brand_directory(
         &PathBuf::from(&data_dir),
         &PathBuf::from(&stores_dir),
         &schemas,
+        &brand,
     );
+    if dataset.file_manifest.brands.is_empty() {
+        return Err(Error::from_reason(format!("No brand named '{}' in {}", brand, data_dir)));
+    }
+    let options = core::ValidationOptions {
+        include_passing: include_passing.unwrap_or(false),
+        ..Default::default()
+    };
+    Ok(core::validate_dataset_with_options(&dataset, &options).into())
+}
 
-    let results: Vec<core::ValidationResult> = dataset.json_entries
-        .par_iter()
-        .map(|(path, schema_name, data)| {
-            core::validators::validate_json(data, schema_name, &dataset.schema_cache, Some(path))
-        })
-        .collect();
-
-    let mut result = core::ValidationResult::default();
-    for r in results {
-        result.merge_from(&r);
+/// Validate a zipped dataset (data/, stores/ and schemas/, optionally inside one
+/// wrapping folder) held in a Buffer, without extracting it to disk. With `limits`, an
+/// archive whose declared sizes exceed them gives "Limits" errors and is not extracted.
+#[napi]
+pub fn validate_archive(
+    archive: Buffer,
+    include_passing: Option<bool>,
+    limits: Option<ContentLimitsInput>,
+) -> Result<ValidationResult> {
+    let limits: core::ContentLimits = limits.unwrap_or_default().into();
+    if !limits.is_unlimited() {
+        let exceeded = limits
+            .check_zip(&archive)
+            .map_err(|e| Error::from_reason(format!("Invalid zip archive: {}", e)))?;
+        if !exceeded.is_valid() {
+            return Ok(exceeded.into());
+        }
     }
-    result.into()
+    let dataset = core::DataSet::from_zip(&archive)
+        .map_err(|e| Error::from_reason(format!("Invalid zip archive: {}", e)))?;
+    let options = core::ValidationOptions {
+        include_passing: include_passing.unwrap_or(false),
+        ..Default::default()
+    };
+    Ok(core::validate_dataset_with_options(&dataset, &options).into())
+}
+
+#[napi]
+pub fn validate_json_files(
+    data_dir: String,
+    stores_dir: String,
+    schemas_dir: Option<String>,
+    schema_files: Option<HashMap<String, String>>,
+    full_messages: Option<bool>,
+) -> Result<ValidationResult> {
+    let dataset = load_dataset(&data_dir, &stores_dir, schemas_dir.as_deref(), schema_files, None, Default::default())?;
+    let json_options = json_options(full_messages);
+
+    Ok(core::validate_json_schemas(&dataset, &json_options).into())
 }
 
 #[napi]
@@ -102,22 +496,25 @@ pub fn validate_logo_files(
 
     let results: Vec<core::ValidationResult> = dataset.logo_entries
         .par_iter()
-        .map(|(path, filename, bytes, logo_name)| {
+        .map(|(path, filename, bytes, logo_name, context)| {
             if bytes.is_empty() {
                 let mut r = core::ValidationResult::default();
-                r.add(core::ValidationError::error("Logo", "Logo file not found", Some(path.clone())));
+                r.add(core::ValidationError::error(core::codes::LOGO, "Logo file not found", Some(path.clone())));
                 r
             } else {
-                core::validators::validate_logo(bytes, filename, logo_name.as_deref(), Some(path))
+                core::validators::validate_logo_in_context(
+                    bytes,
+                    filename,
+                    logo_name.as_deref(),
+                    Some(path),
+                    *context,
+                    &core::LogoOptions::default(),
+                )
             }
         })
         .collect();
 
-    let mut result = core::ValidationResult::default();
-    for r in results {
-        result.merge_from(&r);
-    }
-    result.into()
+    core::ValidationResult::merge_all(results).into()
 }
 
 #[napi]
@@ -141,11 +538,7 @@ pub fn validate_folder_names(
         })
         .collect();
 
-    let mut result = core::ValidationResult::default();
-    for r in results {
-        result.merge_from(&r);
-    }
-    result.into()
+    core::ValidationResult::merge_all(results).into()
 }
 
 #[napi]
@@ -156,19 +549,14 @@ pub fn validate_store_ids(
     use std::collections::HashSet;
     use walkdir::WalkDir;
 
-    let stores_path = PathBuf::from(&stores_dir);
-    let data_path = PathBuf::from(&data_dir);
+    let stores_path = core::util::normalize_dir(Path::new(&stores_dir));
+    let data_path = core::util::normalize_dir(Path::new(&data_dir));
 
     let mut valid_store_ids = HashSet::new();
-    if let Ok(entries) = std::fs::read_dir(&stores_path) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let store_dir = entry.path();
-            if !store_dir.is_dir() { continue; }
-            let store_file = store_dir.join("store.json");
-            if let Some(data) = core::util::load_json(&store_file) {
-                if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
-                    valid_store_ids.insert(id.to_string());
-                }
+    for store_dir in core::util::store_dirs(&stores_path) {
+        if let Some(data) = core::util::load_json(&store_dir.join("store.json")) {
+            if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+                valid_store_ids.insert(id.to_string());
             }
         }
     }
@@ -189,7 +577,7 @@ pub fn validate_store_ids(
 pub fn validate_gtin_ean(data_dir: String) -> ValidationResult {
     use walkdir::WalkDir;
 
-    let data_path = PathBuf::from(&data_dir);
+    let data_path = core::util::normalize_dir(Path::new(&data_dir));
     let mut sizes_entries = Vec::new();
     for entry in WalkDir::new(&data_path).into_iter().filter_map(|e| e.ok()) {
         if entry.file_name() != "sizes.json" { continue; }
@@ -223,7 +611,7 @@ pub fn validate_logo_file(
 
     if !path.exists() {
         let mut result = core::ValidationResult::default();
-        result.add(core::ValidationError::error("Logo", "Logo file not found", Some(logo_path)));
+        result.add(core::ValidationError::error(core::codes::LOGO, "Logo file not found", Some(logo_path)));
         return result.into();
     }
 
@@ -237,7 +625,7 @@ pub fn validate_logo_file(
         }
         Err(e) => {
             let mut result = core::ValidationResult::default();
-            result.add(core::ValidationError::error("Logo", format!("Failed to read logo file: {}", e), Some(logo_path)));
+            result.add(core::ValidationError::error(core::codes::LOGO, format!("Failed to read logo file: {}", e), Some(logo_path)));
             result.into()
         }
     }
@@ -254,7 +642,7 @@ pub fn validate_folder_name(
 
     if !json_path.exists() {
         let mut result = core::ValidationResult::default();
-        result.add(core::ValidationError::error("Folder", format!("Missing {}", json_file), Some(folder_path)));
+        result.add(core::ValidationError::error(core::codes::FOLDER, format!("Missing {}", json_file), Some(folder_path)));
         return result.into();
     }
 
@@ -270,6 +658,33 @@ pub fn validate_folder_name(
     core::validators::validate_folder_name(&actual_name, &data, &json_key, Some(&folder_path)).into()
 }
 
+/// Return the validation tasks for a dataset as a JSON string, without running them.
+/// When `output_path` is given the JSON is also written to that file.
+#[napi]
+pub fn dump_tasks(
+    data_dir: String,
+    stores_dir: String,
+    output_path: Option<String>,
+    schemas_dir: Option<String>,
+) -> Result<String> {
+    let schemas = PathBuf::from(schemas_dir.as_deref().unwrap_or("schemas"));
+    let dataset = core::DataSet::from_directories(
+        &PathBuf::from(&data_dir),
+        &PathBuf::from(&stores_dir),
+        &schemas,
+    );
+
+    let json = serde_json::to_string_pretty(&dataset.task_list())
+        .map_err(|e| Error::from_reason(format!("Failed to serialize tasks: {}", e)))?;
+
+    if let Some(path) = output_path {
+        std::fs::write(&path, &json)
+            .map_err(|e| Error::from_reason(format!("Failed to write '{}': {}", path, e)))?;
+    }
+
+    Ok(json)
+}
+
 // ---- String/Content mode (in-memory, no filesystem access) ----
 
 #[napi(object)]
@@ -286,6 +701,13 @@ pub struct LogoFileInput {
     pub content: Buffer,
 }
 
+/// One logo's outcome from `validate_logos_content`.
+#[napi(object)]
+pub struct LogoFileResult {
+    pub path: String,
+    pub result: ValidationResult,
+}
+
 #[napi(object)]
 pub struct FolderInput {
     pub path: String,
@@ -307,6 +729,33 @@ pub struct ValidateAllContentInput {
     pub folders: Vec<FolderInput>,
     pub store_ids: Vec<String>,
     pub schemas: HashMap<String, String>,
+    /// Reject the input with "Limits" errors, before parsing it, when it exceeds these
+    pub limits: Option<ContentLimitsInput>,
+    /// Validation settings as for `validateAll`; the ones about files on disk
+    /// (`schemaFiles`, `extraJson`, `since`, `summaryOut`, `manifestOut`, `baseline`,
+    /// `writeBaseline`, `layout`) are ignored
+    pub options: Option<ValidateAllOptions>,
+}
+
+/// Size limits for in-memory input; unset fields are unlimited.
+#[napi(object)]
+#[derive(Default)]
+pub struct ContentLimitsInput {
+    /// Combined size of all files, in bytes
+    pub max_total_bytes: Option<u32>,
+    pub max_files: Option<u32>,
+    /// Size of any single logo, in bytes
+    pub max_logo_bytes: Option<u32>,
+}
+
+impl From<ContentLimitsInput> for core::ContentLimits {
+    fn from(limits: ContentLimitsInput) -> Self {
+        Self {
+            max_total_bytes: limits.max_total_bytes.map(u64::from),
+            max_files: limits.max_files.map(|n| n as usize),
+            max_logo_bytes: limits.max_logo_bytes.map(u64::from),
+        }
+    }
 }
 
 #[napi]
@@ -328,7 +777,37 @@ pub fn validate_json_content(
         .collect::<Result<_>>()?;
 
     let cache = core::SchemaCache::from_map(schema_values);
-    Ok(core::validators::validate_json(&data, &schema_name, &cache, file_path.as_deref()).into())
+    Ok(core::validators::validate_json_source(
+        &data,
+        &content,
+        &schema_name,
+        &cache,
+        file_path.as_deref(),
+        &core::JsonOptions::default(),
+    )
+    .into())
+}
+
+/// Validate a JSON string against an inline schema (also a JSON string), without named
+/// schemas. `$ref`s can only point inside the schema.
+#[napi]
+pub fn validate_json_against_schema(
+    content: String,
+    schema: String,
+    file_path: Option<String>,
+) -> Result<ValidationResult> {
+    let data: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| Error::from_reason(format!("Invalid JSON: {}", e)))?;
+    let schema: serde_json::Value = serde_json::from_str(&schema)
+        .map_err(|e| Error::from_reason(format!("Invalid schema JSON: {}", e)))?;
+
+    Ok(core::validators::validate_value_against_schema_with_options(
+        &data,
+        &schema,
+        file_path.as_deref(),
+        &core::JsonOptions::default(),
+    )
+    .into())
 }
 
 #[napi]
@@ -337,15 +816,48 @@ pub fn validate_logo_content(
     filename: String,
     logo_name: Option<String>,
     file_path: Option<String>,
+    require_transparency: Option<bool>,
+    allowed_sizes: Option<Vec<u32>>,
+    warn_high_bit_depth: Option<bool>,
 ) -> ValidationResult {
-    core::validators::validate_logo(
+    let options = core::LogoOptions {
+        require_transparency: require_transparency.unwrap_or(false),
+        allowed_sizes,
+        warn_high_bit_depth: warn_high_bit_depth.unwrap_or(false),
+        ..Default::default()
+    };
+    core::validators::validate_logo_with_options(
         content.as_ref(),
         &filename,
         logo_name.as_deref(),
         file_path.as_deref(),
+        &options,
     ).into()
 }
 
+/// Validate several logos in parallel, returning one result per file in input order
+/// so each logo's status can be shown on its own.
+#[napi]
+pub fn validate_logos_content(files: Vec<LogoFileInput>) -> Vec<LogoFileResult> {
+    use rayon::prelude::*;
+
+    // Convert Buffer to Vec<u8> for Send safety
+    let logo_data: Vec<(String, String, Vec<u8>)> = files.into_iter()
+        .map(|logo| (logo.path, logo.filename, logo.content.to_vec()))
+        .collect();
+    let logo_results: Vec<core::ValidationResult> = logo_data
+        .par_iter()
+        .map(|(path, filename, bytes)| {
+            core::validators::validate_logo(bytes, filename, None, Some(path.as_str()))
+        })
+        .collect();
+
+    logo_data.into_iter()
+        .zip(logo_results)
+        .map(|((path, _, _), result)| LogoFileResult { path, result: result.into() })
+        .collect()
+}
+
 #[napi]
 pub fn validate_folder_name_content(
     folder_name: String,
@@ -401,9 +913,20 @@ pub fn validate_store_ids_content(
 
 #[napi]
 pub fn validate_all_content(
-    data: ValidateAllContentInput,
+    mut data: ValidateAllContentInput,
 ) -> Result<ValidationResult> {
-    use rayon::prelude::*;
+    let options = core_options(&data.options.take().unwrap_or_default())?;
+
+    // Check the size limits before parsing or decoding anything
+    let limits: core::ContentLimits = data.limits.take().unwrap_or_default().into();
+    let exceeded = limits.check(
+        data.json_files.iter().map(|f| (f.path.as_str(), f.content.len() as u64, false))
+            .chain(data.folders.iter().map(|f| (f.path.as_str(), f.json_content.len() as u64, false)))
+            .chain(data.logo_files.iter().map(|f| (f.path.as_str(), f.content.len() as u64, true))),
+    );
+    if !exceeded.is_valid() {
+        return Ok(exceeded.into());
+    }
 
     // Build schema cache from provided schemas
     let schema_values: HashMap<String, serde_json::Value> = data.schemas.into_iter()
@@ -413,19 +936,20 @@ pub fn validate_all_content(
             val.map(|v| (k, v))
         })
         .collect::<Result<_>>()?;
-    let schema_cache = core::SchemaCache::from_map(schema_values);
 
-    // Parse all JSON file inputs
-    let json_parsed: Vec<(String, String, serde_json::Value)> = data.json_files.into_iter()
+    // Parse all JSON file inputs, keeping their text for line/column info
+    let mut json_sources = HashMap::new();
+    let json_entries: Vec<(String, String, serde_json::Value)> = data.json_files.into_iter()
         .map(|f| {
             let val = serde_json::from_str(&f.content)
-                .map_err(|e| Error::from_reason(format!("Invalid JSON for '{}': {}", f.path, e)));
-            val.map(|v| (f.path, f.schema_name, v))
+                .map_err(|e| Error::from_reason(format!("Invalid JSON for '{}': {}", f.path, e)))?;
+            json_sources.insert(f.path.clone(), f.content);
+            Ok((f.path, f.schema_name, val))
         })
         .collect::<Result<_>>()?;
 
     // Parse folder JSON inputs
-    let folder_parsed: Vec<(String, String, serde_json::Value, String)> = data.folders.into_iter()
+    let folder_entries: Vec<(String, String, serde_json::Value, String)> = data.folders.into_iter()
         .map(|f| {
             let val = serde_json::from_str(&f.json_content)
                 .map_err(|e| Error::from_reason(format!("Invalid JSON for folder '{}': {}", f.path, e)));
@@ -433,61 +957,43 @@ pub fn validate_all_content(
         })
         .collect::<Result<_>>()?;
 
-    let mut result = core::ValidationResult::default();
-
-    // JSON validation (parallel)
-    let json_results: Vec<core::ValidationResult> = json_parsed
-        .par_iter()
-        .map(|(path, schema_name, data)| {
-            core::validators::validate_json(data, schema_name, &schema_cache, Some(path))
-        })
-        .collect();
-    for r in json_results {
-        result.merge_from(&r);
-    }
-
-    // Logo validation (parallel) — convert Buffer to Vec<u8> for Send safety
-    let logo_data: Vec<(String, String, Vec<u8>)> = data.logo_files.into_iter()
-        .map(|logo| (logo.path, logo.filename, logo.content.to_vec()))
+    // A store.json's folder is a store directory, and logos in it are store logos
+    let parent = |path: &str| path.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+    let store_dirs: std::collections::HashSet<String> = json_entries.iter()
+        .filter(|(_, schema_name, _)| schema_name == "store")
+        .map(|(path, _, _)| parent(path))
         .collect();
-    let logo_results: Vec<core::ValidationResult> = logo_data
-        .par_iter()
-        .map(|(path, filename, bytes)| {
-            core::validators::validate_logo(
-                bytes,
-                filename,
-                None,
-                Some(path.as_str()),
-            )
-        })
-        .collect();
-    for r in logo_results {
-        result.merge_from(&r);
-    }
-
-    // Folder name validation (parallel)
-    let folder_results: Vec<core::ValidationResult> = folder_parsed
-        .par_iter()
-        .map(|(path, folder_name, json_data, json_key)| {
-            core::validators::validate_folder_name(folder_name, json_data, json_key, Some(path))
+    let logo_entries: Vec<_> = data.logo_files.into_iter()
+        .map(|logo| {
+            let context = if store_dirs.contains(&parent(&logo.path)) {
+                core::validators::LogoContext::Store
+            } else {
+                core::validators::LogoContext::Brand
+            };
+            (logo.path, logo.filename, logo.content.to_vec(), None, context)
         })
         .collect();
-    for r in folder_results {
-        result.merge_from(&r);
-    }
-
-    // Collect sizes entries from json_parsed for gtin/store_id validation
-    let sizes_entries: Vec<(&str, &serde_json::Value)> = json_parsed.iter()
-        .filter(|(_, schema_name, _)| schema_name == "sizes")
-        .map(|(path, _, data)| (path.as_str(), data))
-        .collect();
-
-    // Store ID validation
-    let valid_ids: std::collections::HashSet<String> = data.store_ids.into_iter().collect();
-    result.merge_from(&core::validators::validate_store_ids(&valid_ids, &sizes_entries));
 
-    // GTIN/EAN validation
-    result.merge_from(&core::validators::validate_gtin_ean(&sizes_entries));
-
-    Ok(result.into())
+    let dataset = core::DataSet {
+        sizes_entries: json_entries.iter()
+            .filter(|(_, schema_name, _)| schema_name == "sizes")
+            .map(|(path, _, data)| (path.clone(), data.clone()))
+            .collect(),
+        json_entries,
+        json_sources,
+        logo_entries,
+        folder_entries,
+        valid_store_ids: data.store_ids.into_iter().collect(),
+        file_manifest: core::validators::missing_files::FileManifest {
+            brands: Vec::new(),
+            stores: store_dirs.into_iter()
+                .map(|path| core::validators::missing_files::StoreEntry { path, has_store_json: true })
+                .collect(),
+            orphan_sizes_dirs: Vec::new(),
+        },
+        schema_cache: core::SchemaCache::from_map(schema_values),
+        load_issues: Vec::new(),
+        changed_only: false,
+    };
+    Ok(core::validate_dataset_with_options(&dataset, &options).into())
 }
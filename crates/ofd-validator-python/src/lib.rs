@@ -6,13 +6,15 @@ mod util;
 mod validators;
 
 use orchestrator::{
-    validate_all, validate_all_with_changes, validate_folder_names, validate_json_files,
-    validate_logo_files,
+    dump_tasks, validate_all, validate_all_with_changes, validate_all_with_dataset, validate_archive,
+    validate_brand, validate_folder_names, validate_json_files, validate_logo_files, validate_stores,
 };
-use types::{ValidationError, ValidationLevel, ValidationResult};
+use types::{list_codes, ValidationError, ValidationLevel, ValidationResult};
+use util::set_color;
 use validators::{
-    validate_folder_name, validate_gtin_ean, validate_logo_file, validate_required_files,
-    validate_store_ids,
+    validate_folder_name, validate_folder_name_content, validate_gtin_ean,
+    validate_json_against_schema, validate_json_content, validate_logo_file,
+    validate_required_files, validate_store_ids,
 };
 
 #[pymodule]
@@ -24,9 +26,14 @@ fn ofd_validator(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Orchestrated batch validators (internally parallel with rayon)
     m.add_function(wrap_pyfunction!(validate_all, m)?)?;
     m.add_function(wrap_pyfunction!(validate_all_with_changes, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_all_with_dataset, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_stores, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_brand, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_archive, m)?)?;
     m.add_function(wrap_pyfunction!(validate_json_files, m)?)?;
     m.add_function(wrap_pyfunction!(validate_logo_files, m)?)?;
     m.add_function(wrap_pyfunction!(validate_folder_names, m)?)?;
+    m.add_function(wrap_pyfunction!(dump_tasks, m)?)?;
 
     // Individual validators
     m.add_function(wrap_pyfunction!(validate_store_ids, m)?)?;
@@ -35,5 +42,16 @@ fn ofd_validator(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(validate_logo_file, m)?)?;
     m.add_function(wrap_pyfunction!(validate_folder_name, m)?)?;
 
+    // Content-mode validators (in-memory, no data directory access)
+    m.add_function(wrap_pyfunction!(validate_json_content, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_json_against_schema, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_folder_name_content, m)?)?;
+
+    // Reference
+    m.add_function(wrap_pyfunction!(list_codes, m)?)?;
+
+    // Output settings
+    m.add_function(wrap_pyfunction!(set_color, m)?)?;
+
     Ok(())
 }
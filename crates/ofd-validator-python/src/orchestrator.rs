@@ -1,49 +1,292 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use rayon::ThreadPoolBuilder;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use ofd_validator_core as core;
 
 use crate::types::ValidationResult;
-use crate::util::log_step;
+use crate::util::{ensure_dir, log_step};
 
-/// Run the closure on a custom Rayon thread pool when `max_workers` is set,
-/// otherwise use the global pool (default Rayon behaviour).
-fn with_thread_pool<F, R>(max_workers: Option<usize>, f: F) -> R
+/// The Rayon pool built for the most recent explicit `max_workers` value, so repeated
+/// calls with the same worker count reuse it. A different count replaces it; the old
+/// pool's threads exit once calls still running on it finish.
+static THREAD_POOL: Mutex<Option<(usize, Arc<ThreadPool>)>> = Mutex::new(None);
+
+/// Return the cached pool if it has `num_threads` threads, otherwise build one and cache it.
+fn cached_thread_pool(num_threads: usize) -> PyResult<Arc<ThreadPool>> {
+    let mut cached = THREAD_POOL.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((n, pool)) = cached.as_ref() {
+        if *n == num_threads {
+            return Ok(Arc::clone(pool));
+        }
+    }
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| PyRuntimeError::new_err(format!("Failed to build Rayon thread pool: {}", e)))?;
+    let pool = Arc::new(pool);
+    *cached = Some((num_threads, Arc::clone(&pool)));
+    Ok(pool)
+}
+
+/// Run the closure on a cached Rayon thread pool sized by `max_workers`, falling back to
+/// the `OFD_VALIDATOR_WORKERS` env var, otherwise use the global pool (default Rayon behaviour).
+fn with_thread_pool<F, R>(max_workers: Option<usize>, f: F) -> PyResult<R>
 where
     F: FnOnce() -> R + Send,
     R: Send,
 {
-    match max_workers {
-        Some(n) if n > 0 => {
-            let pool = ThreadPoolBuilder::new()
-                .num_threads(n)
-                .build()
-                .expect("failed to build Rayon thread pool");
-            pool.install(f)
+    match core::util::resolve_workers(max_workers) {
+        Some(n) => Ok(cached_thread_pool(n)?.install(f)),
+        None => Ok(f()),
+    }
+}
+
+/// Write the `core::report::StatusSummary` for `result` to `path`, timed from `started`.
+fn write_summary(path: &str, result: &core::ValidationResult, files_validated: usize, started: Instant) -> PyResult<()> {
+    let summary = core::report::StatusSummary::new(result, files_validated, started.elapsed());
+    std::fs::write(path, summary.to_json())
+        .map_err(|e| PyIOError::new_err(format!("Failed to write '{}': {}", path, e)))
+}
+
+/// Write the catalog (`core::DataSet::catalog`) discovered while loading to `path`.
+fn write_manifest(path: &str, dataset: &core::DataSet) -> PyResult<()> {
+    std::fs::write(path, dataset.catalog().to_json())
+        .map_err(|e| PyIOError::new_err(format!("Failed to write '{}': {}", path, e)))
+}
+
+/// Parse rules written as `<schema>: if <field>[ == <value>] then <field>[ nonempty]`,
+/// raising `ValueError` for one that doesn't follow the format.
+fn parse_conditional_rules(rules: Option<Vec<String>>) -> PyResult<Vec<core::validators::ConditionalRule>> {
+    rules
+        .unwrap_or_default()
+        .iter()
+        .map(|rule| rule.parse().map_err(PyValueError::new_err))
+        .collect()
+}
+
+/// Write every issue in `result` to `write_to` as a baseline, then drop the issues listed
+/// in the baseline file `suppress_from`. An unreadable baseline raises `IOError` and an
+/// invalid one `ValueError`.
+fn apply_baseline(
+    result: &mut core::ValidationResult,
+    suppress_from: Option<&str>,
+    write_to: Option<&str>,
+) -> PyResult<()> {
+    if let Some(path) = write_to {
+        std::fs::write(path, core::baseline::Baseline::from_result(result).to_json())
+            .map_err(|e| PyIOError::new_err(format!("Failed to write '{}': {}", path, e)))?;
+    }
+    if let Some(path) = suppress_from {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| PyIOError::new_err(format!("Failed to read '{}': {}", path, e)))?;
+        let baseline = core::baseline::Baseline::from_json(&json)
+            .map_err(|e| PyValueError::new_err(format!("Invalid baseline '{}': {}", path, e)))?;
+        baseline.suppress(result);
+    }
+    Ok(())
+}
+
+/// Load a dataset, applying any custom schema-name-to-filename mappings and
+/// collecting extra JSON files matched by glob (an invalid glob raises `ValueError`).
+fn load_dataset(
+    data_dir: &Path,
+    stores_dir: &Path,
+    schemas_dir: &Path,
+    schema_files: &Option<HashMap<String, String>>,
+    extra_json: &Option<HashMap<String, String>>,
+    layout: core::LayoutStrategy,
+) -> PyResult<core::DataSet> {
+    let schema_cache = match schema_files {
+        Some(mapping) => {
+            let extra: Vec<(&str, &str)> = mapping.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+            core::SchemaCache::with_mapping(schemas_dir, &extra)
         }
-        _ => f(),
+        None => core::SchemaCache::from_directory(schemas_dir),
+    };
+    let mut dataset = core::DataSet::from_directories_with_cache_and_layout(data_dir, stores_dir, schema_cache, layout);
+    if let Some(globs) = extra_json {
+        let extra: Vec<(&str, &str)> = globs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        dataset
+            .add_extra_json(data_dir, &extra)
+            .map_err(|e| PyValueError::new_err(format!("Invalid extra_json pattern: {}", e)))?;
     }
+    Ok(dataset)
+}
+
+/// JSON validation settings; `full_messages` disables message truncation.
+fn json_options(full_messages: bool) -> core::JsonOptions {
+    if full_messages {
+        core::JsonOptions { max_message_len: None }
+    } else {
+        core::JsonOptions::default()
+    }
+}
+
+/// The `validate_all*` keyword arguments that map onto `ValidationOptions`.
+struct OptionArgs {
+    include_passing: bool,
+    require_transparent_logos: bool,
+    full_messages: bool,
+    allowed_logo_sizes: Option<Vec<u32>>,
+    purchase_link_fields: Option<Vec<String>>,
+    warn_high_bit_depth_logos: bool,
+    whitespace_fields: Option<Vec<String>>,
+    max_purchase_links: Option<usize>,
+    schema_preflight: bool,
+    field_length_limits: Option<HashMap<String, usize>>,
+    number_fields: Option<Vec<String>>,
+    text_fields: Option<Vec<String>>,
+    allowed_logo_extensions: Option<Vec<String>>,
+    max_logo_metadata_bytes: Option<u64>,
+    logo_square_tolerance: Option<u32>,
+    require_barcode: bool,
+    fail_fast: bool,
+    consistent_logo_formats: bool,
+    diameter_consistency: bool,
+    brand_without_store: bool,
+    store_brand_coverage: bool,
+    conditional_rules: Option<Vec<String>>,
+}
+
+/// Build the core options from the keyword arguments, raising `ValueError` for an
+/// invalid conditional rule.
+fn build_options(args: OptionArgs) -> PyResult<core::ValidationOptions> {
+    let mut options = core::ValidationOptions {
+        include_passing: args.include_passing,
+        logo: core::LogoOptions {
+            require_transparency: args.require_transparent_logos,
+            allowed_sizes: args.allowed_logo_sizes,
+            warn_high_bit_depth: args.warn_high_bit_depth_logos,
+            max_metadata_bytes: args.max_logo_metadata_bytes,
+            consistent_brand_format: args.consistent_logo_formats,
+            ..Default::default()
+        },
+        json: json_options(args.full_messages),
+        ..Default::default()
+    };
+    if let Some(fields) = args.purchase_link_fields {
+        options.purchase_link_fields = fields;
+    }
+    if let Some(fields) = args.whitespace_fields {
+        options.whitespace_fields = fields;
+    }
+    options.max_purchase_links = args.max_purchase_links;
+    options.schema_preflight = args.schema_preflight;
+    if let Some(limits) = args.field_length_limits {
+        options.field_length_limits = limits.into_iter().collect();
+    }
+    if let Some(fields) = args.number_fields {
+        options.number_fields = fields;
+    }
+    if let Some(fields) = args.text_fields {
+        options.text_fields = fields;
+    }
+    if let Some(extensions) = args.allowed_logo_extensions {
+        options.logo.allowed_extensions = extensions;
+    }
+    if let Some(tolerance) = args.logo_square_tolerance {
+        options.logo.brand.square_tolerance = tolerance;
+        options.logo.store.square_tolerance = tolerance;
+    }
+    options.gtin.require_barcode = args.require_barcode;
+    options.fail_fast = args.fail_fast;
+    options.diameter_consistency = args.diameter_consistency;
+    options.brand_without_store_level = args.brand_without_store.then_some(core::ValidationLevel::Warning);
+    options.store_brand_coverage = args.store_brand_coverage;
+    options.conditional_rules = parse_conditional_rules(args.conditional_rules)?;
+    Ok(options)
 }
 
 #[pyfunction]
-#[pyo3(signature = (data_dir, stores_dir, schemas_dir=None, max_workers=None))]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (data_dir, stores_dir, schemas_dir=None, max_workers=None, include_passing=false, schema_files=None, require_transparent_logos=false, full_messages=false, allowed_logo_sizes=None, purchase_link_fields=None, extra_json=None, warn_high_bit_depth_logos=false, whitespace_fields=None, max_purchase_links=None, schema_preflight=false, field_length_limits=None, summary_out=None, number_fields=None, text_fields=None, allowed_logo_extensions=None, manifest_out=None, since=None, max_logo_metadata_bytes=None, baseline=None, write_baseline=None, logo_square_tolerance=None, require_barcode=false, fail_fast=false, consistent_logo_formats=false, diameter_consistency=false, brand_without_store=false, store_brand_coverage=false, conditional_rules=None, layout=None))]
 pub fn validate_all(
     py: Python<'_>,
     data_dir: &str,
     stores_dir: &str,
     schemas_dir: Option<&str>,
     max_workers: Option<usize>,
-) -> ValidationResult {
+    include_passing: bool,
+    schema_files: Option<HashMap<String, String>>,
+    require_transparent_logos: bool,
+    full_messages: bool,
+    allowed_logo_sizes: Option<Vec<u32>>,
+    purchase_link_fields: Option<Vec<String>>,
+    extra_json: Option<HashMap<String, String>>,
+    warn_high_bit_depth_logos: bool,
+    whitespace_fields: Option<Vec<String>>,
+    max_purchase_links: Option<usize>,
+    schema_preflight: bool,
+    field_length_limits: Option<HashMap<String, usize>>,
+    summary_out: Option<&str>,
+    number_fields: Option<Vec<String>>,
+    text_fields: Option<Vec<String>>,
+    allowed_logo_extensions: Option<Vec<String>>,
+    manifest_out: Option<&str>,
+    since: Option<&str>,
+    max_logo_metadata_bytes: Option<u64>,
+    baseline: Option<&str>,
+    write_baseline: Option<&str>,
+    logo_square_tolerance: Option<u32>,
+    require_barcode: bool,
+    fail_fast: bool,
+    consistent_logo_formats: bool,
+    diameter_consistency: bool,
+    brand_without_store: bool,
+    store_brand_coverage: bool,
+    conditional_rules: Option<Vec<String>>,
+    layout: Option<&str>,
+) -> PyResult<ValidationResult> {
+    let started = Instant::now();
+    let layout: core::LayoutStrategy = layout.unwrap_or("nested").parse().map_err(PyValueError::new_err)?;
     let data_dir = PathBuf::from(data_dir);
     let stores_dir = PathBuf::from(stores_dir);
     let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let options = build_options(OptionArgs {
+        include_passing,
+        require_transparent_logos,
+        full_messages,
+        allowed_logo_sizes,
+        purchase_link_fields,
+        warn_high_bit_depth_logos,
+        whitespace_fields,
+        max_purchase_links,
+        schema_preflight,
+        field_length_limits,
+        number_fields,
+        text_fields,
+        allowed_logo_extensions,
+        max_logo_metadata_bytes,
+        logo_square_tolerance,
+        require_barcode,
+        fail_fast,
+        consistent_logo_formats,
+        diameter_consistency,
+        brand_without_store,
+        store_brand_coverage,
+        conditional_rules,
+    })?;
 
     py.allow_threads(|| {
         with_thread_pool(max_workers, || {
             log_step("Loading dataset", None);
-            let dataset = core::DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+            let mut dataset = load_dataset(&data_dir, &stores_dir, &schemas_dir, &schema_files, &extra_json, layout)?;
+            if let Some(git_ref) = since {
+                log_step("Listing changed files", None);
+                let changed = core::git::changed_files(&data_dir, git_ref).map_err(|e| {
+                    PyIOError::new_err(format!("Could not list files changed since '{}': {}", git_ref, e))
+                })?;
+                dataset.retain_changed(&changed);
+            }
 
             log_step("Checking required files", None);
             log_step("Validating JSON schemas", Some(dataset.json_entries.len()));
@@ -52,13 +295,22 @@ pub fn validate_all(
             log_step("Validating store IDs", None);
             log_step("Validating GTIN/EAN codes", None);
 
-            core::validate_dataset(&dataset).into()
+            let mut result = core::validate_dataset_with_options(&dataset, &options);
+            apply_baseline(&mut result, baseline, write_baseline)?;
+            if let Some(path) = summary_out {
+                write_summary(path, &result, dataset.file_count(), started)?;
+            }
+            if let Some(path) = manifest_out {
+                write_manifest(path, &dataset)?;
+            }
+            Ok(result.into())
         })
-    })
+    })?
 }
 
 #[pyfunction]
-#[pyo3(signature = (data_dir, stores_dir, changes_json, schemas_dir=None, max_workers=None))]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (data_dir, stores_dir, changes_json, schemas_dir=None, max_workers=None, include_passing=false, schema_files=None, require_transparent_logos=false, full_messages=false, allowed_logo_sizes=None, purchase_link_fields=None, extra_json=None, warn_high_bit_depth_logos=false, whitespace_fields=None, max_purchase_links=None, schema_preflight=false, field_length_limits=None, summary_out=None, number_fields=None, text_fields=None, allowed_logo_extensions=None, manifest_out=None, max_logo_metadata_bytes=None, baseline=None, write_baseline=None, logo_square_tolerance=None, require_barcode=false, fail_fast=false, consistent_logo_formats=false, diameter_consistency=false, brand_without_store=false, store_brand_coverage=false, conditional_rules=None))]
 pub fn validate_all_with_changes(
     py: Python<'_>,
     data_dir: &str,
@@ -66,16 +318,72 @@ pub fn validate_all_with_changes(
     changes_json: &str,
     schemas_dir: Option<&str>,
     max_workers: Option<usize>,
-) -> ValidationResult {
+    include_passing: bool,
+    schema_files: Option<HashMap<String, String>>,
+    require_transparent_logos: bool,
+    full_messages: bool,
+    allowed_logo_sizes: Option<Vec<u32>>,
+    purchase_link_fields: Option<Vec<String>>,
+    extra_json: Option<HashMap<String, String>>,
+    warn_high_bit_depth_logos: bool,
+    whitespace_fields: Option<Vec<String>>,
+    max_purchase_links: Option<usize>,
+    schema_preflight: bool,
+    field_length_limits: Option<HashMap<String, usize>>,
+    summary_out: Option<&str>,
+    number_fields: Option<Vec<String>>,
+    text_fields: Option<Vec<String>>,
+    allowed_logo_extensions: Option<Vec<String>>,
+    manifest_out: Option<&str>,
+    max_logo_metadata_bytes: Option<u64>,
+    baseline: Option<&str>,
+    write_baseline: Option<&str>,
+    logo_square_tolerance: Option<u32>,
+    require_barcode: bool,
+    fail_fast: bool,
+    consistent_logo_formats: bool,
+    diameter_consistency: bool,
+    brand_without_store: bool,
+    store_brand_coverage: bool,
+    conditional_rules: Option<Vec<String>>,
+) -> PyResult<ValidationResult> {
+    let started = Instant::now();
     let data_dir = PathBuf::from(data_dir);
     let stores_dir = PathBuf::from(stores_dir);
     let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let options = build_options(OptionArgs {
+        include_passing,
+        require_transparent_logos,
+        full_messages,
+        allowed_logo_sizes,
+        purchase_link_fields,
+        warn_high_bit_depth_logos,
+        whitespace_fields,
+        max_purchase_links,
+        schema_preflight,
+        field_length_limits,
+        number_fields,
+        text_fields,
+        allowed_logo_extensions,
+        max_logo_metadata_bytes,
+        logo_square_tolerance,
+        require_barcode,
+        fail_fast,
+        consistent_logo_formats,
+        diameter_consistency,
+        brand_without_store,
+        store_brand_coverage,
+        conditional_rules,
+    })?;
     let changes_json = changes_json.to_string();
 
     py.allow_threads(|| {
         with_thread_pool(max_workers, || {
             log_step("Loading dataset", None);
-            let mut dataset = core::DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+            let mut dataset = load_dataset(&data_dir, &stores_dir, &schemas_dir, &schema_files, &extra_json, Default::default())?;
 
             log_step("Applying pending changes", None);
             dataset.apply_changes(&changes_json, &data_dir, &stores_dir);
@@ -87,46 +395,222 @@ pub fn validate_all_with_changes(
             log_step("Validating store IDs", None);
             log_step("Validating GTIN/EAN codes", None);
 
-            core::validate_dataset(&dataset).into()
+            let mut result = core::validate_dataset_with_options(&dataset, &options);
+            apply_baseline(&mut result, baseline, write_baseline)?;
+            if let Some(path) = summary_out {
+                write_summary(path, &result, dataset.file_count(), started)?;
+            }
+            if let Some(path) = manifest_out {
+                write_manifest(path, &dataset)?;
+            }
+            Ok(result.into())
+        })
+    })?
+}
+
+/// Run the store-related validations (store.json schema, folder names, logos,
+/// metadata and missing files) on the stores directory only.
+#[pyfunction]
+#[pyo3(signature = (stores_dir, schemas_dir=None, max_workers=None, include_passing=false))]
+pub fn validate_stores(
+    py: Python<'_>,
+    stores_dir: &str,
+    schemas_dir: Option<&str>,
+    max_workers: Option<usize>,
+    include_passing: bool,
+) -> PyResult<ValidationResult> {
+    let stores_dir = PathBuf::from(stores_dir);
+    let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let options = core::ValidationOptions {
+        include_passing,
+        ..Default::default()
+    };
+
+    py.allow_threads(|| {
+        with_thread_pool(max_workers, || {
+            log_step("Loading stores", None);
+            let dataset = core::DataSet::from_stores_directory(&stores_dir, &schemas_dir);
+
+            log_step("Checking required files", None);
+            log_step("Validating JSON schemas", Some(dataset.json_entries.len()));
+            log_step("Validating logos", Some(dataset.logo_entries.len()));
+            log_step("Validating folder names", Some(dataset.folder_entries.len()));
+
+            core::validate_dataset_with_options(&dataset, &options).into()
         })
     })
 }
 
+/// Run the validations on one brand's subtree, matched by folder name or by the `id`
+/// or `name` in its brand.json. Stores are only read for their ids, so purchase links
+/// are still checked against every store. Raises `ValueError` if no brand matches.
 #[pyfunction]
-#[pyo3(signature = (data_dir, stores_dir, schemas_dir=None, max_workers=None))]
-pub fn validate_json_files(
+#[pyo3(signature = (data_dir, stores_dir, brand, schemas_dir=None, max_workers=None, include_passing=false))]
+pub fn validate_brand(
     py: Python<'_>,
     data_dir: &str,
     stores_dir: &str,
+    brand: &str,
     schemas_dir: Option<&str>,
     max_workers: Option<usize>,
-) -> ValidationResult {
+    include_passing: bool,
+) -> PyResult<ValidationResult> {
     let data_dir = PathBuf::from(data_dir);
     let stores_dir = PathBuf::from(stores_dir);
     let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let options = core::ValidationOptions {
+        include_passing,
+        ..Default::default()
+    };
 
     py.allow_threads(|| {
         with_thread_pool(max_workers, || {
-            let dataset = core::DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+            log_step("Loading brand", None);
+            let dataset = core::DataSet::from_brand_directory(&data_dir, &stores_dir, &schemas_dir, brand);
+            if dataset.file_manifest.brands.is_empty() {
+                return Err(PyValueError::new_err(format!("No brand named '{}' in {}", brand, data_dir.display())));
+            }
+
+            log_step("Checking required files", None);
             log_step("Validating JSON schemas", Some(dataset.json_entries.len()));
+            log_step("Validating logos", Some(dataset.logo_entries.len()));
+            log_step("Validating folder names", Some(dataset.folder_entries.len()));
+            log_step("Validating store IDs", None);
+            log_step("Validating GTIN/EAN codes", None);
 
-            use rayon::prelude::*;
-            let results: Vec<core::ValidationResult> = dataset.json_entries
-                .par_iter()
-                .map(|(path, schema_name, data)| {
-                    core::validators::validate_json(data, schema_name, &dataset.schema_cache, Some(path))
-                })
+            Ok(core::validate_dataset_with_options(&dataset, &options).into())
+        })
+    })?
+}
+
+/// Like `validate_all`, also returning the JSON files it loaded as a list of
+/// `{"path", "schema", "data"}` dicts, so the parsed data can be processed without
+/// reading the files again.
+#[pyfunction]
+#[pyo3(signature = (data_dir, stores_dir, schemas_dir=None, max_workers=None, include_passing=false, schema_files=None, extra_json=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn validate_all_with_dataset<'py>(
+    py: Python<'py>,
+    data_dir: &str,
+    stores_dir: &str,
+    schemas_dir: Option<&str>,
+    max_workers: Option<usize>,
+    include_passing: bool,
+    schema_files: Option<HashMap<String, String>>,
+    extra_json: Option<HashMap<String, String>>,
+) -> PyResult<(ValidationResult, Bound<'py, PyAny>)> {
+    let data_dir = PathBuf::from(data_dir);
+    let stores_dir = PathBuf::from(stores_dir);
+    let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let options = core::ValidationOptions {
+        include_passing,
+        ..Default::default()
+    };
+
+    let (result, entries_json) = py.allow_threads(|| {
+        with_thread_pool(max_workers, || {
+            log_step("Loading dataset", None);
+            let dataset = load_dataset(&data_dir, &stores_dir, &schemas_dir, &schema_files, &extra_json, Default::default())?;
+            log_step("Validating", Some(dataset.file_count()));
+            let result = core::validate_dataset_with_options(&dataset, &options);
+            let entries: Vec<serde_json::Value> = dataset
+                .json_entries
+                .into_iter()
+                .map(|(path, schema, data)| serde_json::json!({ "path": path, "schema": schema, "data": data }))
                 .collect();
+            Ok::<_, PyErr>((result, serde_json::Value::Array(entries).to_string()))
+        })
+    })??;
+    let entries = py.import("json")?.call_method1("loads", (entries_json,))?;
+    Ok((result.into(), entries))
+}
+
+/// Validate a zipped dataset (data/, stores/ and schemas/, optionally inside one
+/// wrapping folder) without extracting it to disk. An archive whose declared sizes exceed
+/// `max_total_bytes`, `max_files` or `max_logo_bytes` gives "Limits" errors and is not
+/// extracted. Without limits, an archive declaring an entry over 64 MiB or more than
+/// 1 GiB in total raises `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (archive_path, max_workers=None, include_passing=false, max_total_bytes=None, max_files=None, max_logo_bytes=None))]
+pub fn validate_archive(
+    py: Python<'_>,
+    archive_path: &str,
+    max_workers: Option<usize>,
+    include_passing: bool,
+    max_total_bytes: Option<u64>,
+    max_files: Option<usize>,
+    max_logo_bytes: Option<u64>,
+) -> PyResult<ValidationResult> {
+    let archive = std::fs::read(archive_path)
+        .map_err(|e| PyIOError::new_err(format!("Could not read archive {}: {}", archive_path, e)))?;
+    let options = core::ValidationOptions {
+        include_passing,
+        ..Default::default()
+    };
 
-            let mut result = core::ValidationResult::default();
-            for r in results {
-                result.merge_from(&r);
+    py.allow_threads(|| {
+        with_thread_pool(max_workers, || {
+            log_step("Reading archive", None);
+            let limits = core::ContentLimits { max_total_bytes, max_files, max_logo_bytes };
+            if !limits.is_unlimited() {
+                let exceeded = limits
+                    .check_zip(&archive)
+                    .map_err(|e| PyValueError::new_err(format!("Invalid zip archive {}: {}", archive_path, e)))?;
+                if !exceeded.is_valid() {
+                    return Ok(exceeded.into());
+                }
             }
-            result.into()
-        })
+            let dataset = core::DataSet::from_zip(&archive)
+                .map_err(|e| PyValueError::new_err(format!("Invalid zip archive {}: {}", archive_path, e)))?;
+
+            log_step("Validating JSON schemas", Some(dataset.json_entries.len()));
+            log_step("Validating logos", Some(dataset.logo_entries.len()));
+            log_step("Validating folder names", Some(dataset.folder_entries.len()));
+
+            Ok(core::validate_dataset_with_options(&dataset, &options).into())
+        })?
     })
 }
 
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (data_dir, stores_dir, schemas_dir=None, max_workers=None, schema_files=None, full_messages=false, extra_json=None))]
+pub fn validate_json_files(
+    py: Python<'_>,
+    data_dir: &str,
+    stores_dir: &str,
+    schemas_dir: Option<&str>,
+    max_workers: Option<usize>,
+    schema_files: Option<HashMap<String, String>>,
+    full_messages: bool,
+    extra_json: Option<HashMap<String, String>>,
+) -> PyResult<ValidationResult> {
+    let data_dir = PathBuf::from(data_dir);
+    let stores_dir = PathBuf::from(stores_dir);
+    let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+    let json_options = json_options(full_messages);
+
+    py.allow_threads(|| {
+        with_thread_pool(max_workers, || {
+            let dataset = load_dataset(&data_dir, &stores_dir, &schemas_dir, &schema_files, &extra_json, Default::default())?;
+            log_step("Validating JSON schemas", Some(dataset.json_entries.len()));
+
+            Ok(core::validate_json_schemas(&dataset, &json_options).into())
+        })
+    })?
+}
+
 #[pyfunction]
 #[pyo3(signature = (data_dir, stores_dir, max_workers=None))]
 pub fn validate_logo_files(
@@ -134,10 +618,12 @@ pub fn validate_logo_files(
     data_dir: &str,
     stores_dir: &str,
     max_workers: Option<usize>,
-) -> ValidationResult {
+) -> PyResult<ValidationResult> {
     let data_dir = PathBuf::from(data_dir);
     let stores_dir = PathBuf::from(stores_dir);
     let schemas_dir = PathBuf::from("schemas");
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
 
     py.allow_threads(|| {
         with_thread_pool(max_workers, || {
@@ -147,26 +633,29 @@ pub fn validate_logo_files(
             use rayon::prelude::*;
             let results: Vec<core::ValidationResult> = dataset.logo_entries
                 .par_iter()
-                .map(|(path, filename, bytes, logo_name)| {
+                .map(|(path, filename, bytes, logo_name, context)| {
                     if bytes.is_empty() {
                         let mut r = core::ValidationResult::default();
                         r.add(core::ValidationError::error(
-                            "Logo",
+                            core::codes::LOGO,
                             "Logo file not found",
                             Some(path.clone()),
                         ));
                         r
                     } else {
-                        core::validators::validate_logo(bytes, filename, logo_name.as_deref(), Some(path))
+                        core::validators::validate_logo_in_context(
+                            bytes,
+                            filename,
+                            logo_name.as_deref(),
+                            Some(path),
+                            *context,
+                            &core::LogoOptions::default(),
+                        )
                     }
                 })
                 .collect();
 
-            let mut result = core::ValidationResult::default();
-            for r in results {
-                result.merge_from(&r);
-            }
-            result.into()
+            core::ValidationResult::merge_all(results).into()
         })
     })
 }
@@ -178,10 +667,12 @@ pub fn validate_folder_names(
     data_dir: &str,
     stores_dir: &str,
     max_workers: Option<usize>,
-) -> ValidationResult {
+) -> PyResult<ValidationResult> {
     let data_dir = PathBuf::from(data_dir);
     let stores_dir = PathBuf::from(stores_dir);
     let schemas_dir = PathBuf::from("schemas");
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
 
     py.allow_threads(|| {
         with_thread_pool(max_workers, || {
@@ -196,11 +687,39 @@ pub fn validate_folder_names(
                 })
                 .collect();
 
-            let mut result = core::ValidationResult::default();
-            for r in results {
-                result.merge_from(&r);
-            }
-            result.into()
+            core::ValidationResult::merge_all(results).into()
         })
     })
 }
+
+/// Return the validation tasks for a dataset as a JSON string, without running them.
+/// When `output_path` is given the JSON is also written to that file.
+#[pyfunction]
+#[pyo3(signature = (data_dir, stores_dir, output_path=None, schemas_dir=None))]
+pub fn dump_tasks(
+    py: Python<'_>,
+    data_dir: &str,
+    stores_dir: &str,
+    output_path: Option<&str>,
+    schemas_dir: Option<&str>,
+) -> PyResult<String> {
+    let data_dir = PathBuf::from(data_dir);
+    let stores_dir = PathBuf::from(stores_dir);
+    let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+    ensure_dir(&schemas_dir, "Schemas")?;
+
+    let json = py.allow_threads(|| {
+        let dataset = core::DataSet::from_directories(&data_dir, &stores_dir, &schemas_dir);
+        serde_json::to_string_pretty(&dataset.task_list())
+    })
+    .map_err(|e| PyRuntimeError::new_err(format!("Failed to serialize tasks: {}", e)))?;
+
+    if let Some(path) = output_path {
+        std::fs::write(path, &json)
+            .map_err(|e| PyIOError::new_err(format!("Failed to write '{}': {}", path, e)))?;
+    }
+
+    Ok(json)
+}
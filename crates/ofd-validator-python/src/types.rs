@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
@@ -19,6 +21,15 @@ impl From<core::ValidationLevel> for ValidationLevel {
     }
 }
 
+impl From<&ValidationLevel> for core::ValidationLevel {
+    fn from(level: &ValidationLevel) -> Self {
+        match level {
+            ValidationLevel::Error => core::ValidationLevel::Error,
+            ValidationLevel::Warning => core::ValidationLevel::Warning,
+        }
+    }
+}
+
 impl std::fmt::Display for ValidationLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -50,6 +61,12 @@ impl ValidationLevel {
     }
 }
 
+/// Every category the validator can emit, as `(code, description)` pairs.
+#[pyfunction]
+pub fn list_codes() -> Vec<(&'static str, &'static str)> {
+    core::codes::ALL.to_vec()
+}
+
 #[pyclass]
 #[derive(Clone, Debug)]
 pub struct ValidationError {
@@ -61,6 +78,11 @@ pub struct ValidationError {
     pub message: String,
     #[pyo3(get)]
     pub path: Option<String>,
+    /// 1-based line of the offending value in `path`, for JSON schema failures
+    #[pyo3(get)]
+    pub line: Option<usize>,
+    #[pyo3(get)]
+    pub column: Option<usize>,
 }
 
 impl From<core::ValidationError> for ValidationError {
@@ -70,16 +92,32 @@ impl From<core::ValidationError> for ValidationError {
             category: e.category,
             message: e.message,
             path: e.path,
+            line: e.line,
+            column: e.column,
         }
     }
 }
 
+impl From<&ValidationError> for core::ValidationError {
+    fn from(e: &ValidationError) -> Self {
+        core::ValidationError::new((&e.level).into(), e.category.clone(), e.message.clone(), e.path.clone())
+            .with_location(e.line.zip(e.column))
+    }
+}
+
 #[pymethods]
 impl ValidationError {
     #[new]
-    #[pyo3(signature = (level, category, message, path=None))]
-    fn new(level: ValidationLevel, category: String, message: String, path: Option<String>) -> Self {
-        Self { level, category, message, path }
+    #[pyo3(signature = (level, category, message, path=None, line=None, column=None))]
+    fn new(
+        level: ValidationLevel,
+        category: String,
+        message: String,
+        path: Option<String>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
+        Self { level, category, message, path, line, column }
     }
 
     fn __str__(&self) -> String {
@@ -100,6 +138,8 @@ impl ValidationError {
         dict.set_item("category", &self.category)?;
         dict.set_item("message", &self.message)?;
         dict.set_item("path", &self.path)?;
+        dict.set_item("line", self.line)?;
+        dict.set_item("column", self.column)?;
         Ok(dict)
     }
 }
@@ -108,12 +148,14 @@ impl ValidationError {
 #[derive(Clone, Debug, Default)]
 pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
+    pub passing: BTreeMap<String, Vec<String>>,
 }
 
 impl From<core::ValidationResult> for ValidationResult {
     fn from(r: core::ValidationResult) -> Self {
         Self {
             errors: r.errors.into_iter().map(|e| e.into()).collect(),
+            passing: r.passing,
         }
     }
 }
@@ -130,6 +172,19 @@ impl ValidationResult {
     pub fn is_valid_check(&self) -> bool {
         !self.errors.iter().any(|e| e.level == ValidationLevel::Error)
     }
+
+    /// The issues as a core result, for reusing core helpers (`passing` is not carried over).
+    fn to_core(&self) -> core::ValidationResult {
+        core::ValidationResult {
+            errors: self.errors.iter().map(|e| e.into()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Error/warning/per-category counts in a single pass (see `core::ValidationResult::summary`).
+    pub fn summarize(&self) -> core::Summary {
+        self.to_core().summary()
+    }
 }
 
 #[pymethods]
@@ -145,6 +200,18 @@ impl ValidationResult {
 
     fn merge(&mut self, other: &ValidationResult) {
         self.errors.extend(other.errors.iter().cloned());
+        for (validator, paths) in &other.passing {
+            self.passing.entry(validator.clone()).or_default().extend(paths.iter().cloned());
+        }
+    }
+
+    /// Rewrite error categories using `mapping` (old label -> new label).
+    fn remap_categories(&mut self, mapping: HashMap<String, String>) {
+        for error in &mut self.errors {
+            if let Some(new_category) = mapping.get(&error.category) {
+                error.category = new_category.clone();
+            }
+        }
     }
 
     #[getter]
@@ -152,6 +219,12 @@ impl ValidationResult {
         self.errors.clone()
     }
 
+    /// Paths that passed each validator (only filled when `include_passing=True`).
+    #[getter]
+    fn passing(&self) -> BTreeMap<String, Vec<String>> {
+        self.passing.clone()
+    }
+
     #[getter]
     fn is_valid(&self) -> bool {
         self.is_valid_check()
@@ -167,27 +240,85 @@ impl ValidationResult {
         self.count_warnings()
     }
 
+    /// True when no error (warnings are allowed) has the given category, one of the codes
+    /// listed by `list_codes()`.
+    fn category_is_valid(&self, category: &str) -> bool {
+        !self
+            .errors
+            .iter()
+            .any(|e| e.level == ValidationLevel::Error && e.category == category)
+    }
+
+    /// True when an error (not a warning) has the category `code`, e.g. "GTIN".
+    fn has_error_with_code(&self, code: &str) -> bool {
+        !self.category_is_valid(code)
+    }
+
+    /// Every issue (errors and warnings) reported against exactly `path`, in order.
+    fn errors_for_path(&self, path: &str) -> Vec<ValidationError> {
+        self.errors.iter().filter(|e| e.path.as_deref() == Some(path)).cloned().collect()
+    }
+
+    /// Counts computed in one pass: {"errors", "warnings", "total", "categories"},
+    /// where "categories" is a list of (category, count) tuples sorted by category.
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let summary = self.summarize();
+        let dict = PyDict::new(py);
+        dict.set_item("errors", summary.errors)?;
+        dict.set_item("warnings", summary.warnings)?;
+        dict.set_item("total", summary.total)?;
+        dict.set_item("categories", summary.categories)?;
+        Ok(dict)
+    }
+
     fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let dict = PyDict::new(py);
+        let summary = self.summarize();
 
         let error_dicts: Vec<Bound<'py, PyDict>> = self.errors
             .iter()
             .map(|e| e.to_dict(py))
             .collect::<PyResult<_>>()?;
         dict.set_item("errors", error_dicts)?;
-        dict.set_item("error_count", self.count_errors())?;
-        dict.set_item("warning_count", self.count_warnings())?;
-        dict.set_item("is_valid", self.is_valid_check())?;
+        dict.set_item("error_count", summary.errors)?;
+        dict.set_item("warning_count", summary.warnings)?;
+        dict.set_item("is_valid", summary.errors == 0)?;
+        if !self.passing.is_empty() {
+            dict.set_item("passing", &self.passing)?;
+        }
 
         Ok(dict)
     }
 
+    /// The issues as CSV with columns `level,category,code,path,message`,
+    /// for filtering in a spreadsheet.
+    fn to_csv(&self) -> String {
+        core::report::to_csv(&self.to_core())
+    }
+
+    /// The result as JSON with issues grouped by category:
+    /// {"is_valid", "error_count", "warning_count", "by_category": {<code>: [...], ...}}.
+    fn to_json_grouped(&self) -> String {
+        core::report::to_json_grouped(&self.to_core())
+    }
+
+    /// At most one issue per distinct path: its first error, or its first warning if
+    /// it has no errors. Handy for a "headline issue per file" triage view.
+    fn first_error_per_file(&self) -> Vec<ValidationError> {
+        self.to_core()
+            .first_error_per_file()
+            .into_iter()
+            .map(|e| e.clone().into())
+            .collect()
+    }
+
     fn __str__(&self) -> String {
+        let summary = self.summarize();
         format!(
             "ValidationResult(errors={}, warnings={}, valid={})",
-            self.count_errors(),
-            self.count_warnings(),
-            self.is_valid_check()
+            summary.errors,
+            summary.warnings,
+            summary.errors == 0
         )
     }
 
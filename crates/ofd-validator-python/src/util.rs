@@ -1,9 +1,50 @@
-use std::io::IsTerminal;
+use std::io::{ErrorKind, IsTerminal};
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
 
-/// Print a colored progress step to stderr.
+use pyo3::exceptions::{
+    PyFileNotFoundError, PyNotADirectoryError, PyOSError, PyPermissionError, PyValueError,
+};
+use pyo3::prelude::*;
+
+const COLOR_AUTO: u8 = 0;
+const COLOR_ALWAYS: u8 = 1;
+const COLOR_NEVER: u8 = 2;
+
+/// Process-wide color mode for progress output, set via `set_color`.
+static COLOR_MODE: AtomicU8 = AtomicU8::new(COLOR_AUTO);
+
+/// Whether progress output should use ANSI colors under the current color mode.
+fn use_color() -> bool {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        COLOR_ALWAYS => true,
+        COLOR_NEVER => false,
+        _ => std::io::stderr().is_terminal(),
+    }
+}
+
+/// Choose when progress output is colored: "always", "never", or "auto"
+/// (the default, colored only when stderr is a terminal).
+#[pyfunction]
+pub fn set_color(mode: &str) -> PyResult<()> {
+    let value = match mode {
+        "auto" => COLOR_AUTO,
+        "always" => COLOR_ALWAYS,
+        "never" => COLOR_NEVER,
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Invalid color mode '{}': expected 'always', 'never' or 'auto'",
+                other
+            )))
+        }
+    };
+    COLOR_MODE.store(value, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Print a progress step to stderr, colored according to the color mode.
 pub fn log_step(label: &str, count: Option<usize>) {
-    let is_tty = std::io::stderr().is_terminal();
-    if is_tty {
+    if use_color() {
         match count {
             Some(n) => eprintln!("  \x1b[36m->\x1b[0m {} \x1b[2m({} items)\x1b[0m", label, n),
             None => eprintln!("  \x1b[36m->\x1b[0m {}", label),
@@ -15,3 +56,20 @@ pub fn log_step(label: &str, count: Option<usize>) {
         }
     }
 }
+
+/// Raise a Python exception when `path` can't be listed as a directory, rather than
+/// letting the walk find nothing and report a misleadingly clean result.
+pub fn ensure_dir(path: &Path, what: &str) -> PyResult<()> {
+    match std::fs::read_dir(path) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let msg = format!("{} directory '{}': {}", what, path.display(), e);
+            Err(match e.kind() {
+                ErrorKind::NotFound => PyFileNotFoundError::new_err(msg),
+                ErrorKind::PermissionDenied => PyPermissionError::new_err(msg),
+                ErrorKind::NotADirectory => PyNotADirectoryError::new_err(msg),
+                _ => PyOSError::new_err(msg),
+            })
+        }
+    }
+}
@@ -1,18 +1,21 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use ofd_validator_core as core;
 
 use crate::types::ValidationResult;
+use crate::util::ensure_dir;
 
 /// Validate GTIN/EAN fields in all sizes.json files.
 #[pyfunction]
 #[pyo3(signature = (data_dir))]
-pub fn validate_gtin_ean(data_dir: &str) -> ValidationResult {
+pub fn validate_gtin_ean(data_dir: &str) -> PyResult<ValidationResult> {
     use walkdir::WalkDir;
 
-    let data_path = PathBuf::from(data_dir);
+    let data_path = core::util::normalize_dir(Path::new(data_dir));
+    ensure_dir(&data_path, "Data")?;
     let mut sizes_entries = Vec::new();
 
     for entry in WalkDir::new(&data_path).into_iter().filter_map(|e| e.ok()) {
@@ -29,32 +32,27 @@ pub fn validate_gtin_ean(data_dir: &str) -> ValidationResult {
         .map(|(p, v)| (p.as_str(), v))
         .collect();
 
-    core::validators::validate_gtin_ean(&refs).into()
+    Ok(core::validators::validate_gtin_ean(&refs).into())
 }
 
 /// Validate store IDs referenced in purchase links.
 #[pyfunction]
 #[pyo3(signature = (data_dir, stores_dir))]
-pub fn validate_store_ids(data_dir: &str, stores_dir: &str) -> ValidationResult {
+pub fn validate_store_ids(data_dir: &str, stores_dir: &str) -> PyResult<ValidationResult> {
     use std::collections::HashSet;
     use walkdir::WalkDir;
 
-    let stores_path = PathBuf::from(stores_dir);
-    let data_path = PathBuf::from(data_dir);
+    let stores_path = core::util::normalize_dir(Path::new(stores_dir));
+    let data_path = core::util::normalize_dir(Path::new(data_dir));
+    ensure_dir(&data_path, "Data")?;
+    ensure_dir(&stores_path, "Stores")?;
 
     // Collect valid store IDs
     let mut valid_store_ids = HashSet::new();
-    if let Ok(entries) = std::fs::read_dir(&stores_path) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let store_dir = entry.path();
-            if !store_dir.is_dir() {
-                continue;
-            }
-            let store_file = store_dir.join("store.json");
-            if let Some(data) = core::util::load_json(&store_file) {
-                if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
-                    valid_store_ids.insert(id.to_string());
-                }
+    for store_dir in core::util::store_dirs(&stores_path) {
+        if let Some(data) = core::util::load_json(&store_dir.join("store.json")) {
+            if let Some(id) = data.get("id").and_then(|v| v.as_str()) {
+                valid_store_ids.insert(id.to_string());
             }
         }
     }
@@ -75,30 +73,38 @@ pub fn validate_store_ids(data_dir: &str, stores_dir: &str) -> ValidationResult
         .map(|(p, v)| (p.as_str(), v))
         .collect();
 
-    core::validators::validate_store_ids(&valid_store_ids, &refs).into()
+    Ok(core::validators::validate_store_ids(&valid_store_ids, &refs).into())
 }
 
 /// Validate required files exist at each hierarchy level.
 #[pyfunction]
 #[pyo3(signature = (data_dir, stores_dir))]
-pub fn validate_required_files(data_dir: &str, stores_dir: &str) -> ValidationResult {
-    let manifest = core::validators::missing_files::build_file_manifest(
-        &PathBuf::from(data_dir),
-        &PathBuf::from(stores_dir),
-    );
-    core::validators::validate_required_files(&manifest).into()
+pub fn validate_required_files(data_dir: &str, stores_dir: &str) -> PyResult<ValidationResult> {
+    let data_dir = PathBuf::from(data_dir);
+    let stores_dir = PathBuf::from(stores_dir);
+    ensure_dir(&data_dir, "Data")?;
+    ensure_dir(&stores_dir, "Stores")?;
+
+    let manifest = core::validators::missing_files::build_file_manifest(&data_dir, &stores_dir);
+    Ok(core::validators::validate_required_files(&manifest).into())
 }
 
 /// Validate a single logo file.
 #[pyfunction]
-#[pyo3(signature = (logo_path, logo_name=None))]
-pub fn validate_logo_file(logo_path: &str, logo_name: Option<&str>) -> ValidationResult {
+#[pyo3(signature = (logo_path, logo_name=None, require_transparency=false, allowed_sizes=None, warn_high_bit_depth=false))]
+pub fn validate_logo_file(
+    logo_path: &str,
+    logo_name: Option<&str>,
+    require_transparency: bool,
+    allowed_sizes: Option<Vec<u32>>,
+    warn_high_bit_depth: bool,
+) -> ValidationResult {
     let path = PathBuf::from(logo_path);
 
     if !path.exists() {
         let mut result = core::ValidationResult::default();
         result.add(core::ValidationError::error(
-            "Logo",
+            core::codes::LOGO,
             "Logo file not found",
             Some(logo_path.to_string()),
         ));
@@ -111,12 +117,25 @@ pub fn validate_logo_file(logo_path: &str, logo_name: Option<&str>) -> Validatio
 
     match std::fs::read(&path) {
         Ok(bytes) => {
-            core::validators::validate_logo(&bytes, &filename, logo_name, Some(logo_path)).into()
+            let options = core::LogoOptions {
+                require_transparency,
+                allowed_sizes,
+                warn_high_bit_depth,
+                ..Default::default()
+            };
+            core::validators::validate_logo_with_options(
+                &bytes,
+                &filename,
+                logo_name,
+                Some(logo_path),
+                &options,
+            )
+            .into()
         }
         Err(e) => {
             let mut result = core::ValidationResult::default();
             result.add(core::ValidationError::error(
-                "Logo",
+                core::codes::LOGO,
                 format!("Failed to read logo file: {}", e),
                 Some(logo_path.to_string()),
             ));
@@ -135,7 +154,7 @@ pub fn validate_folder_name(folder_path: &str, json_file: &str, json_key: &str)
     if !json_path.exists() {
         let mut result = core::ValidationResult::default();
         result.add(core::ValidationError::error(
-            "Folder",
+            core::codes::FOLDER,
             format!("Missing {}", json_file),
             Some(folder_path.to_string()),
         ));
@@ -153,3 +172,72 @@ pub fn validate_folder_name(folder_path: &str, json_file: &str, json_key: &str)
 
     core::validators::validate_folder_name(&actual_name, &data, json_key, Some(folder_path)).into()
 }
+
+/// Validate a JSON string against a named schema without touching the data directory.
+/// Invalid JSON raises `ValueError`; a missing schemas directory raises `FileNotFoundError`.
+#[pyfunction]
+#[pyo3(signature = (content, schema_name, schemas_dir=None, file_path=None))]
+pub fn validate_json_content(
+    content: &str,
+    schema_name: &str,
+    schemas_dir: Option<&str>,
+    file_path: Option<&str>,
+) -> PyResult<ValidationResult> {
+    let data: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
+
+    let schemas_dir = PathBuf::from(schemas_dir.unwrap_or("schemas"));
+    ensure_dir(&schemas_dir, "Schemas")?;
+
+    let cache = core::SchemaCache::from_directory(&schemas_dir);
+    Ok(core::validators::validate_json_source(
+        &data,
+        content,
+        schema_name,
+        &cache,
+        file_path,
+        &core::JsonOptions::default(),
+    )
+    .into())
+}
+
+/// Validate a JSON string against an inline schema (also a JSON string), without a
+/// schemas directory. `$ref`s can only point inside the schema. Invalid JSON raises
+/// `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (content, schema, file_path=None))]
+pub fn validate_json_against_schema(
+    content: &str,
+    schema: &str,
+    file_path: Option<&str>,
+) -> PyResult<ValidationResult> {
+    let data: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
+    let schema: serde_json::Value = serde_json::from_str(schema)
+        .map_err(|e| PyValueError::new_err(format!("Invalid schema JSON: {}", e)))?;
+
+    Ok(core::validators::validate_value_against_schema_with_options(
+        &data,
+        &schema,
+        file_path,
+        &core::JsonOptions::default(),
+    )
+    .into())
+}
+
+/// Validate a folder name against the JSON content that would live inside it, without
+/// touching the filesystem. Mirrors the JS `validate_folder_name_content`.
+/// Invalid JSON raises `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (folder_name, json_content, json_key, file_path=None))]
+pub fn validate_folder_name_content(
+    folder_name: &str,
+    json_content: &str,
+    json_key: &str,
+    file_path: Option<&str>,
+) -> PyResult<ValidationResult> {
+    let data: serde_json::Value = serde_json::from_str(json_content)
+        .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))?;
+
+    Ok(core::validators::validate_folder_name(folder_name, &data, json_key, file_path).into())
+}